@@ -0,0 +1,12 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/solar.proto");
+        let descriptors = protox::compile(["proto/solar.proto"], ["proto"]).expect("proto/solar.proto");
+        tonic_prost_build::configure()
+            .build_client(false)
+            .compile_fds(descriptors)
+            .expect("generating the gRPC service");
+    }
+}
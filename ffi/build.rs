@@ -0,0 +1,11 @@
+//! Regenerates `include/solar_rs.h` from the `extern "C"` items whenever they change.
+
+fn main() {
+    let dir = std::env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR");
+    let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", dir)).expect("cbindgen.toml");
+    cbindgen::generate_with_config(&dir, config)
+        .expect("generate the C header")
+        .write_to_file(format!("{}/include/solar_rs.h", dir));
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+}
@@ -0,0 +1,84 @@
+#ifndef SOLAR_RS_H
+#define SOLAR_RS_H
+
+/* Generated by cbindgen from ffi/src/lib.rs; edit that, not this. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum SolarStatus {
+  SOLAR_STATUS_OK = 0,
+  // A null pointer, an unknown body or source, or a time out of range.
+  SOLAR_STATUS_BAD_ARGUMENT = 1,
+  // The source couldn't answer: a failed query, a body it doesn't cover, an open orbit.
+  SOLAR_STATUS_FAILED = 2,
+} SolarStatus;
+
+// Which source [`solar_state`] asks. Passed as a plain integer, so that any other value
+// is a [`SolarStatus::BadArgument`] rather than an invalid enum.
+typedef enum SolarSource {
+  // JPL Horizons, through the response cache; fetches on a miss.
+  SOLAR_SOURCE_HORIZONS = 0,
+  // VSOP87, computed locally (planets only).
+  SOLAR_SOURCE_VSOP87 = 1,
+  // Built-in mean orbital elements, computed locally (planets and Pluto).
+  SOLAR_SOURCE_KEPLERIAN = 2,
+} SolarSource;
+
+typedef struct SolarVec3 {
+  double x;
+  double y;
+  double z;
+} SolarVec3;
+
+typedef struct SolarState {
+  struct SolarVec3 pos_au;
+  struct SolarVec3 vel_au_d;
+} SolarState;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// State of the body `name` at `unix_seconds` from `source`, one of the [`SolarSource`]
+// values, into `*out`.
+//
+// # Safety
+// `name` is null or a valid NUL-terminated string; `out` is null or valid for writes.
+enum SolarStatus solar_state(const char *name,
+                             int64_t unix_seconds,
+                             uint32_t source,
+                             struct SolarState *out);
+
+// `*state` moved on by `days` (negative for back) along its two-body orbit about the Sun,
+// into `*out`; fails for open orbits.
+//
+// # Safety
+// `state` is null or valid for reads; `out` is null or valid for writes. They may alias.
+enum SolarStatus solar_propagate(const struct SolarState *state,
+                                 double days,
+                                 struct SolarState *out);
+
+// Where `*target` appears from `*observer` (both heliocentric ecliptic, AU): J2000 right
+// ascension and declination in degrees, and the distance in AU. Geometric, without light time
+// or aberration.
+//
+// # Safety
+// `target` and `observer` are null or valid for reads; the outputs are null or valid for writes.
+enum SolarStatus solar_project(const struct SolarVec3 *target,
+                               const struct SolarVec3 *observer,
+                               double *ra_deg,
+                               double *dec_deg,
+                               double *distance_au);
+
+// Why the last failing call on this thread failed; empty if none has. Owned by the library
+// and valid until the thread's next failing call.
+const char *solar_last_error(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SOLAR_RS_H */
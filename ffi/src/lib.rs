@@ -0,0 +1,237 @@
+//! C ABI over the solar-rs data layer, for planetarium software in C or C++: state vectors from
+//! Horizons (through the app's response cache) or the local ephemerides, two-body propagation,
+//! and projection to RA/Dec. `include/solar_rs.h` is generated from this file by cbindgen.
+//!
+//! Vectors are heliocentric on the J2000 ecliptic in AU and AU/day; times are Unix seconds UTC.
+//! Every function returns a [`SolarStatus`]; on failure [`solar_last_error`] says why.
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::sync::{Mutex, OnceLock};
+
+use chrono::{DateTime, Utc};
+
+use solar_rs::config::{load_config, ConfigPaths};
+use solar_rs::frames::{ecliptic_to_equatorial, ra_dec_deg};
+use solar_rs::horizons::EphemerisClient;
+use solar_rs::names;
+use solar_rs::orbital_mechanics;
+use solar_rs::physics::GM_SUN_AU3_D2;
+use solar_rs::sources::{self, Source};
+use solar_rs::types::{all_bodies, BodyMeta, Vec3};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SolarVec3 {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SolarState {
+    pub pos_au: SolarVec3,
+    pub vel_au_d: SolarVec3,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolarStatus {
+    Ok = 0,
+    /// A null pointer, an unknown body or source, or a time out of range.
+    BadArgument = 1,
+    /// The source couldn't answer: a failed query, a body it doesn't cover, an open orbit.
+    Failed = 2,
+}
+
+/// Which source [`solar_state`] asks. Passed as a plain integer, so that any other value
+/// is a [`SolarStatus::BadArgument`] rather than an invalid enum.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolarSource {
+    /// JPL Horizons, through the response cache; fetches on a miss.
+    Horizons = 0,
+    /// VSOP87, computed locally (planets only).
+    Vsop87 = 1,
+    /// Built-in mean orbital elements, computed locally (planets and Pluto).
+    Keplerian = 2,
+}
+
+impl SolarSource {
+    fn from_raw(raw: u32) -> Option<Self> {
+        [SolarSource::Horizons, SolarSource::Vsop87, SolarSource::Keplerian].into_iter().find(|s| *s as u32 == raw)
+    }
+}
+
+impl From<Vec3> for SolarVec3 {
+    fn from(v: Vec3) -> Self {
+        SolarVec3 { x: v.x, y: v.y, z: v.z }
+    }
+}
+
+impl From<SolarVec3> for Vec3 {
+    fn from(v: SolarVec3) -> Self {
+        Vec3 { x: v.x, y: v.y, z: v.z }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<CString> = RefCell::new(CString::default());
+}
+
+fn fail(status: SolarStatus, message: impl Into<String>) -> SolarStatus {
+    let message = CString::new(message.into().replace('\0', " ")).unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = message);
+    status
+}
+
+/// One client and runtime for the process, so repeated calls share the loaded cache.
+fn client() -> anyhow::Result<&'static Mutex<(tokio::runtime::Runtime, EphemerisClient)>> {
+    static CLIENT: OnceLock<Mutex<(tokio::runtime::Runtime, EphemerisClient)>> = OnceLock::new();
+    if let Some(c) = CLIENT.get() {
+        return Ok(c);
+    }
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+    let config = load_config(ConfigPaths::resolve(None).as_ref(), None)?;
+    Ok(CLIENT.get_or_init(|| Mutex::new((runtime, EphemerisClient::new(config)))))
+}
+
+/// The body `name` (any of its names, or its Horizons id) and the time `unix_seconds`.
+///
+/// # Safety
+/// `name` is null or a valid NUL-terminated string.
+unsafe fn arguments(name: *const c_char, unix_seconds: i64) -> Result<(&'static BodyMeta, DateTime<Utc>), SolarStatus> {
+    if name.is_null() {
+        return Err(fail(SolarStatus::BadArgument, "body name is null"));
+    }
+    let name = CStr::from_ptr(name).to_string_lossy();
+    let meta = all_bodies()
+        .find(|m| names::matches(m.name, m.id, &name))
+        .ok_or_else(|| fail(SolarStatus::BadArgument, format!("unknown body {:?}", name)))?;
+    let utc = DateTime::from_timestamp(unix_seconds, 0).ok_or_else(|| fail(SolarStatus::BadArgument, "time out of range"))?;
+    Ok((meta, utc))
+}
+
+/// State of the body `name` at `unix_seconds` from `source`, one of the [`SolarSource`]
+/// values, into `*out`.
+///
+/// # Safety
+/// `name` is null or a valid NUL-terminated string; `out` is null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn solar_state(name: *const c_char, unix_seconds: i64, source: u32, out: *mut SolarState) -> SolarStatus {
+    if out.is_null() {
+        return fail(SolarStatus::BadArgument, "out is null");
+    }
+    let (meta, utc) = match arguments(name, unix_seconds) {
+        Ok(args) => args,
+        Err(status) => return status,
+    };
+    let Some(source) = SolarSource::from_raw(source) else {
+        return fail(SolarStatus::BadArgument, format!("unknown source {}", source));
+    };
+    let result = match source {
+        SolarSource::Horizons => client().and_then(|c| {
+            let mut guard = c.lock().unwrap_or_else(|e| e.into_inner());
+            let (runtime, client) = &mut *guard;
+            let state = runtime.block_on(client.state_at(meta.id, utc))?;
+            client.save()?;
+            Ok(state)
+        }),
+        SolarSource::Vsop87 => sources::local_state(Source::Vsop87, meta.name, meta.id, None, utc),
+        SolarSource::Keplerian => sources::local_state(Source::Keplerian, meta.name, meta.id, None, utc),
+    };
+    match result {
+        Ok((p, v)) => {
+            *out = SolarState { pos_au: p.into(), vel_au_d: v.into() };
+            SolarStatus::Ok
+        }
+        Err(e) => fail(SolarStatus::Failed, format!("{}: {:#}", meta.name, e)),
+    }
+}
+
+/// `*state` moved on by `days` (negative for back) along its two-body orbit about the Sun,
+/// into `*out`; fails for open orbits.
+///
+/// # Safety
+/// `state` is null or valid for reads; `out` is null or valid for writes. They may alias.
+#[no_mangle]
+pub unsafe extern "C" fn solar_propagate(state: *const SolarState, days: f64, out: *mut SolarState) -> SolarStatus {
+    if state.is_null() || out.is_null() {
+        return fail(SolarStatus::BadArgument, "state or out is null");
+    }
+    let s = *state;
+    match orbital_mechanics::propagate(s.pos_au.into(), s.vel_au_d.into(), days, GM_SUN_AU3_D2) {
+        Some((p, v)) => {
+            *out = SolarState { pos_au: p.into(), vel_au_d: v.into() };
+            SolarStatus::Ok
+        }
+        None => fail(SolarStatus::Failed, "not a closed orbit about the Sun"),
+    }
+}
+
+/// Where `*target` appears from `*observer` (both heliocentric ecliptic, AU): J2000 right
+/// ascension and declination in degrees, and the distance in AU. Geometric, without light time
+/// or aberration.
+///
+/// # Safety
+/// `target` and `observer` are null or valid for reads; the outputs are null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn solar_project(
+    target: *const SolarVec3,
+    observer: *const SolarVec3,
+    ra_deg: *mut f64,
+    dec_deg: *mut f64,
+    distance_au: *mut f64,
+) -> SolarStatus {
+    if target.is_null() || observer.is_null() || ra_deg.is_null() || dec_deg.is_null() || distance_au.is_null() {
+        return fail(SolarStatus::BadArgument, "null argument");
+    }
+    let d = Vec3::from(*target) - Vec3::from(*observer);
+    if d.norm() == 0.0 {
+        return fail(SolarStatus::BadArgument, "target and observer coincide");
+    }
+    let (ra, dec) = ra_dec_deg(ecliptic_to_equatorial(d));
+    (*ra_deg, *dec_deg, *distance_au) = (ra, dec, d.norm());
+    SolarStatus::Ok
+}
+
+/// Why the last failing call on this thread failed; empty if none has. Owned by the library
+/// and valid until the thread's next failing call.
+#[no_mangle]
+pub extern "C" fn solar_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ptr())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn propagate_and_project_through_the_abi() {
+        let earth = SolarState { pos_au: SolarVec3 { x: 1.0, y: 0.0, z: 0.0 }, vel_au_d: SolarVec3 { x: 0.0, y: 0.017_202_098_95, z: 0.0 } };
+        let mut later = SolarState::default();
+        assert_eq!(unsafe { solar_propagate(&earth, 365.256_9 / 2.0, &mut later) }, SolarStatus::Ok);
+        assert!((later.pos_au.x + 1.0).abs() < 1e-4);
+
+        // The Sun from Earth at (1, 0, 0) lies on the equinox side opposite: RA 180°, Dec 0°
+        let (mut ra, mut dec, mut dist) = (0.0, 0.0, 0.0);
+        let status = unsafe { solar_project(&SolarVec3::default(), &earth.pos_au, &mut ra, &mut dec, &mut dist) };
+        assert_eq!(status, SolarStatus::Ok);
+        assert!((ra - 180.0).abs() < 1e-9 && dec.abs() < 1e-9 && (dist - 1.0).abs() < 1e-12);
+
+        let name = CString::new("Zork").unwrap();
+        let status = unsafe { solar_state(name.as_ptr(), 0, SolarSource::Vsop87 as u32, &mut later) };
+        assert_eq!(status, SolarStatus::BadArgument);
+        let message = unsafe { CStr::from_ptr(solar_last_error()) };
+        assert_eq!(message.to_str().unwrap(), "unknown body \"Zork\"");
+
+        let name = CString::new("Mars").unwrap();
+        let status = unsafe { solar_state(name.as_ptr(), 0, 3, &mut later) };
+        assert_eq!(status, SolarStatus::BadArgument);
+        let message = unsafe { CStr::from_ptr(solar_last_error()) };
+        assert_eq!(message.to_str().unwrap(), "unknown source 3");
+        let status = unsafe { solar_state(name.as_ptr(), 0, SolarSource::Keplerian as u32, &mut later) };
+        assert_eq!(status, SolarStatus::Ok);
+    }
+}
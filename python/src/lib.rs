@@ -0,0 +1,87 @@
+//! Python bindings for notebooks: Horizons state vectors through solar-rs's response cache, and
+//! two-body propagation about the Sun. Built with `maturin develop` from this directory and
+//! imported as `solar_rs`.
+
+use std::collections::BTreeMap;
+
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+
+use solar_rs::config::{load_config, ConfigPaths};
+use solar_rs::events::parse_epoch;
+use solar_rs::horizons::EphemerisClient;
+use solar_rs::names;
+use solar_rs::orbital_mechanics;
+use solar_rs::physics::GM_SUN_AU3_D2;
+use solar_rs::types::{all_bodies, BodyClass, BodyId, Vec3, BODIES};
+
+type State = ([f64; 3], [f64; 3]);
+
+fn vec3([x, y, z]: [f64; 3]) -> Vec3 {
+    Vec3 { x, y, z }
+}
+
+fn array(v: Vec3) -> [f64; 3] {
+    [v.x, v.y, v.z]
+}
+
+/// Heliocentric ecliptic J2000 state vectors, `{name: (pos_au, vel_au_per_day)}`, at `utc` (an
+/// ISO date or time, default now) for `bodies` (any names or Horizons ids, default the planets
+/// and the Moon). Vectors the app has already fetched are reused; the rest are fetched from
+/// Horizons and added to the shared cache.
+#[pyfunction]
+#[pyo3(signature = (bodies=None, utc=None))]
+fn fetch_states(py: Python<'_>, bodies: Option<Vec<String>>, utc: Option<String>) -> PyResult<BTreeMap<String, State>> {
+    let utc = match utc {
+        Some(text) => parse_epoch(&text).ok_or_else(|| PyValueError::new_err(format!("not a UTC time: {:?}", text)))?,
+        None => chrono::Utc::now(),
+    };
+    let ids: Vec<(&'static str, BodyId)> = match bodies {
+        Some(queries) => queries
+            .iter()
+            .map(|q| {
+                all_bodies()
+                    .find(|m| names::matches(m.name, m.id, q))
+                    .map(|m| (m.name, m.id))
+                    .ok_or_else(|| PyValueError::new_err(format!("unknown body {:?}", q)))
+            })
+            .collect::<PyResult<_>>()?,
+        None => BODIES
+            .iter()
+            .filter(|m| matches!(m.class, BodyClass::Terrestrial | BodyClass::GasGiant | BodyClass::IceGiant) || m.name == "Moon")
+            .map(|m| (m.name, m.id))
+            .collect(),
+    };
+    let config = load_config(ConfigPaths::resolve(None).as_ref(), None).map_err(|e| PyRuntimeError::new_err(format!("{:#}", e)))?;
+
+    // Released while waiting on the network, so other Python threads keep running
+    py.detach(|| {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+        let mut client = EphemerisClient::new(config);
+        let mut states = BTreeMap::new();
+        for (name, id) in ids {
+            let (p, v) = runtime.block_on(client.state_at(id, utc)).map_err(|e| anyhow::anyhow!("{}: {:#}", name, e))?;
+            states.insert(name.to_string(), (array(p), array(v)));
+        }
+        client.save()?;
+        Ok(states)
+    })
+    .map_err(|e: anyhow::Error| PyRuntimeError::new_err(format!("{:#}", e)))
+}
+
+/// `(pos_au, vel_au_per_day)` moved on by `days` (negative for back) along the two-body orbit
+/// about the Sun; raises for open orbits, which don't propagate.
+#[pyfunction]
+fn propagate(pos_au: [f64; 3], vel_au_d: [f64; 3], days: f64) -> PyResult<State> {
+    orbital_mechanics::propagate(vec3(pos_au), vec3(vel_au_d), days, GM_SUN_AU3_D2)
+        .map(|(p, v)| (array(p), array(v)))
+        .ok_or_else(|| PyValueError::new_err("not a closed orbit about the Sun"))
+}
+
+#[pymodule]
+#[pyo3(name = "solar_rs")]
+fn solar_rs_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(fetch_states, m)?)?;
+    m.add_function(wrap_pyfunction!(propagate, m)?)?;
+    Ok(())
+}
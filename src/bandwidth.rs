@@ -0,0 +1,114 @@
+//! Download accounting for metered links (satellite, cellular): bytes fetched from Horizons
+//! this session and today, the daily total kept on disk across restarts, and the optional
+//! `daily_quota_mb` past which refreshes answer from the response cache and local
+//! ephemerides only until local midnight.
+
+use anyhow::Result;
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::PathBuf,
+    sync::{LazyLock, Mutex},
+};
+
+use crate::config::Config;
+
+/// The process-wide tally; every HTTP client reports into it.
+static USAGE: LazyLock<Mutex<Usage>> = LazyLock::new(|| Mutex::new(Usage::load()));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Usage {
+    pub session_bytes: u64,
+    /// Local date `day_bytes` counts for.
+    pub day: Option<NaiveDate>,
+    pub day_bytes: u64,
+}
+
+/// What is kept on disk; the session count starts from zero.
+#[derive(Debug, Serialize, Deserialize)]
+struct Stored {
+    day: NaiveDate,
+    bytes: u64,
+}
+
+impl Usage {
+    pub fn path() -> PathBuf {
+        dirs::data_dir().unwrap_or_else(std::env::temp_dir).join("solar-rs").join("bandwidth.json")
+    }
+
+    fn load() -> Usage {
+        let stored: Option<Stored> = std::fs::read_to_string(Self::path()).ok().and_then(|t| serde_json::from_str(&t).ok());
+        Usage { session_bytes: 0, day: stored.as_ref().map(|s| s.day), day_bytes: stored.map_or(0, |s| s.bytes) }
+    }
+
+    fn save(&self) -> Result<()> {
+        let Some(day) = self.day else { return Ok(()) };
+        let path = Self::path();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string(&Stored { day, bytes: self.day_bytes })?)?;
+        Ok(())
+    }
+
+    /// Adds a download, starting a new daily count if the date has changed.
+    fn add(&mut self, bytes: u64, today: NaiveDate) {
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.day_bytes = 0;
+        }
+        self.session_bytes += bytes;
+        self.day_bytes += bytes;
+    }
+
+    /// Bytes downloaded on `today`, zero if the stored count is from an earlier day.
+    pub fn bytes_on(&self, today: NaiveDate) -> u64 {
+        if self.day == Some(today) { self.day_bytes } else { 0 }
+    }
+
+    /// Whether the configured daily quota is used up.
+    pub fn quota_reached(&self, config: &Config) -> bool {
+        let today = self.bytes_on(Local::now().date_naive());
+        config.daily_quota_mb.is_some_and(|mb| today as f64 >= mb * 1e6)
+    }
+}
+
+/// Counts a finished download and saves the daily total; if saving fails the count is only
+/// lost across a restart.
+pub fn record(bytes: u64) {
+    let mut usage = USAGE.lock().unwrap();
+    usage.add(bytes, Local::now().date_naive());
+    usage.save().ok();
+}
+
+pub fn current() -> Usage {
+    *USAGE.lock().unwrap()
+}
+
+/// `812 kB`, `3.4 MB`: decimal units, as carriers bill.
+pub fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        b if b < 1_000 => format!("{} B", b),
+        b if b < 1_000_000 => format!("{:.0} kB", b as f64 / 1e3),
+        b if b < 1_000_000_000 => format!("{:.1} MB", b as f64 / 1e6),
+        b => format!("{:.2} GB", b as f64 / 1e9),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn daily_count_rolls_over() {
+        let day = |d: u32| NaiveDate::from_ymd_opt(2026, 10, d).unwrap();
+        let mut usage = Usage::default();
+        usage.add(600_000, day(15));
+        usage.add(600_000, day(15));
+        assert_eq!(usage.bytes_on(day(15)), 1_200_000);
+        usage.add(1_000, day(16));
+        assert_eq!((usage.session_bytes, usage.bytes_on(day(16))), (1_201_000, 1_000));
+        assert_eq!(usage.bytes_on(day(17)), 0);
+        assert_eq!(format_bytes(usage.session_bytes), "1.2 MB");
+    }
+}
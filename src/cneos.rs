@@ -0,0 +1,166 @@
+//! Upcoming near-Earth object flybys from the CNEOS close-approach API (`cad.api`), for the
+//! close-approach panel: every pass within [`MAX_DIST_LD`] lunar distances of Earth over the
+//! next [`DAYS_AHEAD`] days, fetched when the panel first opens and again once a day.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::Deserialize;
+
+use crate::physics::AU_KM;
+
+#[cfg(feature = "net")]
+use crate::bandwidth;
+#[cfg(feature = "net")]
+use crate::horizons::{get_api_text, http_client, Retry};
+#[cfg(feature = "net")]
+use crate::types::{AlertLevel, DataState};
+#[cfg(feature = "net")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "net")]
+use url::Url;
+
+pub const DAYS_AHEAD: u32 = 60;
+pub const MAX_DIST_LD: f64 = 10.0;
+
+/// Mean Earth–Moon distance in AU, the unit close approaches are quoted in.
+pub const LD_AU: f64 = 384_400.0 / AU_KM;
+
+/// Between refreshes, and before another try after a failed one.
+#[cfg(feature = "net")]
+const REFRESH: std::time::Duration = std::time::Duration::from_secs(24 * 3600);
+#[cfg(feature = "net")]
+const RETRY: std::time::Duration = std::time::Duration::from_secs(3600);
+
+#[derive(Debug, Clone)]
+pub struct CloseApproach {
+    /// Primary designation, e.g. "2024 YR4" or "99942".
+    pub designation: String,
+    pub utc: DateTime<Utc>,
+    /// Nominal miss distance from Earth's centre.
+    pub dist_ld: f64,
+    /// Speed relative to Earth at closest approach.
+    pub v_rel_km_s: f64,
+    /// Absolute magnitude, a rough size: H 22 is about 140 m across.
+    pub abs_mag: Option<f64>,
+}
+
+/// The latest list, soonest pass first.
+#[derive(Debug, Clone)]
+pub struct CloseApproaches {
+    pub fetched: DateTime<Utc>,
+    pub passes: Vec<CloseApproach>,
+}
+
+/// `fields` names the columns of `data`, every value a string (or null).
+#[derive(Debug, Deserialize)]
+struct CadResponse {
+    #[serde(default)]
+    fields: Vec<String>,
+    #[serde(default)]
+    data: Vec<Vec<Option<String>>>,
+}
+
+#[cfg(feature = "net")]
+fn cad_url() -> Result<Url> {
+    let mut url = Url::parse("https://ssd-api.jpl.nasa.gov/cad.api")?;
+    url.query_pairs_mut()
+        .append_pair("date-min", "now")
+        .append_pair("date-max", &format!("+{}", DAYS_AHEAD))
+        .append_pair("dist-max", &format!("{}LD", MAX_DIST_LD))
+        .append_pair("sort", "date");
+    Ok(url)
+}
+
+/// Passes from a `cad.api` response; empty when nothing comes close.
+pub fn parse_cad(json: &str) -> Result<Vec<CloseApproach>> {
+    let response: CadResponse = serde_json::from_str(json)?;
+    // A quiet stretch comes back with neither `fields` nor `data`
+    if response.data.is_empty() {
+        return Ok(Vec::new());
+    }
+    let column = |name: &str| response.fields.iter().position(|f| f == name).ok_or_else(|| anyhow!("no {} column", name));
+    let (des, cd, dist, v_rel, h) = (column("des")?, column("cd")?, column("dist")?, column("v_rel")?, column("h").ok());
+    response
+        .data
+        .iter()
+        .map(|row| {
+            let value = |i: usize| row.get(i).and_then(|v| v.as_deref()).ok_or_else(|| anyhow!("short row {:?}", row));
+            let number = |i: usize| value(i)?.parse::<f64>().map_err(|e| anyhow!("{}: {:?}", e, row.get(i)));
+            // "2029-Apr-13 21:46", TDB, which is a minute off UTC at most
+            let utc = NaiveDateTime::parse_from_str(value(cd)?, "%Y-%b-%d %H:%M")?.and_utc();
+            Ok(CloseApproach {
+                designation: value(des)?.to_string(),
+                utc,
+                dist_ld: number(dist)? / LD_AU,
+                v_rel_km_s: number(v_rel)?,
+                abs_mag: h.and_then(|i| number(i).ok()),
+            })
+        })
+        .collect()
+}
+
+/// Fetches the list into the shared state; false if it could not be had.
+#[cfg(feature = "net")]
+async fn load(state: &Arc<Mutex<DataState>>) -> bool {
+    let (config, offline) = {
+        let s = state.lock().unwrap();
+        (s.config.clone(), s.offline)
+    };
+    if offline {
+        state.lock().unwrap().status = "Offline; close approaches are not fetched".into();
+        return false;
+    }
+    if bandwidth::current().quota_reached(&config) {
+        state.lock().unwrap().status = "Daily download quota reached; close approaches wait for midnight".into();
+        return false;
+    }
+    let client = http_client(&config);
+    let on_retry = |r: &Retry| state.lock().unwrap().status = format!("Close approaches: {}", r);
+    let result = match cad_url() {
+        Ok(url) => get_api_text(&client, url, "CNEOS", &on_retry).await.map_err(anyhow::Error::from).and_then(|json| parse_cad(&json)),
+        Err(e) => Err(e),
+    };
+
+    let mut s = state.lock().unwrap();
+    s.bandwidth = bandwidth::current();
+    match result {
+        Ok(passes) => {
+            s.status = format!("Close approaches: {} NEO passes within {} LD", passes.len(), MAX_DIST_LD);
+            s.close_approaches = Some(Arc::new(CloseApproaches { fetched: Utc::now(), passes }));
+            true
+        }
+        Err(e) => {
+            s.notify(AlertLevel::Warning, format!("Close-approach fetch failed: {:#}", e));
+            false
+        }
+    }
+}
+
+/// Keeps the list current for the rest of the session, an hour between tries while it fails.
+#[cfg(feature = "net")]
+pub async fn refresh_daily(state: Arc<Mutex<DataState>>) {
+    loop {
+        let wait = if load(&state).await { REFRESH } else { RETRY };
+        tokio::time::sleep(wait).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cad_rows() {
+        let json = r#"{"signature":{"version":"1.5"},"count":"2",
+            "fields":["des","orbit_id","jd","cd","dist","dist_min","dist_max","v_rel","v_inf","t_sigma_f","h"],
+            "data":[["99942","220","2462240.407091595","2029-Apr-13 21:46","0.000254099","0.000254092","0.000254106","7.42249","5.84135","< 00:01","19.09"],
+                    ["2024 XY","3","2460680.5","2025-Jan-03 00:00","0.0205","0.02","0.021","9.1","9.0","00:05",null]]}"#;
+        let passes = parse_cad(json).unwrap();
+        assert_eq!(passes.len(), 2);
+        assert_eq!((passes[0].designation.as_str(), passes[0].utc.to_rfc3339()), ("99942", "2029-04-13T21:46:00+00:00".into()));
+        assert!((passes[0].dist_ld - 0.0989).abs() < 1e-3 && passes[0].abs_mag == Some(19.09));
+        assert_eq!(passes[1].abs_mag, None);
+
+        assert!(parse_cad(r#"{"signature":{"version":"1.5"},"count":"0"}"#).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,112 @@
+//! `solar-rs compare`: residuals of user-supplied positions against the crate's own, for checking
+//! a homework propagator or another ephemeris. Rows are `utc,body,x,y,z` with an optional
+//! `vx,vy,vz`, heliocentric on the J2000 ecliptic, in AU and AU/day (km and km/s with `--km`).
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{DateTime, Utc};
+use std::fmt::Write;
+
+use crate::events::parse_epoch;
+use crate::names;
+use crate::physics::{AU_KM, SECONDS_PER_DAY};
+use crate::sources::{self, Divergence, Source};
+use crate::types::{all_bodies, DataState, Vec3};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row {
+    /// 1-based, for pointing back into the file.
+    pub line: usize,
+    pub utc: DateTime<Utc>,
+    pub body: String,
+    pub pos: Vec3,
+    pub vel: Option<Vec3>,
+}
+
+/// The data rows of a CSV file, converted to AU and AU/day. Blank lines, `#` comments and a
+/// header line are skipped; anything else that doesn't parse is an error naming its line.
+pub fn parse(text: &str, km: bool) -> Result<Vec<Row>> {
+    let (to_au, to_au_d) = if km { (1.0 / AU_KM, SECONDS_PER_DAY / AU_KM) } else { (1.0, 1.0) };
+    let mut rows = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        let numbers: Vec<f64> = fields.iter().skip(2).map_while(|f| f.parse().ok()).collect();
+        if rows.is_empty() && numbers.is_empty() {
+            continue; // header
+        }
+        let context = || format!("line {}", i + 1);
+        let utc = fields.first().and_then(|t| parse_epoch(t)).ok_or_else(|| anyhow!("not a UTC time")).with_context(context)?;
+        let vec = |n: &[f64], scale: f64| Vec3 { x: n[0] * scale, y: n[1] * scale, z: n[2] * scale };
+        let (pos, vel) = match (fields.len(), numbers.len()) {
+            (5, 3) => (vec(&numbers, to_au), None),
+            (8, 6) => (vec(&numbers, to_au), Some(vec(&numbers[3..], to_au_d))),
+            _ => return Err(anyhow!("expected utc,body,x,y,z[,vx,vy,vz]")).with_context(context),
+        };
+        rows.push(Row { line: i + 1, utc, body: fields[1].to_string(), pos, vel });
+    }
+    if rows.is_empty() {
+        bail!("no data rows");
+    }
+    Ok(rows)
+}
+
+/// Each row's residual against `source`, or why the crate couldn't place that body then.
+/// Without user velocities the velocity residual is left at zero.
+pub fn residuals(state: &DataState, source: Source, rows: &[Row]) -> Vec<Result<Divergence>> {
+    rows.iter()
+        .map(|row| {
+            // Any registered body, shown or not, by any of its names
+            let m = all_bodies().find(|m| names::matches(m.name, m.id, &row.body)).ok_or_else(|| anyhow!("unknown body {:?}", row.body))?;
+            let (p, v) = sources::local_state(source, m.name, m.id, state.spk.as_deref(), row.utc)?;
+            Ok(sources::divergence((row.pos, row.vel.unwrap_or(v)), (p, v)))
+        })
+        .collect()
+}
+
+/// A residual table, one line per row, and the RMS and largest position residual.
+pub fn report(rows: &[Row], residuals: &[Result<Divergence>], source: Source) -> String {
+    let mut out = format!("Residuals against {} (yours − solar-rs)\n", source.label());
+    let _ = writeln!(out, "{:>5}  {:<20}  {:<10}  {:>14}  {:>14}  {:>12}  {:>12}", "line", "utc", "body", "pos km", "radial km", "angle \"", "vel m/s");
+    let mut compared = Vec::new();
+    for (row, residual) in rows.iter().zip(residuals) {
+        let utc = row.utc.format("%Y-%m-%d %H:%M:%S");
+        let _ = match residual {
+            Ok(d) => {
+                compared.push(d.pos_km);
+                let vel = if row.vel.is_some() { format!("{:.3}", d.vel_m_s) } else { "—".into() };
+                writeln!(out, "{:>5}  {:<20}  {:<10}  {:>14.3}  {:>14.3}  {:>12.3}  {:>12}", row.line, utc, row.body, d.pos_km, d.radial_km, d.angle_arcsec, vel)
+            }
+            Err(e) => writeln!(out, "{:>5}  {:<20}  {:<10}  skipped: {:#}", row.line, utc, row.body, e),
+        };
+    }
+    match compared.len() {
+        0 => out.push_str("Nothing compared.\n"),
+        n => {
+            let rms = (compared.iter().map(|d| d * d).sum::<f64>() / n as f64).sqrt();
+            let max = compared.iter().cloned().fold(0.0, f64::max);
+            let _ = writeln!(out, "{} of {} rows compared: RMS {:.3} km, largest {:.3} km", n, rows.len(), rms, max);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_skips_header_and_converts_km() {
+        let text = "# my propagator\nutc,body,x,y,z\n2030-01-01,Mars,149597870.7,0,0\n\n2030-01-02T00:00:00Z,Mars,0,1,0,1,0,0\n";
+        let rows = parse(text, true).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].line, 3);
+        assert!((rows[0].pos.x - 1.0).abs() < 1e-12);
+        assert!((rows[1].vel.unwrap().x - SECONDS_PER_DAY / AU_KM).abs() < 1e-15);
+
+        let err = parse("2030-01-01,Mars,1,2\n", false).unwrap_err();
+        assert!(format!("{:#}", err).starts_with("line 1"));
+    }
+}
@@ -0,0 +1,669 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    net::IpAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::physics::{julian_date, TDB_MINUS_UTC_S};
+use crate::sources::{self, Source};
+use crate::types::{all_bodies, meta_by_name, BodyClass, BodyId, BodyState, DataState, BODIES};
+
+/// User configuration, read from `--config <path>` or `$XDG_CONFIG_HOME/solar-rs/config.toml`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Seconds between Horizons refreshes.
+    pub refresh_secs: u64,
+    /// Hours of vectors fetched per Horizons query and interpolated between refreshes; the
+    /// tables are also kept on disk across restarts.
+    pub prefetch_hours: f64,
+    /// Horizons queries in flight at once during a refresh.
+    pub fetch_concurrency: usize,
+    /// Name servers asked before the system resolver, e.g. `["1.1.1.1", "2606:4700:4700::1111"]`.
+    pub dns_servers: Vec<IpAddr>,
+    /// Megabytes a day that may be downloaded from Horizons; past it, refreshes use cached and
+    /// local data until midnight.
+    pub daily_quota_mb: Option<f64>,
+    /// Hours after which a body's data counts as stale and is marked in the table, map and
+    /// header. Prefetched Horizons tables count from their query, so keep this above
+    /// `prefetch_hours`.
+    pub max_data_age_hours: f64,
+    /// Bodies to fetch and show, by name; all built-in planets when unset.
+    pub bodies: Option<Vec<String>>,
+    /// Adds Pluto, Ceres, Haumea, Makemake and Eris to the bodies; `D` toggles at runtime.
+    pub dwarf_planets: bool,
+    /// Adds Voyager 1 and 2, New Horizons, JWST and Parker Solar Probe; `s` toggles at runtime.
+    pub spacecraft: bool,
+    pub theme: Theme,
+    pub layout: PanelLayout,
+    /// Height of a terminal cell over its width, which the orbit map and orrery correct for so
+    /// that circular orbits look circular; about 2 in most fonts.
+    pub cell_aspect: f64,
+    /// Past positions kept per body for its trail on the orbit map; 0 turns trails off.
+    pub trail_length: usize,
+    /// Epoch between trail samples, so a trail spans `trail_length` times this.
+    pub trail_step_hours: f64,
+    /// The older part of each trail, as a fraction of it, that is drawn faded.
+    pub trail_fade: f64,
+    /// Days ahead the selected body's predicted path reaches (`w` on the orbit map).
+    pub path_days: f64,
+    /// Initial view in the map panel; `v` cycles through the others at runtime.
+    pub view: MapView,
+    /// Where the map and table measure positions from; `b` toggles at runtime.
+    pub origin: Origin,
+    /// Initial coloring of bodies; `c` cycles at runtime.
+    pub color_by: ColorBy,
+    /// Language for body names (ISO 639-1, e.g. "de" or "ja"); the locale when unset.
+    pub language: Option<String>,
+    /// Directions drawn as edge markers on the orbit map.
+    pub sky_markers: Vec<SkyMarker>,
+    pub observer: Option<Observer>,
+    /// Mount to send GoTo commands to with `g`.
+    pub telescope: Option<Telescope>,
+    /// Stellarium remote-control endpoint to follow the selection and epoch.
+    pub stellarium: Option<Stellarium>,
+    /// Redis server each refresh's state vectors are published to (`redis` builds).
+    pub redis: Option<Redis>,
+    /// Data sources in priority order, tried per body on every refresh until one answers.
+    pub sources: Vec<Source>,
+    /// Local source the divergence view (`K`) measures the shown positions against.
+    pub reference_source: Source,
+    /// Time scale of Horizons vector queries and of the Julian Date in the header; `U` toggles
+    /// at runtime.
+    pub time_scale: TimeScale,
+    /// Per-body Horizons query overrides, keyed by body name (e.g. `[overrides.Mars]`).
+    pub overrides: BTreeMap<String, QueryOverrides>,
+    /// `low` for always-on displays on small boards such as a Raspberry Pi.
+    pub power: Power,
+    /// Asteroids, comets and other small bodies to fetch and plot with the planets. Read once
+    /// at startup; edits to this list take effect on the next start.
+    pub extra_bodies: Vec<ExtraBody>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            refresh_secs: 5,
+            prefetch_hours: 6.0,
+            fetch_concurrency: 4,
+            dns_servers: Vec::new(),
+            daily_quota_mb: None,
+            max_data_age_hours: 12.0,
+            bodies: None,
+            dwarf_planets: false,
+            spacecraft: false,
+            theme: Theme::default(),
+            layout: PanelLayout::default(),
+            cell_aspect: 2.0,
+            trail_length: 40,
+            trail_step_hours: 6.0,
+            trail_fade: 0.5,
+            path_days: 90.0,
+            view: MapView::default(),
+            origin: Origin::default(),
+            color_by: ColorBy::default(),
+            language: None,
+            sky_markers: vec![SkyMarker::Apex],
+            observer: None,
+            telescope: None,
+            stellarium: None,
+            redis: None,
+            sources: sources::default_chain(),
+            reference_source: Source::Vsop87,
+            time_scale: TimeScale::default(),
+            overrides: BTreeMap::new(),
+            power: Power::default(),
+            extra_bodies: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Theme {
+    /// Per-body colors.
+    #[default]
+    Default,
+    /// No per-body colors, for monochrome terminals.
+    Mono,
+}
+
+/// Power profile, trading smoothness for CPU time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Power {
+    #[default]
+    Normal,
+    /// Refreshes at most every `LOW_POWER_REFRESH_SECS`, redraws once a second (every
+    /// `THROTTLED_FRAME` while the firmware throttles), skips Hill spheres and velocity arrows,
+    /// and shows the CPU temperature (see `power`).
+    Low,
+}
+
+/// Refresh interval floor in the low-power profile.
+const LOW_POWER_REFRESH_SECS: u64 = 300;
+/// Redraw intervals: the normal one keeps the update pulse smooth.
+const FRAME: Duration = Duration::from_millis(50);
+const LOW_POWER_FRAME: Duration = Duration::from_secs(1);
+const THROTTLED_FRAME: Duration = Duration::from_secs(5);
+
+/// Which panels share the screen below the header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PanelLayout {
+    /// Table and detail pane on the left, map on the right.
+    #[default]
+    Split,
+    /// Full-width map, e.g. for wall displays.
+    Map,
+    /// Table and detail pane only.
+    Table,
+    /// Full-width table with heliocentric and geocentric vectors side by side.
+    Compare,
+}
+
+/// The point positions are shown relative to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Origin {
+    /// The Sun's centre (Horizons `CENTER=500@10`).
+    #[default]
+    Heliocentric,
+    /// The solar-system barycentre (`CENTER=500@0`), which the Sun circles at up to about
+    /// two solar radii, mostly at Jupiter's pull.
+    Barycentric,
+}
+
+/// Time scale for Horizons `TIME_TYPE` and the Julian Date shown in the header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TimeScale {
+    /// Universal Time, as the app keeps its clock.
+    #[default]
+    Ut,
+    /// Barycentric Dynamical Time, which JPL's ephemerides are computed in.
+    Tdb,
+}
+
+impl TimeScale {
+    pub fn toggle(self) -> Self {
+        match self {
+            TimeScale::Ut => TimeScale::Tdb,
+            TimeScale::Tdb => TimeScale::Ut,
+        }
+    }
+
+    /// The Horizons `TIME_TYPE` value, also used as the label.
+    pub fn label(self) -> &'static str {
+        match self {
+            TimeScale::Ut => "UT",
+            TimeScale::Tdb => "TDB",
+        }
+    }
+
+    /// `utc` as a time on this scale, still carried in a UTC timestamp.
+    pub fn from_utc(self, utc: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            TimeScale::Ut => utc,
+            TimeScale::Tdb => utc + chrono::Duration::milliseconds((TDB_MINUS_UTC_S * 1000.0) as i64),
+        }
+    }
+
+    pub fn julian_date(self, utc: DateTime<Utc>) -> f64 {
+        julian_date(self.from_utc(utc))
+    }
+}
+
+/// What the map panel draws.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MapView {
+    /// Top-down orbit map, to scale.
+    #[default]
+    Map,
+    /// Bodies on one log-scale distance axis from the Sun, like a subway line.
+    Strip,
+    /// Stylised orrery: evenly spaced orbits and exaggerated planet sizes, for wall displays.
+    Orrery,
+}
+
+impl MapView {
+    pub fn next(self) -> Self {
+        match self {
+            MapView::Map => MapView::Strip,
+            MapView::Strip => MapView::Orrery,
+            MapView::Orrery => MapView::Map,
+        }
+    }
+}
+
+/// How bodies are coloured on the map and in the table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorBy {
+    /// Each body's own color.
+    #[default]
+    Body,
+    /// Star, terrestrial planet, gas giant, ice giant.
+    Class,
+    /// Current distance from the Sun, warm to cold.
+    Distance,
+    /// Apparent brightness from Earth.
+    Magnitude,
+}
+
+impl ColorBy {
+    pub fn next(self) -> Self {
+        match self {
+            ColorBy::Body => ColorBy::Class,
+            ColorBy::Class => ColorBy::Distance,
+            ColorBy::Distance => ColorBy::Magnitude,
+            ColorBy::Magnitude => ColorBy::Body,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorBy::Body => "body",
+            ColorBy::Class => "class",
+            ColorBy::Distance => "distance",
+            ColorBy::Magnitude => "magnitude",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SkyMarker {
+    /// Solar apex, the direction of the Sun's motion relative to nearby stars.
+    Apex,
+    GalacticCenter,
+}
+
+/// Observer location on Earth (geodetic).
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Observer {
+    pub lat_deg: f64,
+    pub lon_deg: f64, // east positive
+    #[serde(default)]
+    pub height_m: f64, // above the WGS 84 ellipsoid
+}
+
+/// A body beyond the built-in ones, e.g.
+/// `{ name = "Apophis", command = "DES=20099942;", icon = "☄", color = "magenta" }`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExtraBody {
+    pub name: String,
+    /// Horizons COMMAND, used verbatim: a designation or SPK id, with a trailing `;` for
+    /// small bodies (`"433;"`, `"DES=20099942;"`, `"C/2023 A3;"`).
+    pub command: String,
+    /// SPK id, for `--spk` kernels that include the body; one is made up when unset.
+    pub id: Option<i32>,
+    pub icon: Option<char>,
+    /// Color name (`"magenta"`, `"light-green"`) or `"#rrggbb"`.
+    pub color: Option<String>,
+    /// Absolute magnitude H, for coloring by magnitude.
+    pub abs_mag: Option<f64>,
+}
+
+/// Telescope mount control endpoint.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "protocol", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum Telescope {
+    /// ASCOM Alpaca REST device, e.g. `address = "192.168.1.20:11111"`, `device = 0`.
+    Alpaca {
+        address: String,
+        #[serde(default)]
+        device: u32,
+    },
+    /// INDI server (usually port 7624) and the mount driver's device name.
+    Indi { address: String, device: String },
+}
+
+/// Stellarium's Remote Control plugin (Configure → Plugins → Remote Control).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Stellarium {
+    pub address: String,
+    /// Only if "Requires password" is enabled in the plugin.
+    pub password: Option<String>,
+}
+
+impl Default for Stellarium {
+    fn default() -> Self {
+        Stellarium { address: "localhost:8090".into(), password: None }
+    }
+}
+
+/// Where each refresh goes in Redis: a pub/sub `channel`, a `stream`, or both.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Redis {
+    pub url: String,
+    /// Channel a JSON message per refresh is published on; `solar-rs` if no stream is given.
+    pub channel: Option<String>,
+    /// Stream an entry per body is added to on each refresh.
+    pub stream: Option<String>,
+    /// Entries the stream is trimmed to, approximately.
+    pub stream_maxlen: usize,
+}
+
+impl Default for Redis {
+    fn default() -> Self {
+        Redis { url: "redis://127.0.0.1/".into(), channel: None, stream: None, stream_maxlen: 100_000 }
+    }
+}
+
+impl Redis {
+    pub fn channel(&self) -> Option<&str> {
+        self.channel.as_deref().or(self.stream.is_none().then_some("solar-rs"))
+    }
+}
+
+/// Horizons query parameters that replace the defaults in `build_horizons_url` for one body.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QueryOverrides {
+    pub center: Option<String>,
+    pub ref_plane: Option<String>,
+    pub step_size: Option<String>,
+    /// Extra `;`-separated COMMAND qualifiers, e.g. `["CAP<2030", "NOFRAG"]` for comet apparitions.
+    pub command_suffix: Vec<String>,
+}
+
+impl Config {
+    pub fn overrides_for(&self, body_name: &str) -> Option<&QueryOverrides> {
+        self.overrides.get(body_name)
+    }
+
+    /// Time between refreshes, with the low-power floor applied.
+    pub fn refresh_interval(&self) -> Duration {
+        match self.power {
+            Power::Normal => Duration::from_secs(self.refresh_secs),
+            Power::Low => Duration::from_secs(self.refresh_secs.max(LOW_POWER_REFRESH_SECS)),
+        }
+    }
+
+    /// Whether the data behind a body's position, as of its source's epoch, is older than
+    /// `max_data_age_hours` at `now`. Bodies without data yet aren't stale, just missing.
+    pub fn is_stale(&self, body: &BodyState, now: DateTime<Utc>) -> bool {
+        body.source.is_some_and(|src| (now - src.epoch).num_seconds() as f64 > self.max_data_age_hours * 3600.0)
+    }
+
+    /// Time between redraws when no key is pressed.
+    pub fn frame_interval(&self, throttled: bool) -> Duration {
+        match (self.power, throttled) {
+            (Power::Normal, _) => FRAME,
+            (Power::Low, false) => LOW_POWER_FRAME,
+            (Power::Low, true) => THROTTLED_FRAME,
+        }
+    }
+
+    /// Whether a body is fetched and shown. Extra bodies, from `extra_bodies` or looked up in
+    /// the SBDB, always are, and the compare layout needs Earth as the centre of its
+    /// geocentric columns.
+    pub fn is_enabled(&self, body_name: &str) -> bool {
+        let meta = meta_by_name(body_name);
+        let class = meta.map(|m| m.class);
+        let (dwarf, craft) = (class == Some(BodyClass::Dwarf), class == Some(BodyClass::Spacecraft));
+        let listed = match &self.bodies {
+            Some(names) => names.iter().any(|n| n == body_name),
+            None => !dwarf && !craft,
+        };
+        let centre = meta.is_some_and(|m| m.id == BodyId::EARTH) && self.layout == PanelLayout::Compare;
+        let extra = class == Some(BodyClass::SmallBody);
+        listed || (dwarf && self.dwarf_planets) || (craft && self.spacecraft) || centre || extra
+    }
+
+    /// Semantic checks that TOML parsing alone can't catch.
+    pub fn validate(&self) -> Result<()> {
+        if self.refresh_secs == 0 {
+            return Err(anyhow!("refresh_secs must be at least 1"));
+        }
+        if !(self.prefetch_hours > 0.0 && self.prefetch_hours <= 240.0) {
+            return Err(anyhow!("prefetch_hours must be within 0..240, got {}", self.prefetch_hours));
+        }
+        if self.max_data_age_hours.is_nan() || self.max_data_age_hours <= 0.0 {
+            return Err(anyhow!("max_data_age_hours must be positive, got {}", self.max_data_age_hours));
+        }
+        if !(self.cell_aspect >= 0.5 && self.cell_aspect <= 4.0) {
+            return Err(anyhow!("cell_aspect must be within 0.5..4, got {}", self.cell_aspect));
+        }
+        if self.trail_step_hours.is_nan() || self.trail_step_hours <= 0.0 {
+            return Err(anyhow!("trail_step_hours must be positive, got {}", self.trail_step_hours));
+        }
+        if !(0.0..=1.0).contains(&self.trail_fade) {
+            return Err(anyhow!("trail_fade must be within 0..1, got {}", self.trail_fade));
+        }
+        if !(self.path_days > 0.0 && self.path_days <= 36_525.0) {
+            return Err(anyhow!("path_days must be within 0..36525, got {}", self.path_days));
+        }
+        if !self.reference_source.is_local() {
+            return Err(anyhow!("reference_source must be keplerian, vsop87 or spk, got {}", self.reference_source.label()));
+        }
+        if self.fetch_concurrency == 0 {
+            return Err(anyhow!("fetch_concurrency must be at least 1"));
+        }
+        let names = self.bodies.iter().flatten().chain(self.overrides.keys());
+        for name in names {
+            if meta_by_name(name).is_none() && !self.extra_bodies.iter().any(|b| &b.name == name) {
+                return Err(anyhow!("unknown body {:?}", name));
+            }
+        }
+        for (i, b) in self.extra_bodies.iter().enumerate() {
+            if b.name.trim().is_empty() || b.command.trim().is_empty() {
+                return Err(anyhow!("extra_bodies[{}] needs a name and a command", i));
+            }
+            let earlier = &self.extra_bodies[..i];
+            if BODIES.iter().any(|m| m.name == b.name) || earlier.iter().any(|e| e.name == b.name) {
+                return Err(anyhow!("extra body name {:?} is already taken", b.name));
+            }
+            let id_taken = |id: i32| BODIES.iter().any(|m| m.id.0 == id) || earlier.iter().any(|e| e.id == Some(id));
+            if let Some(id) = b.id.filter(|&id| id_taken(id)) {
+                return Err(anyhow!("extra body {}: id {} is already taken", b.name, id));
+            }
+        }
+        if let Some(o) = self.observer {
+            if !(-90.0..=90.0).contains(&o.lat_deg) {
+                return Err(anyhow!("observer.lat_deg must be within ±90, got {}", o.lat_deg));
+            }
+            if !(-180.0..=360.0).contains(&o.lon_deg) {
+                return Err(anyhow!("observer.lon_deg must be within -180..360, got {}", o.lon_deg));
+            }
+        }
+        Ok(())
+    }
+
+    /// Body list for `DataState`, carrying over positions already fetched for kept bodies.
+    pub fn body_states(&self, previous: &[BodyState]) -> Vec<BodyState> {
+        all_bodies()
+            .filter(|m| self.is_enabled(m.name))
+            .map(|m| {
+                previous.iter().find(|b| b.id == m.id).cloned().unwrap_or(BodyState {
+                    name: m.name,
+                    id: m.id,
+                    pos_au: None,
+                    vel_au_d: None,
+                    source: None,
+                    updated: None,
+                    trail: VecDeque::new(),
+                })
+            })
+            .collect()
+    }
+}
+
+pub fn default_config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("solar-rs").join("config.toml"))
+}
+
+/// Where the main config and its named profiles live.
+#[derive(Debug, Clone)]
+pub struct ConfigPaths {
+    pub main: PathBuf,
+    /// Whether the main file must exist (true when given with `--config`).
+    pub required: bool,
+}
+
+impl ConfigPaths {
+    pub fn resolve(explicit: Option<PathBuf>) -> Option<Self> {
+        match explicit {
+            Some(main) => Some(ConfigPaths { main, required: true }),
+            None => default_config_path().map(|main| ConfigPaths { main, required: false }),
+        }
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        match self.main.parent() {
+            Some(d) if !d.as_os_str().is_empty() => d.to_path_buf(),
+            _ => PathBuf::from("."),
+        }
+    }
+
+    /// Profiles sit next to the main config: `<config dir>/profiles/<name>.toml`.
+    pub fn profiles_dir(&self) -> PathBuf {
+        self.dir().join("profiles")
+    }
+
+    pub fn profile(&self, name: &str) -> PathBuf {
+        self.profiles_dir().join(format!("{}.toml", name))
+    }
+
+    /// Names of the available profiles, sorted.
+    pub fn list_profiles(&self) -> Vec<String> {
+        toml_stems(&self.profiles_dir())
+    }
+
+    /// Camera tours (see `tours`) sit next to the profiles: `<config dir>/tours/<name>.toml`.
+    pub fn tours_dir(&self) -> PathBuf {
+        self.dir().join("tours")
+    }
+
+    pub fn tour(&self, name: &str) -> PathBuf {
+        self.tours_dir().join(format!("{}.toml", name))
+    }
+
+    /// Names of the available tours, sorted.
+    pub fn list_tours(&self) -> Vec<String> {
+        toml_stems(&self.tours_dir())
+    }
+}
+
+fn toml_stems(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut names: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|x| x == "toml"))
+        .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .collect();
+    names.sort();
+    names
+}
+
+/// Top-level config keys that can be set from the environment as `SOLAR_RS_<KEY>`.
+const ENV_KEYS: &[&str] = &[
+    "refresh_secs",
+    "prefetch_hours",
+    "fetch_concurrency",
+    "dns_servers",
+    "daily_quota_mb",
+    "max_data_age_hours",
+    "bodies",
+    "dwarf_planets",
+    "spacecraft",
+    "theme",
+    "layout",
+    "cell_aspect",
+    "trail_length",
+    "trail_step_hours",
+    "trail_fade",
+    "path_days",
+    "view",
+    "origin",
+    "color_by",
+    "language",
+    "sky_markers",
+    "observer",
+    "telescope",
+    "stellarium",
+    "redis",
+    "sources",
+    "reference_source",
+    "time_scale",
+    "overrides",
+    "power",
+    "extra_bodies",
+];
+
+/// Environment settings, applied over the config file and profile. Values are TOML
+/// (`SOLAR_RS_BODIES='["Sun", "Earth"]'`); anything that doesn't parse is taken as a string.
+fn env_overrides() -> toml::Table {
+    let mut table = toml::Table::new();
+    for key in ENV_KEYS {
+        let Ok(raw) = std::env::var(format!("SOLAR_RS_{}", key.to_uppercase())) else { continue };
+        let value = toml::from_str::<toml::Table>(&format!("v = {}", raw))
+            .ok()
+            .and_then(|mut t| t.remove("v"))
+            .unwrap_or(toml::Value::String(raw));
+        table.insert(key.to_string(), value);
+    }
+    table
+}
+
+fn read_table(path: &PathBuf) -> Result<toml::Table> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("read config {}", path.display()))?;
+    toml::from_str(&text).with_context(|| format!("parse config {}", path.display()))
+}
+
+/// Loads and validates the config, with the named profile's top-level keys replacing the
+/// main file's. A missing optional main file yields the defaults.
+pub fn load_config(paths: Option<&ConfigPaths>, profile: Option<&str>) -> Result<Config> {
+    let Some(paths) = paths else {
+        return match profile {
+            Some(name) => Err(anyhow!("no config directory for profile {:?}", name)),
+            None => {
+                let config: Config = env_overrides().try_into().context("parse SOLAR_RS_* settings")?;
+                config.validate().context("invalid SOLAR_RS_* settings")?;
+                Ok(config)
+            }
+        };
+    };
+
+    let mut table = if paths.required || paths.main.exists() {
+        read_table(&paths.main)?
+    } else {
+        toml::Table::new()
+    };
+
+    if let Some(name) = profile {
+        let path = paths.profile(name);
+        if !path.exists() {
+            return Err(anyhow!("unknown profile {:?} (expected {})", name, path.display()));
+        }
+        table.extend(read_table(&path)?);
+    }
+
+    table.extend(env_overrides());
+
+    let config: Config = table
+        .try_into()
+        .with_context(|| format!("parse config {}", paths.main.display()))?;
+    config.validate().with_context(|| format!("invalid config {}", paths.main.display()))?;
+    Ok(config)
+}
+
+/// Swaps a freshly loaded config into the shared state. The view picks up its settings on
+/// the next frame (see `ViewState::sync`).
+pub fn apply_config(state: &mut DataState, config: Config) {
+    state.bodies = config.body_states(&state.bodies);
+    state.config = Arc::new(config);
+}
@@ -0,0 +1,72 @@
+//! `solar-rs daemon`: a headless collector that keeps the shared snapshot fresh on a schedule,
+//! so TUI instances (and the snapshot store) never wait on the Horizons API.
+
+use anyhow::{bail, Result};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    net::UnixStream,
+    signal::unix::{signal, SignalKind},
+    time::sleep,
+};
+
+use crate::share::{become_primary, socket_path, store_path};
+use solar_rs::types::DataState;
+
+pub async fn run(state: Arc<Mutex<DataState>>) -> Result<()> {
+    let path = socket_path();
+    if UnixStream::connect(&path).await.is_ok() {
+        bail!("another solar-rs instance is already collecting on {}", path.display());
+    }
+
+    eprintln!("solar-rs daemon: serving {}", path.display());
+    if let Some(store) = store_path() {
+        eprintln!("solar-rs daemon: snapshot store {}", store.display());
+    }
+    become_primary(state.clone(), path.clone());
+
+    // One log line per refresh; stderr ends up in the journal under systemd.
+    let mut last_logged: Option<String> = None;
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+    loop {
+        tokio::select! {
+            _ = sleep(Duration::from_secs(1)) => {}
+            _ = &mut shutdown => {
+                // The snapshot store is written atomically, so there is nothing to flush.
+                let _ = std::fs::remove_file(&path);
+                eprintln!("solar-rs daemon: shutting down");
+                return Ok(());
+            }
+        }
+        let mut s = state.lock().unwrap();
+        if let Some(msg) = s.popup.take() {
+            eprintln!("solar-rs daemon: {}", msg);
+        }
+        if s.last_update_utc != last_logged {
+            let fetched = s.bodies.iter().filter(|b| b.pos_au.is_some()).count();
+            eprintln!(
+                "solar-rs daemon: refresh {} — {}/{} bodies — {}",
+                s.last_update_utc.as_deref().unwrap_or("—"),
+                fetched,
+                s.bodies.len(),
+                s.status
+            );
+            last_logged = s.last_update_utc.clone();
+        }
+    }
+}
+
+/// Resolves on SIGTERM (container/systemd stop) or Ctrl-C.
+pub async fn shutdown_signal() {
+    let mut term = match signal(SignalKind::terminate()) {
+        Ok(s) => s,
+        Err(_) => return std::future::pending().await,
+    };
+    tokio::select! {
+        _ = term.recv() => {}
+        _ = tokio::signal::ctrl_c() => {}
+    }
+}
@@ -0,0 +1,152 @@
+//! Name resolution for the HTTP clients, for field laptops on cellular links where DNS is the
+//! flakiest part of the path. Names go to the configured `dns_servers` first, then the system
+//! resolver, and as a last resort to the addresses that worked last time. Both address
+//! families are returned, IPv6 first and interleaved, so the connector's happy-eyeballs race
+//! (IPv6, then IPv4 after 300 ms) works on IPv6-only and broken-IPv6 networks alike.
+//!
+//! Failures come back as [`DnsError`], which [`dns_failure`] finds in a request error so
+//! the status line can say "DNS" rather than blaming Horizons.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    net::{IpAddr, SocketAddr},
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+
+use hickory_resolver::{
+    config::{LookupIpStrategy, NameServerConfigGroup, ResolverConfig},
+    name_server::TokioConnectionProvider,
+    TokioResolver,
+};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+
+/// Per server and record type.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Addresses of every name that last resolved, shared by all clients in the process.
+static LAST_GOOD: LazyLock<Mutex<HashMap<String, Vec<IpAddr>>>> = LazyLock::new(Default::default);
+
+/// A name that no resolver could answer for.
+#[derive(Debug)]
+pub struct DnsError {
+    pub host: String,
+    pub reason: String,
+}
+
+impl fmt::Display for DnsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "DNS lookup for {} failed: {}", self.host, self.reason)
+    }
+}
+
+impl std::error::Error for DnsError {}
+
+/// The `DnsError` behind a failed request, if name resolution is what failed.
+pub fn dns_failure<'a>(e: &'a (dyn std::error::Error + 'static)) -> Option<&'a DnsError> {
+    let mut source = Some(e);
+    while let Some(e) = source {
+        if let Some(dns) = e.downcast_ref::<DnsError>() {
+            return Some(dns);
+        }
+        source = e.source();
+    }
+    None
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Resolver {
+    /// Asks the configured servers; `None` without any.
+    servers: Option<TokioResolver>,
+}
+
+impl Resolver {
+    /// Asks `servers` (port 53, UDP and TCP for truncated replies) before the system
+    /// resolver; just the system one when empty.
+    pub fn new(servers: &[IpAddr]) -> Self {
+        if servers.is_empty() {
+            return Resolver::default();
+        }
+        let config = ResolverConfig::from_parts(None, Vec::new(), NameServerConfigGroup::from_ips_clear(servers, 53, true));
+        let mut builder = TokioResolver::builder_with_config(config, TokioConnectionProvider::default());
+        let options = builder.options_mut();
+        // A and AAAA asked for separately, so a family that times out doesn't lose the other
+        options.ip_strategy = LookupIpStrategy::Ipv4AndIpv6;
+        options.timeout = QUERY_TIMEOUT;
+        options.attempts = 1;
+        Resolver { servers: Some(builder.build()) }
+    }
+
+    async fn lookup(&self, host: &str) -> Result<Vec<IpAddr>, DnsError> {
+        let mut reasons = Vec::new();
+        if let Some(servers) = &self.servers {
+            match servers.lookup_ip(host).await {
+                Ok(lookup) if lookup.iter().next().is_some() => return Ok(lookup.iter().collect()),
+                Ok(_) => reasons.push("dns_servers: no addresses".to_string()),
+                Err(e) => reasons.push(format!("dns_servers: {}", e)),
+            }
+        }
+        match tokio::net::lookup_host((host, 0)).await {
+            Ok(addrs) => {
+                let addrs: Vec<IpAddr> = addrs.map(|a| a.ip()).collect();
+                if !addrs.is_empty() {
+                    return Ok(addrs);
+                }
+                reasons.push("system resolver: no addresses".into());
+            }
+            Err(e) => reasons.push(format!("system resolver: {}", e)),
+        }
+        Err(DnsError { host: host.to_string(), reason: reasons.join("; ") })
+    }
+}
+
+impl Resolve for Resolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs = match resolver.lookup(&host).await {
+                Ok(addrs) => {
+                    let addrs = happy_eyeballs_order(addrs);
+                    LAST_GOOD.lock().unwrap().insert(host, addrs.clone());
+                    addrs
+                }
+                Err(e) => match LAST_GOOD.lock().unwrap().get(&host) {
+                    Some(known) => known.clone(),
+                    None => return Err(e.into()),
+                },
+            };
+            let addrs: Addrs = Box::new(addrs.into_iter().map(|ip| SocketAddr::new(ip, 0)));
+            Ok(addrs)
+        })
+    }
+}
+
+/// IPv6 and IPv4 addresses alternating, IPv6 first (RFC 8305, section 4).
+fn happy_eyeballs_order(addrs: Vec<IpAddr>) -> Vec<IpAddr> {
+    let (mut v6, mut v4): (Vec<_>, Vec<_>) = addrs.into_iter().partition(IpAddr::is_ipv6);
+    v6.dedup();
+    v4.dedup();
+    let mut ordered = Vec::with_capacity(v6.len() + v4.len());
+    let (mut v6, mut v4) = (v6.into_iter(), v4.into_iter());
+    loop {
+        match (v6.next(), v4.next()) {
+            (None, None) => return ordered,
+            (a, b) => ordered.extend(a.into_iter().chain(b)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_ipv6_first_and_interleaved() {
+        let v4 = |n: u8| IpAddr::from([10, 0, 0, n]);
+        let v6 = |n: u16| IpAddr::from([0x2001, 0xdb8, 0, 0, 0, 0, 0, n]);
+        assert_eq!(happy_eyeballs_order(vec![v4(1), v4(2), v6(1)]), vec![v6(1), v4(1), v4(2)]);
+        assert_eq!(happy_eyeballs_order(vec![v4(1), v6(1), v6(2), v4(1)]), vec![v6(1), v4(1), v6(2)]);
+    }
+}
@@ -0,0 +1,61 @@
+//! Built-in low-precision ephemeris for the eight planets and Pluto from Standish's "Keplerian Elements
+//! for Approximate Positions of the Major Planets" (JPL, table 1: 1800–2050 AD), so positions
+//! keep advancing with no network. Errors stay under an arcminute or so for the inner planets
+//! and reach about ten for Jupiter and Saturn over that span. "Earth" is the Earth–Moon
+//! barycentre, up to 4,700 km from Earth's centre.
+
+use chrono::{DateTime, Utc};
+
+use crate::orbital_mechanics::{elements_to_state, true_from_mean_anomaly_deg, KeplerElements};
+use crate::physics::{days_since_j2000, reference_orbit, GM_SUN_AU3_D2};
+use crate::types::Vec3;
+
+/// Rates per Julian century of a, e, I, L, ϖ, Ω, added to the J2000 values in
+/// [`crate::physics::REFERENCE_ORBITS`].
+struct Rates {
+    name: &'static str,
+    a_au: f64,
+    e: f64,
+    i_deg: f64,
+    mean_longitude_deg: f64,
+    long_perihelion_deg: f64,
+    long_node_deg: f64,
+}
+
+#[rustfmt::skip]
+const RATES: &[Rates] = &[
+    Rates { name: "Mercury", a_au: 0.000_000_37,  e: 0.000_019_06,  i_deg: -0.005_947_49, mean_longitude_deg: 149_472.674_111_75, long_perihelion_deg: 0.160_476_89,  long_node_deg: -0.125_340_81 },
+    Rates { name: "Venus",   a_au: 0.000_003_90,  e: -0.000_041_07, i_deg: -0.000_788_90, mean_longitude_deg: 58_517.815_387_29,  long_perihelion_deg: 0.002_683_29,  long_node_deg: -0.277_694_18 },
+    Rates { name: "Earth",   a_au: 0.000_005_62,  e: -0.000_043_92, i_deg: -0.012_946_68, mean_longitude_deg: 35_999.372_449_81,  long_perihelion_deg: 0.323_273_64,  long_node_deg: 0.0 },
+    Rates { name: "Mars",    a_au: 0.000_018_47,  e: 0.000_078_82,  i_deg: -0.008_131_31, mean_longitude_deg: 19_140.302_684_99,  long_perihelion_deg: 0.444_410_88,  long_node_deg: -0.292_573_43 },
+    Rates { name: "Jupiter", a_au: -0.000_116_07, e: -0.000_132_53, i_deg: -0.001_837_14, mean_longitude_deg: 3_034.746_127_75,   long_perihelion_deg: 0.212_526_68,  long_node_deg: 0.204_691_06 },
+    Rates { name: "Saturn",  a_au: -0.001_250_60, e: -0.000_509_91, i_deg: 0.001_936_09,  mean_longitude_deg: 1_222.493_622_01,   long_perihelion_deg: -0.418_972_16, long_node_deg: -0.288_677_94 },
+    Rates { name: "Uranus",  a_au: -0.001_961_76, e: -0.000_043_97, i_deg: -0.002_429_39, mean_longitude_deg: 428.482_027_85,     long_perihelion_deg: 0.408_052_81,  long_node_deg: 0.042_405_89 },
+    Rates { name: "Neptune", a_au: 0.000_262_91,  e: 0.000_051_05,  i_deg: 0.000_353_72,  mean_longitude_deg: 218.459_453_25,     long_perihelion_deg: -0.322_414_64, long_node_deg: -0.005_086_64 },
+    Rates { name: "Pluto",   a_au: -0.000_315_96, e: 0.000_051_70,  i_deg: 0.000_048_18,  mean_longitude_deg: 145.207_805_15,     long_perihelion_deg: -0.040_629_42, long_node_deg: -0.011_834_82 },
+];
+
+/// Mean elements of `name` at `utc`, or `None` for bodies outside the table.
+pub fn mean_elements(name: &str, utc: DateTime<Utc>) -> Option<KeplerElements> {
+    let base = reference_orbit(name)?;
+    let rate = RATES.iter().find(|r| r.name == name)?;
+    let t = days_since_j2000(utc) / 36_525.0;
+
+    let e = base.e + rate.e * t;
+    let node = base.long_node_deg + rate.long_node_deg * t;
+    let perihelion = base.long_perihelion_deg + rate.long_perihelion_deg * t;
+    let mean_longitude = base.mean_longitude_deg + rate.mean_longitude_deg * t;
+    Some(KeplerElements {
+        a_au: base.a_au + rate.a_au * t,
+        e,
+        i_deg: base.i_deg + rate.i_deg * t,
+        raan_deg: node,
+        arg_periapsis_deg: perihelion - node,
+        true_anomaly_deg: true_from_mean_anomaly_deg((mean_longitude - perihelion).rem_euclid(360.0), e),
+    })
+}
+
+/// Heliocentric J2000 ecliptic position (AU) and velocity (AU/day) of `name` at `utc`.
+pub fn state_at(name: &str, utc: DateTime<Utc>) -> Option<(Vec3, Vec3)> {
+    mean_elements(name, utc).map(|el| elements_to_state(&el, GM_SUN_AU3_D2))
+}
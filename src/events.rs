@@ -0,0 +1,622 @@
+//! Upcoming orbital events predicted from the latest state vectors by two-body propagation.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration as ChronoDuration, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+use std::{io::Write, path::Path, time::Instant};
+use serde::Serialize;
+#[cfg(feature = "net")]
+use utoipa::ToSchema;
+
+use crate::markers::Marker;
+use crate::orbital_mechanics::{propagate, state_to_elements};
+use crate::physics::{AU_KM, GM_SUN_AU3_D2};
+use crate::horizons::Trajectory;
+use crate::types::{meta_by_id, meta_by_name, BodyClass, BodyId, BodyState, DataState, Vec3};
+
+/// Below this inclination to the J2000 ecliptic nodes are meaningless; Earth's own orbit
+/// drifts only a few thousandths of a degree away from it per decade.
+const MIN_INCLINATION_DEG: f64 = 0.05;
+/// Coarse steps per orbit when scanning for a sign change.
+const SCAN_STEPS: usize = 360;
+
+/// How far ahead the rare-event search looks.
+pub const SEARCH_YEARS: f64 = 50.0;
+/// Sampling step for the search; minima between samples are refined afterwards.
+const SEARCH_STEP_DAYS: f64 = 0.5;
+/// Geocentric separations below this are reported as appulses.
+const APPULSE_LIMIT_ARCMIN: f64 = 1.0;
+/// Sampled minima closer than this are worth refining.
+const REFINE_BELOW_DEG: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "net", derive(ToSchema))]
+#[serde(rename_all = "kebab-case")]
+pub enum EventKind {
+    AscendingNode,
+    DescendingNode,
+    /// Two planets within [`APPULSE_LIMIT_ARCMIN`] as seen from Earth.
+    Appulse,
+    /// One planet's disc passes in front of another's.
+    Occultation,
+}
+
+impl EventKind {
+    pub fn symbol(self) -> char {
+        match self {
+            EventKind::AscendingNode => '☊',
+            EventKind::DescendingNode => '☋',
+            EventKind::Appulse => '☌',
+            EventKind::Occultation => '●',
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EventKind::AscendingNode => "asc. node",
+            EventKind::DescendingNode => "desc. node",
+            EventKind::Appulse => "appulse",
+            EventKind::Occultation => "occultation",
+        }
+    }
+
+    /// Found by the on-demand search rather than recomputed every refresh.
+    pub fn is_search_result(self) -> bool {
+        matches!(self, EventKind::Appulse | EventKind::Occultation)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "net", derive(ToSchema))]
+pub struct OrbitEvent {
+    pub body: &'static str,
+    pub kind: EventKind,
+    pub utc: DateTime<Utc>,
+    /// Heliocentric position at the event, AU.
+    pub pos_au: Vec3,
+    /// The farther planet, for events involving two.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub other: Option<&'static str>,
+    /// Geocentric separation of the pair's centres, arcminutes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub separation_arcmin: Option<f64>,
+}
+
+/// One row of the events panel: a predicted event or a marker of the user's.
+#[derive(Debug, Clone, Copy)]
+pub enum TimelineEntry<'a> {
+    Event(&'a OrbitEvent),
+    Marker(&'a Marker),
+}
+
+impl TimelineEntry<'_> {
+    pub fn utc(&self) -> DateTime<Utc> {
+        match self {
+            TimelineEntry::Event(e) => e.utc,
+            TimelineEntry::Marker(m) => m.utc,
+        }
+    }
+}
+
+/// Events and markers together, earliest first.
+pub fn timeline(state: &DataState) -> Vec<TimelineEntry<'_>> {
+    let events = state.events.iter().map(TimelineEntry::Event);
+    let mut entries: Vec<_> = events.chain(state.markers.iter().map(TimelineEntry::Marker)).collect();
+    entries.sort_by_key(|e| e.utc());
+    entries
+}
+
+/// An epoch jumped to from the events panel, shown until Esc.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoTo {
+    pub utc: DateTime<Utc>,
+    /// For the header, e.g. "Mars asc. node" or "\"flyby\"".
+    pub label: String,
+    /// Highlighted in the table and on the map.
+    pub bodies: Vec<&'static str>,
+}
+
+/// Time-lapse speeds stepped through with `<` and `>`, in simulated seconds per second.
+pub const PLAYBACK_SPEEDS: [u64; 7] = [1, 10, 100, 1_000, 10_000, 100_000, 1_000_000];
+
+/// Time-lapse playback: a simulated clock run at a multiple of real time from the epoch on
+/// screen. Every frame shows it as a go-to, by local propagation, so no frame waits on the
+/// network however fast the clock runs.
+#[derive(Debug, Clone)]
+pub struct Playback {
+    pub utc: DateTime<Utc>,
+    pub speed: usize, // index into `PLAYBACK_SPEEDS`
+    pub paused: bool,
+    /// A prefetched range to stay within: playback pauses at its end.
+    pub bounds: Option<(DateTime<Utc>, DateTime<Utc>)>,
+    ticked: Instant,
+}
+
+impl Playback {
+    /// Starts at 100,000×, about a day per second.
+    pub fn new(utc: DateTime<Utc>) -> Self {
+        Playback { utc, speed: 5, paused: false, bounds: None, ticked: Instant::now() }
+    }
+
+    /// Paused at the start of a prefetched range.
+    pub fn over(from: DateTime<Utc>, to: DateTime<Utc>) -> Self {
+        Playback { paused: true, bounds: Some((from, to)), ..Playback::new(from) }
+    }
+
+    /// Moves the clock on by the real time since the last tick, unless paused.
+    pub fn tick(&mut self, now: Instant) {
+        let real = now.saturating_duration_since(self.ticked);
+        self.ticked = now;
+        if !self.paused {
+            let ms = real.as_secs_f64() * 1000.0 * PLAYBACK_SPEEDS[self.speed] as f64;
+            self.step(ChronoDuration::milliseconds(ms as i64));
+        }
+    }
+
+    /// Moves the clock by `by`, stopping at the ends of the range if there is one.
+    pub fn step(&mut self, by: ChronoDuration) {
+        self.utc += by;
+        if let Some((from, to)) = self.bounds {
+            if self.utc >= to {
+                self.paused = true;
+            }
+            self.utc = self.utc.clamp(from, to);
+        }
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed + 1).min(PLAYBACK_SPEEDS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+
+    /// The speed with thousands separators, e.g. "100,000×".
+    pub fn speed_label(&self) -> String {
+        let digits = PLAYBACK_SPEEDS[self.speed].to_string();
+        let mut out = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push(',');
+            }
+            out.push(c);
+        }
+        out + "×"
+    }
+
+    /// The frame to draw, through the go-to path.
+    pub fn goto(&self) -> GoTo {
+        let state = if self.paused { "paused" } else { "playing" };
+        let label = format!("{} UTC, {} at {}", self.utc.format("%Y-%m-%d %H:%M"), state, self.speed_label());
+        GoTo { utc: self.utc, label, bodies: Vec::new() }
+    }
+}
+
+/// Next ascending and descending node passages within one orbit of `epoch`, found where the
+/// propagated ecliptic Z changes sign and refined by bisection.
+pub fn node_passages(body: &'static str, p: Vec3, v: Vec3, epoch: DateTime<Utc>) -> Vec<OrbitEvent> {
+    let el = state_to_elements(p, v, GM_SUN_AU3_D2);
+    let bound = el.e < 1.0 && el.a_au > 0.0; // false for NaN elements too
+    if !bound || el.i_deg < MIN_INCLINATION_DEG {
+        return Vec::new();
+    }
+    let period_days = std::f64::consts::TAU / (GM_SUN_AU3_D2 / el.a_au.powi(3)).sqrt();
+    let dt = period_days / SCAN_STEPS as f64;
+    let z_at = |t: f64| propagate(p, v, t, GM_SUN_AU3_D2).map_or(0.0, |(q, _)| q.z);
+
+    let mut events = Vec::new();
+    // The last sample off the ecliptic, so a sample landing exactly on a node still brackets it
+    let (mut prev_t, mut prev) = (0.0, p.z);
+    for k in 1..=SCAN_STEPS {
+        let t = k as f64 * dt;
+        let z = z_at(t);
+        if z == 0.0 {
+            continue;
+        }
+        if prev != 0.0 && (prev < 0.0) != (z < 0.0) {
+            let (mut lo, mut hi) = (prev_t, t);
+            for _ in 0..40 {
+                let mid = 0.5 * (lo + hi);
+                if (z_at(mid) < 0.0) == (prev < 0.0) { lo = mid } else { hi = mid }
+            }
+            let t_node = 0.5 * (lo + hi);
+            let Some((pos, _)) = propagate(p, v, t_node, GM_SUN_AU3_D2) else { break };
+            events.push(OrbitEvent {
+                body,
+                kind: if prev < 0.0 { EventKind::AscendingNode } else { EventKind::DescendingNode },
+                utc: epoch + ChronoDuration::seconds((t_node * 86_400.0) as i64),
+                pos_au: pos,
+                other: None,
+                separation_arcmin: None,
+            });
+            if events.len() == 2 {
+                break;
+            }
+        }
+        (prev_t, prev) = (t, z);
+    }
+    events
+}
+
+/// Next opposition of a body outside Earth's orbit: when Earth overtakes it in heliocentric
+/// longitude, searched over one synodic period by two-body propagation of both. `None` for
+/// bodies inside Earth's orbit and for open orbits.
+pub fn next_opposition(earth: (Vec3, Vec3), body: (Vec3, Vec3), epoch: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let a_earth = state_to_elements(earth.0, earth.1, GM_SUN_AU3_D2).a_au;
+    let el = state_to_elements(body.0, body.1, GM_SUN_AU3_D2);
+    if !(el.e < 1.0 && el.a_au > a_earth && a_earth > 0.0) {
+        return None;
+    }
+    let synodic_days = 365.25 / (1.0 - (a_earth / el.a_au).powf(1.5));
+
+    // Signed ecliptic longitude of Earth ahead of the body; crosses zero upward at opposition
+    let lead = |t: f64| -> Option<(f64, f64)> {
+        let (e, _) = propagate(earth.0, earth.1, t, GM_SUN_AU3_D2)?;
+        let (b, _) = propagate(body.0, body.1, t, GM_SUN_AU3_D2)?;
+        Some(((b.x * e.y - b.y * e.x).atan2(b.x * e.x + b.y * e.y), b.x * e.x + b.y * e.y))
+    };
+    let steps = synodic_days.ceil() as usize + 10;
+    let (mut prev, _) = lead(0.0)?;
+    for k in 1..=steps {
+        let t = k as f64;
+        let (d, facing) = lead(t)?;
+        if prev < 0.0 && d >= 0.0 && facing > 0.0 {
+            let (mut lo, mut hi) = (t - 1.0, t);
+            for _ in 0..30 {
+                let mid = 0.5 * (lo + hi);
+                if lead(mid)?.0 < 0.0 { lo = mid } else { hi = mid }
+            }
+            return Some(epoch + ChronoDuration::seconds((0.5 * (lo + hi) * 86_400.0) as i64));
+        }
+        prev = d;
+    }
+    None
+}
+
+/// Heliocentric positions of every body `t` days after the epoch.
+fn positions_at(bodies: &[(&'static str, Vec3, Vec3)], t: f64) -> Vec<Vec3> {
+    bodies.iter().map(|&b| position_at(b, t)).collect()
+}
+
+fn position_at((_, p, v): (&'static str, Vec3, Vec3), t: f64) -> Vec3 {
+    propagate(p, v, t, GM_SUN_AU3_D2).map_or(p, |(q, _)| q)
+}
+
+/// Angle between two directions, degrees.
+fn separation_deg(a: Vec3, b: Vec3) -> f64 {
+    a.cross(b).norm().atan2(a.dot(b)).to_degrees()
+}
+
+/// Long-range search for mutual occultations and sub-arcminute appulses between planets as
+/// seen from Earth, `years` ahead of `epoch`. Two-body propagation drifts by up to a few
+/// arcminutes per decade for the outer planets, so results are candidates to confirm against
+/// Horizons. `progress` gets the completed fraction every few percent.
+pub fn search_conjunctions(
+    bodies: &[(&'static str, Vec3, Vec3)],
+    epoch: DateTime<Utc>,
+    years: f64,
+    mut progress: impl FnMut(f64),
+) -> Vec<OrbitEvent> {
+    let is_earth = |name: &str| meta_by_name(name).is_some_and(|m| m.id == BodyId::EARTH);
+    let Some(earth) = bodies.iter().position(|b| is_earth(b.0)) else { return Vec::new() };
+    let planets: Vec<usize> = (0..bodies.len()).filter(|&i| i != earth && bodies[i].1.norm() > 0.0).collect();
+    let pairs: Vec<(usize, usize)> = planets
+        .iter()
+        .enumerate()
+        .flat_map(|(k, &i)| planets[k + 1..].iter().map(move |&j| (i, j)))
+        .collect();
+
+    // Geocentric directions of a pair, propagating only the three bodies involved.
+    let pair_directions = |t: f64, (i, j): (usize, usize)| {
+        let e = position_at(bodies[earth], t);
+        (position_at(bodies[i], t) - e, position_at(bodies[j], t) - e)
+    };
+    let pair_separation = |t: f64, pair: (usize, usize)| {
+        let (gi, gj) = pair_directions(t, pair);
+        separation_deg(gi, gj)
+    };
+
+    let steps = (years * 365.25 / SEARCH_STEP_DAYS) as usize;
+    let mut window: Vec<[f64; 3]> = vec![[f64::INFINITY; 3]; pairs.len()];
+    let mut events = Vec::new();
+
+    for k in 0..=steps {
+        if k % (steps / 50).max(1) == 0 {
+            progress(k as f64 / steps as f64);
+        }
+        let t = k as f64 * SEARCH_STEP_DAYS;
+        let pos = positions_at(bodies, t);
+        for (n, &(i, j)) in pairs.iter().enumerate() {
+            let sep = separation_deg(pos[i] - pos[earth], pos[j] - pos[earth]);
+            let w = &mut window[n];
+            *w = [w[1], w[2], sep];
+            if !(w[1] < w[0] && w[1] <= w[2] && w[1] < REFINE_BELOW_DEG) {
+                continue;
+            }
+
+            // Ternary search for the true minimum around the middle sample.
+            let (mut lo, mut hi) = (t - 2.0 * SEARCH_STEP_DAYS, t);
+            for _ in 0..60 {
+                let (m1, m2) = (lo + (hi - lo) / 3.0, hi - (hi - lo) / 3.0);
+                if pair_separation(m1, (i, j)) < pair_separation(m2, (i, j)) { hi = m2 } else { lo = m1 }
+            }
+            let t_min = 0.5 * (lo + hi);
+            let (gi, gj) = pair_directions(t_min, (i, j));
+            let sep_arcmin = separation_deg(gi, gj) * 60.0;
+
+            // Angular radii decide between an occultation and a near miss.
+            let radius_arcmin = |name: &str, d: Vec3| {
+                meta_by_name(name).map_or(0.0, |m| (m.radius_km / (d.norm() * AU_KM)).atan().to_degrees() * 60.0)
+            };
+            let (near, far) = if gi.norm() < gj.norm() { (i, j) } else { (j, i) };
+            let discs = radius_arcmin(bodies[i].0, gi) + radius_arcmin(bodies[j].0, gj);
+            let kind = if sep_arcmin < discs {
+                EventKind::Occultation
+            } else if sep_arcmin < APPULSE_LIMIT_ARCMIN {
+                EventKind::Appulse
+            } else {
+                continue;
+            };
+            events.push(OrbitEvent {
+                body: bodies[near].0,
+                kind,
+                utc: epoch + ChronoDuration::seconds((t_min * 86_400.0) as i64),
+                pos_au: position_at(bodies[near], t_min),
+                other: Some(bodies[far].0),
+                separation_arcmin: Some(sep_arcmin),
+            });
+        }
+    }
+    progress(1.0);
+    events
+}
+
+/// Writes events as CSV (UTC, bodies, kind, separation, heliocentric position).
+pub fn export_csv(events: &[OrbitEvent], path: &Path) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut out = std::io::BufWriter::new(std::fs::File::create(path)?);
+    writeln!(out, "utc,body,other,event,separation_arcmin,x_au,y_au,z_au")?;
+    for e in events {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{}",
+            e.utc.to_rfc3339(),
+            e.body,
+            e.other.unwrap_or(""),
+            e.kind.label(),
+            e.separation_arcmin.map(|s| format!("{:.3}", s)).unwrap_or_default(),
+            e.pos_au.x,
+            e.pos_au.y,
+            e.pos_au.z,
+        )?;
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Where `x` exports the events list.
+pub fn export_path() -> std::path::PathBuf {
+    dirs::data_dir().unwrap_or_else(std::env::temp_dir).join("solar-rs").join("events.csv")
+}
+
+/// Epoch of the current vectors, if there has been a refresh.
+pub fn epoch(state: &DataState) -> Option<DateTime<Utc>> {
+    state
+        .last_update_utc
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// A time-travel epoch as typed: `2061-07-28`, `1969-07-20 20:17` or a full RFC 3339 time,
+/// all UTC.
+pub fn parse_epoch(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if let Ok(t) = DateTime::parse_from_rfc3339(text) {
+        return Some(t.with_timezone(&Utc));
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(text, f).ok())
+        .or_else(|| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok().and_then(|d| d.and_hms_opt(0, 0, 0)))
+        .map(|t| t.and_utc())
+}
+
+/// Bodies with both position and velocity, for propagation. Moons and spacecraft are left
+/// out: two-body motion about the Sun doesn't hold for moons, and spacecraft manoeuvre.
+pub fn fetched_states(state: &DataState) -> Vec<(&'static str, Vec3, Vec3)> {
+    state
+        .bodies
+        .iter()
+        .filter(|b| meta_by_id(b.id).is_none_or(|m| m.parent.is_none() && m.class != BodyClass::Spacecraft))
+        .filter_map(|b| Some((b.name, b.pos_au?, b.vel_au_d?)))
+        .collect()
+}
+
+/// Moves a snapshot of the state to `utc`, for the events panel's go-to: every body's vectors
+/// propagated from the current epoch by two-body motion about the Sun, and moons carried along
+/// at their present offset from their planet. Bodies on open orbits drop out of the table and
+/// map. A preview only; nothing is written back to the shared state.
+pub fn shift_to(state: &mut DataState, utc: DateTime<Utc>) {
+    let Some(from) = epoch(state) else { return };
+    let dt_days = (utc - from).num_seconds() as f64 / 86400.0;
+    let before: Vec<(BodyId, Option<Vec3>, Option<Vec3>)> = state.bodies.iter().map(|b| (b.id, b.pos_au, b.vel_au_d)).collect();
+    let parent_of = |id: BodyId| meta_by_id(id).and_then(|m| m.parent);
+
+    for b in state.bodies.iter_mut().filter(|b| b.id != BodyId::SUN && parent_of(b.id).is_none()) {
+        let moved = b.pos_au.zip(b.vel_au_d).and_then(|(p, v)| propagate(p, v, dt_days, GM_SUN_AU3_D2));
+        (b.pos_au, b.vel_au_d) = (moved.map(|(p, _)| p), moved.map(|(_, v)| v));
+    }
+    let after: Vec<(BodyId, Option<Vec3>, Option<Vec3>)> = state.bodies.iter().map(|b| (b.id, b.pos_au, b.vel_au_d)).collect();
+    let find = |list: &[(BodyId, Option<Vec3>, Option<Vec3>)], id| list.iter().find(|(i, ..)| *i == id).map(|&(_, p, v)| (p, v));
+    for (b, &(_, p, v)) in state.bodies.iter_mut().zip(&before) {
+        let Some(parent) = parent_of(b.id) else { continue };
+        let (Some((p0, v0)), Some((p1, v1))) = (find(&before, parent), find(&after, parent)) else {
+            (b.pos_au, b.vel_au_d) = (None, None);
+            continue;
+        };
+        b.pos_au = p.zip(p0).zip(p1).map(|((p, p0), p1)| p1 + (p - p0));
+        b.vel_au_d = v.zip(v0).zip(v1).map(|((v, v0), v1)| v1 + (v - v0));
+    }
+    state.last_update_utc = Some(utc.to_rfc3339_opts(SecondsFormat::Secs, true));
+}
+
+/// Where body `i` will be over the `days` after the epoch on screen, as `samples` evenly
+/// spaced points: from the fetched range where it covers them, else by two-body propagation
+/// about the Sun. Moons, which that can't move, only get the part a range covers.
+pub fn predicted_path(state: &DataState, i: usize, days: f64, samples: usize) -> Vec<Vec3> {
+    let (Some(b), Some(from)) = (state.bodies.get(i), epoch(state)) else { return Vec::new() };
+    let track = state.range.as_ref().and_then(|r| r.tracks.get(&b.id));
+    path_from(b, track, from, days, samples)
+}
+
+fn path_from(b: &BodyState, track: Option<&Trajectory>, from: DateTime<Utc>, days: f64, samples: usize) -> Vec<Vec3> {
+    let orbits_sun = b.id != BodyId::SUN && meta_by_id(b.id).is_none_or(|m| m.parent.is_none());
+    (1..=samples)
+        .filter_map(|k| {
+            let dt_days = days * k as f64 / samples as f64;
+            let utc = from + ChronoDuration::milliseconds((dt_days * 86_400_000.0) as i64);
+            let fetched = track.and_then(|t| t.state_at(utc));
+            let propagated = || b.pos_au.zip(b.vel_au_d).filter(|_| orbits_sun).and_then(|(p, v)| propagate(p, v, dt_days, GM_SUN_AU3_D2));
+            fetched.or_else(propagated).map(|(p, _)| p)
+        })
+        .collect()
+}
+
+/// Recomputes the node passages in `state.events` from the current vectors, keeping search
+/// results; soonest first. Call after each refresh.
+pub fn refresh(state: &mut DataState) {
+    let Some(epoch) = epoch(state) else { return };
+
+    let nodes = fetched_states(state)
+        .into_iter()
+        .filter(|(_, p, _)| p.norm() > 0.0)
+        .flat_map(|(name, p, v)| node_passages(name, p, v, epoch));
+    state.events.retain(|e| e.kind.is_search_result());
+    state.events.extend(nodes);
+    state.events.sort_by_key(|e| e.utc);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn playback_runs_at_its_speed() {
+        let start = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        let mut p = Playback::new(start);
+        let t0 = p.ticked;
+        p.tick(t0 + std::time::Duration::from_secs(2));
+        assert_eq!(p.utc, start + ChronoDuration::seconds(200_000));
+        p.paused = true;
+        p.tick(t0 + std::time::Duration::from_secs(60));
+        assert_eq!(p.utc, start + ChronoDuration::seconds(200_000));
+        p.faster();
+        p.faster();
+        assert_eq!(p.speed_label(), "1,000,000×");
+        (0..9).for_each(|_| p.slower());
+        assert_eq!(p.speed_label(), "1×");
+    }
+
+    #[test]
+    fn predicted_path_follows_the_orbit() {
+        // A circular orbit at 1 AU, a quarter of the way round in a quarter period
+        let v = (GM_SUN_AU3_D2).sqrt();
+        let period = std::f64::consts::TAU / v;
+        let earth = BodyState { name: "Earth", id: BodyId(399), pos_au: Some(Vec3 { x: 1.0, y: 0.0, z: 0.0 }), vel_au_d: Some(Vec3 { x: 0.0, y: v, z: 0.0 }), source: None, updated: None, trail: Default::default() };
+        let start = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        let path = path_from(&earth, None, start, period / 4.0, 30);
+        assert_eq!(path.len(), 30);
+        assert!((path[29] - Vec3 { x: 0.0, y: 1.0, z: 0.0 }).norm() < 1e-6);
+        // Without a fetched range a moon has no path
+        let moon = BodyState { name: "Moon", id: BodyId(301), ..earth };
+        assert!(path_from(&moon, None, start, 30.0, 30).is_empty());
+    }
+
+    #[test]
+    fn playback_stops_at_the_end_of_its_range() {
+        let at = |d| Utc.with_ymd_and_hms(2025, 1, d, 0, 0, 0).unwrap();
+        let mut p = Playback::over(at(1), at(3));
+        p.paused = false;
+        p.step(ChronoDuration::days(5));
+        assert_eq!(p.utc, at(3));
+        assert!(p.paused);
+        p.step(ChronoDuration::days(-9));
+        assert_eq!(p.utc, at(1));
+    }
+
+    #[test]
+    fn opposition_when_earth_catches_up() {
+        let circular = |r: f64, deg: f64| {
+            let (s, c) = deg.to_radians().sin_cos();
+            let speed = (GM_SUN_AU3_D2 / r).sqrt();
+            (Vec3 { x: r * c, y: r * s, z: 0.0 }, Vec3 { x: -speed * s, y: speed * c, z: 0.0 })
+        };
+        let epoch = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        // Earth gains 0.9856° − 0.5240° a day on a body at 1.524 AU, 30° ahead: 65 days
+        let at = next_opposition(circular(1.0, 0.0), circular(1.524, 30.0), epoch).unwrap();
+        assert!(((at - epoch).num_hours() as f64 / 24.0 - 65.0).abs() < 1.0, "{}", at);
+        assert_eq!(next_opposition(circular(1.0, 0.0), circular(0.72, 30.0), epoch), None);
+    }
+
+    #[test]
+    fn nodes_of_an_inclined_circular_orbit() {
+        // 1 AU, inclined 10° with the ascending node on +X, 30° past it at the epoch
+        let (i, u) = (10f64.to_radians(), 30f64.to_radians());
+        let speed = GM_SUN_AU3_D2.sqrt();
+        let p = Vec3 { x: u.cos(), y: u.sin() * i.cos(), z: u.sin() * i.sin() };
+        let v = Vec3 { x: -speed * u.sin(), y: speed * u.cos() * i.cos(), z: speed * u.cos() * i.sin() };
+        let epoch = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        let period_days = std::f64::consts::TAU / speed;
+
+        let nodes = node_passages("Test", p, v, epoch);
+        assert_eq!(nodes.len(), 2, "{:?}", nodes);
+        for (node, kind, degrees, x) in [(&nodes[0], EventKind::DescendingNode, 150.0, -1.0), (&nodes[1], EventKind::AscendingNode, 330.0, 1.0)] {
+            assert_eq!(node.kind, kind);
+            let expected = epoch + ChronoDuration::seconds((degrees / 360.0 * period_days * 86_400.0) as i64);
+            assert!((node.utc - expected).num_seconds().abs() < 60, "{:?} at {}, expected {}", kind, node.utc, expected);
+            assert!((node.pos_au.x - x).abs() < 1e-6 && node.pos_au.y.abs() < 1e-6 && node.pos_au.z.abs() < 1e-9, "{:?}", node.pos_au);
+        }
+    }
+
+    #[test]
+    fn appulse_at_a_known_alignment() {
+        // Earth, Jupiter and Saturn in line along +X 50 days on, Saturn 0.8' above the line
+        // as seen from Earth: outside the two discs (about 0.57'), so an appulse. The line
+        // through them crosses Earth's orbit again 100 days later, past the search.
+        let offset_au = (0.8f64 / 60.0).to_radians() * 9.0;
+        let at_alignment = |name: &'static str, p: Vec3| {
+            let v = Vec3 { x: 0.0, y: (GM_SUN_AU3_D2 / p.norm()).sqrt(), z: 0.0 };
+            let (p, v) = propagate(p, v, -50.0, GM_SUN_AU3_D2).unwrap();
+            (name, p, v)
+        };
+        let bodies = [
+            at_alignment("Earth", Vec3 { x: 1.0, y: 0.0, z: 0.0 }),
+            at_alignment("Jupiter", Vec3 { x: 5.0, y: 0.0, z: 0.0 }),
+            at_alignment("Saturn", Vec3 { x: 10.0, y: 0.0, z: offset_au }),
+        ];
+        let epoch = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+
+        let events = search_conjunctions(&bodies, epoch, 0.25, |_| {});
+        assert_eq!(events.len(), 1, "{:?}", events);
+        let e = &events[0];
+        assert_eq!((e.kind, e.body, e.other), (EventKind::Appulse, "Jupiter", Some("Saturn")));
+        let expected = epoch + ChronoDuration::days(50);
+        assert!((e.utc - expected).num_minutes().abs() < 30, "{}", e.utc);
+        assert!(e.separation_arcmin.is_some_and(|s| (s - 0.8).abs() < 0.01), "{:?}", e.separation_arcmin);
+        assert!((e.pos_au - Vec3 { x: 5.0, y: 0.0, z: 0.0 }).norm() < 1e-3, "{:?}", e.pos_au);
+    }
+
+    #[test]
+    fn parse_epoch_forms() {
+        let landing = Utc.with_ymd_and_hms(1969, 7, 20, 20, 17, 0).unwrap();
+        assert_eq!(parse_epoch("1969-07-20 20:17"), Some(landing));
+        assert_eq!(parse_epoch(" 1969-07-20T20:17:00Z "), Some(landing));
+        assert_eq!(parse_epoch("2061-07-28"), Some(Utc.with_ymd_and_hms(2061, 7, 28, 0, 0, 0).unwrap()));
+        assert_eq!(parse_epoch("1969-07-32"), None);
+        assert_eq!(parse_epoch("now"), None);
+    }
+}
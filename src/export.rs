@@ -0,0 +1,200 @@
+//! `solar-rs export`: trajectories for external viewers, as CZML for Cesium globes or GeoJSON
+//! for GIS-style tools. Sampled through the configured source chain without fetching anything:
+//! Horizons answers from the response cache only, `cache` propagates the last cached vectors
+//! two-body past the end of their window, and the local ephemerides answer as usual.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration as ChronoDuration, SecondsFormat, Utc};
+use serde_json::{json, Value};
+
+use crate::frames::{ecliptic_to_equatorial, ra_dec_deg};
+use crate::horizons::ResponseCache;
+use crate::physics::AU_KM;
+use crate::sources::{self, Cached, Provenance, Source};
+use crate::types::{meta_by_id, BodyId, DataState, Vec3};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Czml,
+    GeoJson,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Result<Format> {
+        match name {
+            "czml" => Ok(Format::Czml),
+            "geojson" => Ok(Format::GeoJson),
+            _ => Err(anyhow!("unknown export format {:?} (czml or geojson)", name)),
+        }
+    }
+}
+
+/// One body's heliocentric ecliptic positions (AU), at the sample times it could be placed.
+#[derive(Debug, Clone)]
+pub struct Track {
+    pub name: &'static str,
+    pub id: BodyId,
+    pub samples: Vec<(DateTime<Utc>, Vec3)>,
+}
+
+/// Every body sampled every `step` from `start` to `stop`; bodies no source could place at any
+/// sample are left out.
+pub fn tracks(state: &DataState, responses: &ResponseCache, start: DateTime<Utc>, stop: DateTime<Utc>, step: ChronoDuration) -> Vec<Track> {
+    let times: Vec<DateTime<Utc>> = std::iter::successors(Some(start), |t| Some(*t + step).filter(|t| *t <= stop)).collect();
+    state
+        .bodies
+        .iter()
+        .map(|b| Track {
+            name: b.name,
+            id: b.id,
+            samples: times.iter().filter_map(|&t| Some((t, position(state, responses, b.name, b.id, t)?))).collect(),
+        })
+        .filter(|t| !t.samples.is_empty())
+        .collect()
+}
+
+/// The first source in the chain that places the body at `utc`, the way a refresh would.
+fn position(state: &DataState, responses: &ResponseCache, name: &'static str, id: BodyId, utc: DateTime<Utc>) -> Option<Vec3> {
+    if id == BodyId::SUN {
+        return Some(Vec3::ZERO);
+    }
+    let moon = meta_by_id(id).is_some_and(|m| m.parent.is_some());
+    let chain = state.source.map_or_else(|| state.config.sources.clone(), |src| vec![src]);
+    let overrides = state.config.overrides_for(name);
+    chain.into_iter().find_map(|source| match source {
+        Source::Horizons => responses.get(id, overrides, utc).map(|(p, _, _)| p),
+        // Two-body about the Sun doesn't hold for moons, as in the updater
+        Source::Cache if moon => None,
+        Source::Cache => {
+            let (row, fetched) = responses.last_row(id, overrides)?;
+            let cached = Cached { pos: row.pos, vel: row.vel, at: row.utc, provenance: Provenance { source: Source::Horizons, epoch: fetched } };
+            sources::from_cache(Some(cached), utc).ok().map(|(p, _, _)| p)
+        }
+        Source::Keplerian | Source::Vsop87 if moon => None,
+        local => sources::local_state(local, name, id, state.spk.as_deref(), utc).ok().map(|(p, _)| p),
+    })
+}
+
+/// Positions about Earth on the J2000 equator (AU), which both formats use: Cesium's inertial
+/// frame is Earth-centred ICRF, and RA/Dec is the sky map GIS tools can draw. Earth itself
+/// drops out, as do samples where Earth couldn't be placed.
+fn geocentric(tracks: &[Track]) -> Vec<Track> {
+    let Some(earth) = tracks.iter().find(|t| t.id == BodyId::EARTH) else { return Vec::new() };
+    tracks
+        .iter()
+        .filter(|t| t.id != BodyId::EARTH)
+        .map(|t| Track {
+            name: t.name,
+            id: t.id,
+            samples: t
+                .samples
+                .iter()
+                .filter_map(|&(utc, p)| {
+                    let (_, e) = earth.samples.iter().find(|(at, _)| *at == utc)?;
+                    Some((utc, ecliptic_to_equatorial(p - *e)))
+                })
+                .collect(),
+        })
+        .filter(|t| !t.samples.is_empty())
+        .collect()
+}
+
+fn iso(utc: DateTime<Utc>) -> String {
+    utc.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// A CZML document: a clock over the sampled interval and one packet per body, with its
+/// position in metres in Cesium's `INERTIAL` frame, a labelled point and a trailing path.
+pub fn czml(tracks: &[Track]) -> Value {
+    let tracks = geocentric(tracks);
+    let (Some(start), Some(stop)) = (
+        tracks.iter().filter_map(|t| t.samples.first()).map(|s| s.0).min(),
+        tracks.iter().filter_map(|t| t.samples.last()).map(|s| s.0).max(),
+    ) else {
+        return json!([{ "id": "document", "name": "solar-rs", "version": "1.0" }]);
+    };
+    let interval = format!("{}/{}", iso(start), iso(stop));
+    let mut packets = vec![json!({
+        "id": "document",
+        "name": "solar-rs",
+        "version": "1.0",
+        "clock": { "interval": interval, "currentTime": iso(start), "multiplier": 3600, "range": "LOOP_STOP", "step": "SYSTEM_CLOCK_MULTIPLIER" },
+    })];
+    for t in &tracks {
+        let cartesian: Vec<f64> = t
+            .samples
+            .iter()
+            .flat_map(|&(utc, p)| {
+                let m = p * (AU_KM * 1000.0);
+                [(utc - start).num_seconds() as f64, m.x, m.y, m.z]
+            })
+            .collect();
+        packets.push(json!({
+            "id": t.name,
+            "name": t.name,
+            "availability": format!("{}/{}", iso(t.samples[0].0), iso(t.samples[t.samples.len() - 1].0)),
+            "position": {
+                "epoch": iso(start),
+                "referenceFrame": "INERTIAL",
+                "interpolationAlgorithm": "LAGRANGE",
+                "interpolationDegree": 5,
+                "cartesian": cartesian,
+            },
+            "point": { "pixelSize": 6 },
+            "label": { "text": t.name, "pixelOffset": { "cartesian2": [8, 0] } },
+            "path": { "width": 1, "leadTime": 0, "trailTime": (stop - start).num_seconds() },
+        }));
+    }
+    Value::Array(packets)
+}
+
+/// A GeoJSON feature collection with one line per body across the sky as seen from Earth's
+/// centre: longitude is right ascension folded into ±180°, latitude is declination, and the
+/// sample times and distances ride along as properties.
+pub fn geojson(tracks: &[Track]) -> Value {
+    let features: Vec<Value> = geocentric(tracks)
+        .iter()
+        .map(|t| {
+            let coordinates: Vec<[f64; 2]> = t
+                .samples
+                .iter()
+                .map(|&(_, p)| {
+                    let (ra, dec) = ra_dec_deg(p);
+                    [if ra > 180.0 { ra - 360.0 } else { ra }, dec]
+                })
+                .collect();
+            json!({
+                "type": "Feature",
+                "geometry": { "type": "LineString", "coordinates": coordinates },
+                "properties": {
+                    "name": t.name,
+                    "times": t.samples.iter().map(|&(utc, _)| iso(utc)).collect::<Vec<_>>(),
+                    "distance_au": t.samples.iter().map(|&(_, p)| p.norm()).collect::<Vec<_>>(),
+                },
+            })
+        })
+        .collect();
+    json!({ "type": "FeatureCollection", "features": features })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn czml_positions_are_geocentric_metres() {
+        let t0 = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap();
+        let t1 = t0 + ChronoDuration::hours(1);
+        let earth = Track { name: "Earth", id: BodyId::EARTH, samples: vec![(t0, Vec3 { x: 1.0, y: 0.0, z: 0.0 }), (t1, Vec3 { x: 1.0, y: 0.0, z: 0.0 })] };
+        let sun = Track { name: "Sun", id: BodyId::SUN, samples: vec![(t0, Vec3::ZERO), (t1, Vec3::ZERO)] };
+        let doc = czml(&[earth, sun]);
+        let packets = doc.as_array().unwrap();
+        assert_eq!(packets.len(), 2, "document and the Sun; Earth is the origin");
+        let cartesian = packets[1]["position"]["cartesian"].as_array().unwrap();
+        assert_eq!(cartesian.len(), 8);
+        assert_eq!(cartesian[4].as_f64(), Some(3600.0));
+        assert!((cartesian[1].as_f64().unwrap() + AU_KM * 1000.0).abs() < 1e-3);
+        assert_eq!(packets[0]["clock"]["interval"], "2030-01-01T00:00:00Z/2030-01-01T01:00:00Z");
+    }
+}
@@ -0,0 +1,168 @@
+//! `solar-rs render-frame`: one frame of the app drawn off-screen from a state fixture and
+//! printed to stdout, as plain text or with ANSI colors, for golden-file tests in CI and for
+//! attaching an exact reproduction to a bug report. Needs no terminal.
+
+use anyhow::{anyhow, Result};
+use ratatui::{
+    backend::TestBackend,
+    buffer::Buffer,
+    style::{Color, Modifier},
+    text::Span,
+    Terminal,
+};
+
+use crate::ui::draw_ui;
+use solar_rs::snapshot::Snapshot;
+use solar_rs::types::{DataState, ViewState};
+use solar_rs::{events, names};
+
+/// `--size` as `<columns>x<rows>`.
+pub fn parse_size(text: &str) -> Result<(u16, u16)> {
+    let size = text.split_once('x').and_then(|(w, h)| Some((w.trim().parse().ok()?, h.trim().parse().ok()?)));
+    size.filter(|&(w, h): &(u16, u16)| w >= 20 && h >= 10).ok_or_else(|| anyhow!("--size: expected <columns>x<rows>, at least 20x10, got {:?}", text))
+}
+
+/// Puts `snap` into `data` in place of anything of this machine's: the journal, markers, pins
+/// and bandwidth record are left out, names are in English unless the config sets `language`,
+/// and the clock stops at the refresh, so the same fixture and config draw the same frame on
+/// any machine on any day.
+pub fn load(data: &mut DataState, view: &mut ViewState, snap: Snapshot) {
+    data.journal.clear();
+    data.markers.clear();
+    data.pins.clear();
+    data.bandwidth = Default::default();
+    snap.apply(data);
+    data.status = snap.status;
+    data.frozen_clock = events::epoch(data);
+    view.sync(data);
+    view.language = names::resolve_language(Some(data.config.language.as_deref().unwrap_or("en")));
+}
+
+/// The frame `draw_ui` makes of `data` and `view` at `size`.
+pub fn render(data: &DataState, view: &ViewState, (width, height): (u16, u16), ansi: bool) -> Result<String> {
+    let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+    terminal.draw(|f| {
+        draw_ui(f, data, view);
+    })?;
+    Ok(buffer_text(terminal.backend().buffer(), ansi))
+}
+
+/// A buffer as lines of text, with trailing spaces trimmed; with `ansi`, styled by SGR
+/// sequences that are reset at the end of each line.
+fn buffer_text(buf: &Buffer, ansi: bool) -> String {
+    let mut out = String::new();
+    for y in 0..buf.area.height {
+        let mut line = String::new();
+        let mut style = None;
+        let mut skip = 0;
+        for x in 0..buf.area.width {
+            // The cells under the right half of a wide glyph
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            let cell = &buf[(x, y)];
+            if ansi && style != Some((cell.fg, cell.bg, cell.modifier)) {
+                style = Some((cell.fg, cell.bg, cell.modifier));
+                line.push_str(&sgr(cell.fg, cell.bg, cell.modifier));
+            }
+            line.push_str(cell.symbol());
+            skip = Span::raw(cell.symbol()).width().saturating_sub(1);
+        }
+        if ansi {
+            line.push_str("\x1b[0m");
+        } else {
+            line.truncate(line.trim_end().len());
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+    out
+}
+
+fn sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+    for (flag, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::SLOW_BLINK, "5"),
+        (Modifier::RAPID_BLINK, "6"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::HIDDEN, "8"),
+        (Modifier::CROSSED_OUT, "9"),
+    ] {
+        if modifier.contains(flag) {
+            codes.push(code.into());
+        }
+    }
+    codes.extend(color_code(fg, false));
+    codes.extend(color_code(bg, true));
+    format!("\x1b[{}m", codes.join(";"))
+}
+
+/// The SGR parameters for a foreground or background color; none for the default.
+fn color_code(color: Color, bg: bool) -> Option<String> {
+    let base = |n: u8| Some((if bg { n + 10 } else { n }).to_string());
+    match color {
+        Color::Reset => None,
+        Color::Black => base(30),
+        Color::Red => base(31),
+        Color::Green => base(32),
+        Color::Yellow => base(33),
+        Color::Blue => base(34),
+        Color::Magenta => base(35),
+        Color::Cyan => base(36),
+        Color::Gray => base(37),
+        Color::DarkGray => base(90),
+        Color::LightRed => base(91),
+        Color::LightGreen => base(92),
+        Color::LightYellow => base(93),
+        Color::LightBlue => base(94),
+        Color::LightMagenta => base(95),
+        Color::LightCyan => base(96),
+        Color::White => base(97),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{};{};{}", if bg { 48 } else { 38 }, r, g, b)),
+        Color::Indexed(i) => Some(format!("{};5;{}", if bg { 48 } else { 38 }, i)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+    use solar_rs::config::Config;
+    use std::{path::Path, sync::Arc};
+
+    #[test]
+    fn prints_text_and_styles() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 6, 2));
+        buf.set_string(0, 0, "☉ Sun", Style::default().fg(Color::Yellow));
+        buf.set_string(0, 1, "日x", Style::default().fg(Color::Rgb(1, 2, 3)).add_modifier(Modifier::BOLD));
+        assert_eq!(buffer_text(&buf, false), "☉ Sun\n日x\n");
+        assert_eq!(buffer_text(&buf, true), "\x1b[0;33m☉ Sun\x1b[0m \x1b[0m\n\x1b[0;1;38;2;1;2;3m日x\x1b[0m   \x1b[0m\n");
+        assert_eq!(parse_size("120x40").unwrap(), (120, 40));
+        assert!(parse_size("120").is_err() && parse_size("5x5").is_err());
+    }
+
+    /// `tests/fixtures/frame_state.json` at 120x40 against the frame checked in beside it.
+    /// After a deliberate change to the layout, regenerate it with `solar-rs render-frame
+    /// --config <empty file> --state tests/fixtures/frame_state.json > tests/fixtures/frame_120x40.txt`.
+    #[test]
+    fn draws_the_fixture_frame() {
+        let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let config = Arc::new(Config::default());
+        let mut data = DataState::new(config.clone(), config.body_states(&[]));
+        let mut view = ViewState::new(config, false);
+        load(&mut data, &mut view, Snapshot::read(&fixtures.join("frame_state.json")).unwrap());
+        let frame = render(&data, &view, (120, 40), false).unwrap();
+        // Whatever the golden file says, the table must still show whole names and values
+        for shown in ["Mercury", "Jupiter", "Neptune", "5.3095", "5.714 / 47m 31s"] {
+            assert!(frame.contains(shown), "{:?} missing from the frame:\n{}", shown, frame);
+        }
+        let expected = std::fs::read_to_string(fixtures.join("frame_120x40.txt")).unwrap();
+        assert!(frame == expected, "frame differs from frame_120x40.txt:\n{}", frame);
+    }
+}
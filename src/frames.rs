@@ -0,0 +1,180 @@
+use crate::physics::SPEED_OF_LIGHT_AU_D;
+use crate::types::Vec3;
+
+/// Mean obliquity of the ecliptic at J2000 (IAU 2006: 84381.406″; Horizons uses 84381.448″).
+pub const J2000_OBLIQUITY_DEG: f64 = 84_381.448 / 3600.0;
+
+/// Rotates a J2000 ecliptic vector into the J2000 (ICRF) equatorial frame.
+pub fn ecliptic_to_equatorial(v: Vec3) -> Vec3 {
+    let (s, c) = J2000_OBLIQUITY_DEG.to_radians().sin_cos();
+    Vec3 { x: v.x, y: c * v.y - s * v.z, z: s * v.y + c * v.z }
+}
+
+/// Inverse of [`ecliptic_to_equatorial`].
+pub fn equatorial_to_ecliptic(v: Vec3) -> Vec3 {
+    let (s, c) = J2000_OBLIQUITY_DEG.to_radians().sin_cos();
+    Vec3 { x: v.x, y: c * v.y + s * v.z, z: -s * v.y + c * v.z }
+}
+
+/// Rotates a galactic (l, b) vector into the ICRS equatorial frame, using the Hipparcos
+/// definition of the galactic pole and origin (transpose of ESA SP-1200 eq. 1.5.11).
+pub fn galactic_to_equatorial(v: Vec3) -> Vec3 {
+    Vec3 {
+        x: -0.054_875_560_416_215 * v.x + 0.494_109_427_875_584 * v.y - 0.867_666_149_019_005 * v.z,
+        y: -0.873_437_090_234_885 * v.x - 0.444_829_629_960_011 * v.y - 0.198_076_373_431_201 * v.z,
+        z: -0.483_835_015_548_713 * v.x + 0.746_982_244_497_219 * v.y + 0.455_983_776_175_067 * v.z,
+    }
+}
+
+/// Solar motion relative to the local standard of rest, galactic U, V, W in km/s
+/// (Schönrich, Binney & Dehnen 2010).
+pub const SOLAR_MOTION_UVW_KM_S: Vec3 = Vec3 { x: 11.1, y: 12.24, z: 7.25 };
+
+/// Unit ecliptic vector toward the solar apex, the direction the Sun moves among nearby stars.
+pub fn solar_apex() -> Vec3 {
+    let v = equatorial_to_ecliptic(galactic_to_equatorial(SOLAR_MOTION_UVW_KM_S));
+    v * (1.0 / v.norm())
+}
+
+/// Unit ecliptic vector toward the galactic centre (l = 0, b = 0).
+pub fn galactic_center() -> Vec3 {
+    equatorial_to_ecliptic(galactic_to_equatorial(Vec3 { x: 1.0, y: 0.0, z: 0.0 }))
+}
+
+/// Precesses a J2000 equatorial vector to the mean equator and equinox of date, `days` (TT)
+/// after J2000, with the IAU 2006 (P03) angles ζ, z, θ. The 23 mas frame bias between ICRS and
+/// the J2000 mean equator is ignored.
+pub fn precess_from_j2000(v: Vec3, days: f64) -> Vec3 {
+    let m = precession_matrix(days);
+    Vec3 {
+        x: m[0][0] * v.x + m[0][1] * v.y + m[0][2] * v.z,
+        y: m[1][0] * v.x + m[1][1] * v.y + m[1][2] * v.z,
+        z: m[2][0] * v.x + m[2][1] * v.y + m[2][2] * v.z,
+    }
+}
+
+/// The inverse of [`precess_from_j2000`]: mean equator of date back to J2000.
+pub fn precess_to_j2000(v: Vec3, days: f64) -> Vec3 {
+    let m = precession_matrix(days);
+    Vec3 {
+        x: m[0][0] * v.x + m[1][0] * v.y + m[2][0] * v.z,
+        y: m[0][1] * v.x + m[1][1] * v.y + m[2][1] * v.z,
+        z: m[0][2] * v.x + m[1][2] * v.y + m[2][2] * v.z,
+    }
+}
+
+fn precession_matrix(days: f64) -> [[f64; 3]; 3] {
+    let t = days / 36_525.0;
+    let (sz, cz) = arcsec_poly(&[2.650_545, 2_306.083_227, 0.298_849_9, 0.018_018_28, -0.000_005_971, -0.000_000_317_3], t)
+        .sin_cos(); // ζ
+    let (sx, cx) = arcsec_poly(&[-2.650_545, 2_306.077_181, 1.092_734_8, 0.018_268_37, -0.000_028_596, -0.000_000_290_4], t)
+        .sin_cos(); // z
+    let (st, ct) = arcsec_poly(&[0.0, 2_004.191_903, -0.429_493_4, -0.041_822_64, -0.000_007_089, -0.000_000_127_4], t)
+        .sin_cos(); // θ
+    [
+        [cz * cx * ct - sz * sx, -sz * cx * ct - cz * sx, -cx * st],
+        [cz * sx * ct + sz * cx, -sz * sx * ct + cz * cx, -sx * st],
+        [cz * st, -sz * st, ct],
+    ]
+}
+
+/// Polynomial in `t` with coefficients in arcseconds, as radians.
+fn arcsec_poly(coeffs: &[f64], t: f64) -> f64 {
+    (coeffs.iter().rev().fold(0.0, |acc, c| acc * t + c) / 3600.0).to_radians()
+}
+
+/// Mean obliquity of the ecliptic of date (IAU 2006), degrees.
+pub fn mean_obliquity_deg(days: f64) -> f64 {
+    let t = days / 36_525.0;
+    arcsec_poly(&[84_381.406, -46.836_769, -0.000_183_1, 0.002_003_40, -0.000_000_576, -0.000_000_043_4], t).to_degrees()
+}
+
+/// Leading terms of the IAU 2000B nutation series: multipliers of the Delaunay arguments
+/// (l, l′, F, D, Ω), then Δψ sin, its rate, Δψ cos, Δε cos, its rate, Δε sin, in 0.1 µas.
+/// The omitted terms are each below 0.06″; the sum stays within about 0.1″ of the full series.
+const NUTATION: [([i8; 5], [f64; 6]); 15] = [
+    ([0, 0, 0, 0, 1], [-172_064_161.0, -174_666.0, 33_386.0, 92_052_331.0, 9_086.0, 15_377.0]),
+    ([0, 0, 2, -2, 2], [-13_170_906.0, -1_675.0, -13_696.0, 5_730_336.0, -3_015.0, -4_587.0]),
+    ([0, 0, 2, 0, 2], [-2_276_413.0, -234.0, 2_796.0, 978_459.0, -485.0, 1_374.0]),
+    ([0, 0, 0, 0, 2], [2_074_554.0, 207.0, -698.0, -897_492.0, 470.0, -291.0]),
+    ([0, 1, 0, 0, 0], [1_475_877.0, -3_633.0, 11_817.0, 73_871.0, -184.0, -1_924.0]),
+    ([0, 1, 2, -2, 2], [-516_821.0, 1_226.0, -524.0, 224_386.0, -677.0, -174.0]),
+    ([1, 0, 0, 0, 0], [711_159.0, 73.0, -872.0, -6_750.0, 0.0, 358.0]),
+    ([0, 0, 2, 0, 1], [-387_298.0, -367.0, 380.0, 200_728.0, 18.0, 318.0]),
+    ([1, 0, 2, 0, 2], [-301_461.0, -36.0, 816.0, 129_025.0, -63.0, 367.0]),
+    ([0, -1, 2, -2, 2], [215_829.0, -494.0, 111.0, -95_929.0, 299.0, 132.0]),
+    ([0, 0, 2, -2, 1], [128_227.0, 137.0, 181.0, -68_982.0, -9.0, 39.0]),
+    ([-1, 0, 2, 0, 2], [123_457.0, 11.0, 19.0, -53_311.0, 32.0, -4.0]),
+    ([-1, 0, 0, 2, 0], [156_994.0, 10.0, -168.0, -1_235.0, 0.0, 82.0]),
+    ([1, 0, 0, 0, 1], [63_110.0, 63.0, 27.0, -33_228.0, 0.0, -9.0]),
+    ([-1, 0, 0, 0, 1], [-57_976.0, -63.0, -189.0, 31_429.0, 0.0, -75.0]),
+];
+
+/// Nutation in longitude and obliquity (Δψ, Δε), degrees, `days` (TT) after J2000.
+pub fn nutation_deg(days: f64) -> (f64, f64) {
+    let t = days / 36_525.0;
+    // Delaunay arguments (Simon et al. 1994, as used by IAU 2000B), arcseconds
+    let delaunay = [
+        arcsec_poly(&[485_868.249_036, 1_717_915_923.217_8], t),
+        arcsec_poly(&[1_287_104.793_05, 129_596_581.048_1], t),
+        arcsec_poly(&[335_779.526_232, 1_739_527_262.847_8], t),
+        arcsec_poly(&[1_072_260.703_69, 1_602_961_601.209], t),
+        arcsec_poly(&[450_160.398_036, -6_962_890.543_1], t),
+    ];
+    let (mut dpsi, mut deps) = (0.0, 0.0);
+    for (mult, [ps, pst, pc, ec, ect, es]) in NUTATION {
+        let arg: f64 = mult.iter().zip(delaunay).map(|(&m, a)| m as f64 * a).sum();
+        let (sa, ca) = arg.sin_cos();
+        dpsi += (ps + pst * t) * sa + pc * ca;
+        deps += (ec + ect * t) * ca + es * sa;
+    }
+    (dpsi * 1e-7 / 3600.0, deps * 1e-7 / 3600.0)
+}
+
+/// Rotates a J2000 equatorial vector to the true equator and equinox of date: IAU 2006
+/// precession followed by nutation. This is the frame telescope mounts expect for pointing.
+pub fn true_of_date(v: Vec3, days: f64) -> Vec3 {
+    let mean = precess_from_j2000(v, days);
+    let eps = mean_obliquity_deg(days).to_radians();
+    let (dpsi, deps) = nutation_deg(days);
+    // Mean equator → ecliptic of date, shift the equinox by Δψ, back to the true equator
+    let rot_x = |v: Vec3, a: f64| {
+        let (s, c) = a.sin_cos();
+        Vec3 { x: v.x, y: c * v.y + s * v.z, z: -s * v.y + c * v.z }
+    };
+    let ecl = rot_x(mean, eps);
+    let (s, c) = dpsi.to_radians().sin_cos();
+    let shifted = Vec3 { x: c * ecl.x - s * ecl.y, y: s * ecl.x + c * ecl.y, z: ecl.z };
+    rot_x(shifted, -(eps + deps.to_radians()))
+}
+
+/// Apparent offset of a body from an observer, given their geometric offset `d` and the
+/// heliocentric velocities of body and observer (AU, AU/day). Light-time moves the body back
+/// along its path to where it was when the light left it; annual aberration then tilts the
+/// direction by up to ~20″ toward the observer's motion (first order in v/c). The Sun is taken
+/// as inertial, so the Sun's own ~0.01 km/s barycentric wobble is ignored.
+pub fn apparent_offset(d: Vec3, body_vel: Vec3, observer_vel: Vec3) -> Vec3 {
+    if d.norm() == 0.0 {
+        return d;
+    }
+    let tau = d.norm() / SPEED_OF_LIGHT_AU_D;
+    let d = d - body_vel * tau;
+    let u = d * (1.0 / d.norm());
+    let beta = observer_vel * (1.0 / SPEED_OF_LIGHT_AU_D);
+    let tilted = u + beta - u * u.dot(beta);
+    tilted * (d.norm() / tilted.norm())
+}
+
+/// Right ascension and declination (degrees) of an equatorial direction vector.
+pub fn ra_dec_deg(v: Vec3) -> (f64, f64) {
+    let ra = v.y.atan2(v.x).to_degrees().rem_euclid(360.0);
+    let dec = (v.z / v.norm()).asin().to_degrees();
+    (ra, dec)
+}
+
+/// Ecliptic longitude and latitude (degrees) of an ecliptic direction vector.
+pub fn lon_lat_deg(v: Vec3) -> (f64, f64) {
+    let lon = v.y.atan2(v.x).to_degrees().rem_euclid(360.0);
+    let lat = (v.z / v.norm()).asin().to_degrees();
+    (lon, lat)
+}
@@ -0,0 +1,76 @@
+//! Net gravitational acceleration sampled on a coarse grid across the orbit map, for the field
+//! overlay (`G`): the pull a test particle would feel from the Sun and every positioned body
+//! with a known mass. Sampling runs on the blocking pool and the result is kept until the map
+//! window or the vectors change.
+
+use crate::physics::{GM_SUN_AU3_D2, SUN_MASS_KG};
+use crate::types::{meta_by_id, BodyId, BodyState, Vec3};
+
+/// Grid points on each side of the centre, enough to cover a wide terminal at any zoom.
+pub const HALF_STEPS: i32 = 32;
+
+/// What a sampled field covers: it is resampled when any of this changes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldWindow {
+    /// `DataState::last_update_utc` of the positions it was sampled from.
+    pub epoch: Option<String>,
+    pub centre: Vec3,
+    pub step_au: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct GravityField {
+    pub window: FieldWindow,
+    /// (sample point, acceleration in AU/day²), heliocentric ecliptic.
+    pub samples: Vec<(Vec3, Vec3)>,
+}
+
+/// (GM in AU³/day², position) of the Sun and of every body with a position and a mass; moons
+/// and spacecraft count too, the latter for nothing.
+pub fn attractors(bodies: &[BodyState]) -> Vec<(f64, Vec3)> {
+    let planets = bodies.iter().filter(|b| b.id != BodyId::SUN).filter_map(|b| {
+        let m = meta_by_id(b.id)?;
+        Some((GM_SUN_AU3_D2 * m.mass_kg / SUN_MASS_KG, b.pos_au?))
+    });
+    std::iter::once((GM_SUN_AU3_D2, Vec3::ZERO)).chain(planets).filter(|&(gm, _)| gm > 0.0).collect()
+}
+
+/// Σ GMᵢ (rᵢ − p) / |rᵢ − p|³; a point on top of a body skips that body.
+pub fn acceleration(p: Vec3, attractors: &[(f64, Vec3)]) -> Vec3 {
+    attractors.iter().fold(Vec3::ZERO, |a, &(gm, r)| {
+        let d = r - p;
+        let dist = d.norm();
+        if dist == 0.0 { a } else { a + d * (gm / (dist * dist * dist)) }
+    })
+}
+
+/// Samples the field on the (2·`HALF_STEPS` + 1)² grid around the window's centre.
+pub fn sample(window: FieldWindow, attractors: &[(f64, Vec3)]) -> GravityField {
+    let steps = -HALF_STEPS..=HALF_STEPS;
+    let samples = steps
+        .clone()
+        .flat_map(|j| steps.clone().map(move |i| (i, j)))
+        .map(|(i, j)| {
+            let p = window.centre + Vec3 { x: i as f64 * window.step_au, y: j as f64 * window.step_au, z: 0.0 };
+            (p, acceleration(p, attractors))
+        })
+        .collect();
+    GravityField { window, samples }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sun_pull_at_one_au() {
+        let a = acceleration(Vec3 { x: 1.0, y: 0.0, z: 0.0 }, &attractors(&[]));
+        assert!((a.x + GM_SUN_AU3_D2).abs() < 1e-15 && a.y == 0.0);
+
+        let window = FieldWindow { epoch: None, centre: Vec3::ZERO, step_au: 0.5 };
+        let field = sample(window, &attractors(&[]));
+        assert_eq!(field.samples.len(), ((2 * HALF_STEPS + 1) * (2 * HALF_STEPS + 1)) as usize);
+        // The Sun's own point has no direction
+        assert!(field.samples.iter().any(|(p, a)| p.norm() == 0.0 && a.norm() == 0.0));
+    }
+}
@@ -0,0 +1,143 @@
+//! `solar-rs grpc`: the same collector as `serve`, over gRPC for typed clients. `StreamStates`
+//! pushes every refresh to subscribers and `QueryState` answers one-off ephemeris queries; the
+//! interface is `proto/solar.proto`.
+
+use anyhow::Result;
+use futures::Stream;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tonic::{transport::Server, Request, Response, Status};
+
+use crate::daemon::shutdown_signal;
+use crate::share;
+use solar_rs::events::parse_epoch;
+use solar_rs::horizons::EphemerisClient;
+use solar_rs::names;
+use solar_rs::sources::{self, Source};
+use solar_rs::types::{all_bodies, DataState, Vec3};
+
+mod proto {
+    tonic::include_proto!("solar.v1");
+}
+
+use proto::solar_server::{Solar, SolarServer};
+use proto::{BodyState, StateQuery, StateUpdate, StreamStatesRequest};
+
+type Shared = Arc<Mutex<DataState>>;
+
+/// How often a stream checks the shared state for a new refresh.
+const POLL: Duration = Duration::from_secs(1);
+
+struct Service {
+    state: Shared,
+    /// For Horizons queries, created on the first; shares the app's response cache on disk.
+    client: tokio::sync::Mutex<Option<EphemerisClient>>,
+}
+
+pub async fn run(state: Shared, listen: &str) -> Result<()> {
+    share::start(state.clone()).await;
+
+    let service = Service { state, client: tokio::sync::Mutex::new(None) };
+    let addr = tokio::net::lookup_host(listen).await?.next().ok_or_else(|| anyhow::anyhow!("{}: no address", listen))?;
+    eprintln!("solar-rs grpc: listening on {}", addr);
+    Server::builder().add_service(SolarServer::new(service)).serve_with_shutdown(addr, shutdown_signal()).await?;
+    eprintln!("solar-rs grpc: shut down");
+    Ok(())
+}
+
+fn vec3(v: Vec3) -> proto::Vec3 {
+    proto::Vec3 { x: v.x, y: v.y, z: v.z }
+}
+
+/// The bodies `filter` names (all if empty) as of the latest refresh.
+fn update(s: &DataState, filter: &[String]) -> StateUpdate {
+    StateUpdate {
+        last_update_utc: s.last_update_utc.clone().unwrap_or_default(),
+        status: s.status.clone(),
+        bodies: s
+            .bodies
+            .iter()
+            .filter(|b| filter.is_empty() || filter.iter().any(|q| names::matches(b.name, b.id, q)))
+            .map(|b| BodyState {
+                name: b.name.to_string(),
+                id: b.id.to_string(),
+                pos_au: b.pos_au.map(vec3),
+                vel_au_d: b.vel_au_d.map(vec3),
+                source: b.source.map(|p| p.source.label().to_string()).unwrap_or_default(),
+            })
+            .collect(),
+    }
+}
+
+#[tonic::async_trait]
+impl Solar for Service {
+    type StreamStatesStream = Pin<Box<dyn Stream<Item = Result<StateUpdate, Status>> + Send>>;
+
+    async fn stream_states(&self, request: Request<StreamStatesRequest>) -> Result<Response<Self::StreamStatesStream>, Status> {
+        let filter = request.into_inner().bodies;
+        if let Some(q) = filter.iter().find(|q| !all_bodies().any(|m| names::matches(m.name, m.id, q))) {
+            return Err(Status::invalid_argument(format!("unknown body {:?}", q)));
+        }
+        // The current state at once, then one update per refresh
+        let state = self.state.clone();
+        let stream = futures::stream::unfold((state, filter, None), |(state, filter, mut sent)| async move {
+            loop {
+                let next = {
+                    let s = state.lock().unwrap();
+                    (sent.as_ref() != Some(&s.last_update_utc)).then(|| (s.last_update_utc.clone(), update(&s, &filter)))
+                };
+                match next {
+                    Some((refresh, u)) => {
+                        sent = Some(refresh);
+                        return Some((Ok(u), (state, filter, sent)));
+                    }
+                    None => tokio::time::sleep(POLL).await,
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn query_state(&self, request: Request<StateQuery>) -> Result<Response<BodyState>, Status> {
+        let query = request.into_inner();
+        let meta = all_bodies()
+            .find(|m| names::matches(m.name, m.id, &query.body))
+            .ok_or_else(|| Status::invalid_argument(format!("unknown body {:?}", query.body)))?;
+        let utc = match query.utc.as_str() {
+            "" => chrono::Utc::now(),
+            text => parse_epoch(text).ok_or_else(|| Status::invalid_argument(format!("not a UTC time: {:?}", text)))?,
+        };
+        let source = match query.source.as_str() {
+            "" => Source::Horizons,
+            name => Source::parse(name).map_err(|e| Status::invalid_argument(e.to_string()))?,
+        };
+
+        let (p, v) = match source {
+            Source::Horizons => {
+                let mut client = self.client.lock().await;
+                let client = match client.as_mut() {
+                    Some(c) => c,
+                    None => client.insert(EphemerisClient::new((*self.state.lock().unwrap().config).clone())),
+                };
+                let state = client.state_at(meta.id, utc).await;
+                client.save().map_err(|e| Status::internal(format!("{:#}", e)))?;
+                state
+            }
+            Source::Cache => return Err(Status::invalid_argument("the cache source only serves refreshes")),
+            local => {
+                let spk = self.state.lock().unwrap().spk.clone();
+                sources::local_state(local, meta.name, meta.id, spk.as_deref(), utc)
+            }
+        }
+        .map_err(|e| Status::unavailable(format!("{}: {:#}", meta.name, e)))?;
+
+        Ok(Response::new(BodyState {
+            name: meta.name.to_string(),
+            id: meta.id.to_string(),
+            pos_au: Some(vec3(p)),
+            vel_au_d: Some(vec3(v)),
+            source: source.label().to_string(),
+        }))
+    }
+}
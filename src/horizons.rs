@@ -1,122 +1,1544 @@
-use anyhow::{anyhow, Context, Result};
-use chrono::{Duration as ChronoDuration, SecondsFormat, Utc};
-use std::{collections::BTreeMap, sync::{Arc, Mutex}, time::Duration};
+use anyhow::Result;
+use chrono::Duration as ChronoDuration;
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 use tokio::time::sleep;
+#[cfg(feature = "net")]
 use url::Url;
 
-use crate::types::{AppState, HorizonsJson, Vec3};
+use crate::config::{Config, Origin, QueryOverrides};
+#[cfg(feature = "net")]
+use crate::config::{Observer, TimeScale};
+#[cfg(feature = "net")]
+use crate::dns;
+use crate::bandwidth;
+use crate::ephemeris;
+use crate::{events, trails};
+use crate::observer::SkyPosition;
+use crate::orbital_mechanics::{elements_to_state, propagate, state_to_elements, KeplerElements};
+use crate::physics::{self, GM_SUN_AU3_D2, TDB_MINUS_UTC_S};
+use crate::sources::{self, Cached, Provenance, Source};
+use crate::spk::Spk;
+use crate::types::{meta_by_id, AlertLevel, BodyClass, BodyId, BodyState, DataState, HorizonsJson, Vec3, BODIES};
+
+const J2000_JD: f64 = 2_451_545.0;
+
+/// Why a request to Horizons, or another of JPL's APIs, failed. The kinds call for different
+/// remedies: network trouble and a busy server are retried with backoff, while a response
+/// that doesn't parse or an error from the API itself would only fail the same way again.
+#[derive(Debug)]
+pub enum HorizonsError {
+    /// Name resolution, the connection or a timeout: the link rather than the server.
+    Network(String),
+    /// HTTP 429 or 503, with the `Retry-After` delay when the server gave one.
+    RateLimited { api: &'static str, status: u16, retry_after: Option<Duration> },
+    /// A response that isn't the expected JSON or table.
+    Parse(String),
+    /// The API turned the request down: any other HTTP error status, or Horizons' own `error`
+    /// field (an unknown target, a span outside the ephemeris).
+    Api { api: &'static str, status: Option<u16>, message: String },
+}
+
+impl HorizonsError {
+    fn parse(reason: impl fmt::Display) -> Self {
+        HorizonsError::Parse(reason.to_string())
+    }
+
+    /// Worth another try in a moment: the link, a busy server or a failure on its side.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            HorizonsError::Network(_) | HorizonsError::RateLimited { .. } => true,
+            HorizonsError::Api { status, .. } => status.is_some_and(|s| s >= 500),
+            HorizonsError::Parse(_) => false,
+        }
+    }
+}
+
+impl fmt::Display for HorizonsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HorizonsError::Network(reason) => write!(f, "{}", reason),
+            HorizonsError::RateLimited { api, status, .. } => write!(f, "{} is busy: HTTP {}", api, status),
+            HorizonsError::Parse(reason) => write!(f, "unreadable response: {}", reason),
+            HorizonsError::Api { api, status: Some(_), message } => write!(f, "{} API error: HTTP {}", api, message),
+            HorizonsError::Api { api, status: None, message } => write!(f, "{} error: {}", api, message),
+        }
+    }
+}
+
+impl std::error::Error for HorizonsError {}
+
+/// Vector query (`EPHEM_TYPE=VECTORS`); `start` and `stop` are times on `scale`.
+#[cfg(feature = "net")]
+pub fn build_horizons_url(
+    body_id: BodyId,
+    start: &str,
+    stop: &str,
+    overrides: Option<&QueryOverrides>,
+    scale: TimeScale,
+) -> Result<Url> {
+    let step_size = overrides.and_then(|o| o.step_size.as_deref()).unwrap_or("10 m");
+    let table = [("EPHEM_TYPE", "VECTORS"), ("VEC_TABLE", "2"), ("TIME_TYPE", scale.label())];
+    query_url(body_id, start, stop, step_size, heliocentric(overrides), overrides, &table)
+}
+
+/// Osculating-element query (`EPHEM_TYPE=ELEMENTS`), e.g. `step_size = "30 d"` for a history.
+#[cfg(feature = "net")]
+pub fn build_elements_url(
+    body_id: BodyId,
+    start_utc: &str,
+    stop_utc: &str,
+    step_size: &str,
+    overrides: Option<&QueryOverrides>,
+) -> Result<Url> {
+    query_url(body_id, start_utc, stop_utc, step_size, heliocentric(overrides), overrides, &[("EPHEM_TYPE", "ELEMENTS")])
+}
+
+/// Observer table (`EPHEM_TYPE=OBSERVER`) from a place on Earth: apparent RA/Dec of date and
+/// refracted azimuth/elevation in degrees, with Julian dates so rows time like vector rows.
+#[cfg(feature = "net")]
+pub fn build_observer_url(body_id: BodyId, observer: &Observer, start_utc: &str, stop_utc: &str) -> Result<Url> {
+    let site = format!("'{},{},{}'", observer.lon_deg, observer.lat_deg, observer.height_m / 1000.0);
+    let table = [
+        ("EPHEM_TYPE", "OBSERVER"),
+        ("COORD_TYPE", "GEODETIC"),
+        ("SITE_COORD", site.as_str()),
+        ("QUANTITIES", "'2,4'"),
+        ("ANG_FORMAT", "DEG"),
+        ("APPARENT", "REFRACTED"),
+        ("CAL_FORMAT", "JD"),
+    ];
+    query_url(body_id, start_utc, stop_utc, SKY_STEP, "coord@399", None, &table)
+}
+
+/// The Sun's centre unless the body's overrides name another.
+#[cfg(feature = "net")]
+fn heliocentric(overrides: Option<&QueryOverrides>) -> &str {
+    overrides.and_then(|o| o.center.as_deref()).unwrap_or("500@10")
+}
+
+#[cfg(feature = "net")]
+fn query_url(
+    body_id: BodyId,
+    start_utc: &str,
+    stop_utc: &str,
+    step_size: &str,
+    center: &str,
+    overrides: Option<&QueryOverrides>,
+    table: &[(&str, &str)],
+) -> Result<Url> {
+    let ref_plane = overrides.and_then(|o| o.ref_plane.as_deref()).unwrap_or("ECLIPTIC");
+    // Small bodies go by SPK id (Ceres is 2000001) in the small-body form, `DES=2000001;`;
+    // extra bodies by their configured command, used verbatim
+    let extra = crate::types::extra_bodies().into_iter().find(|e| e.meta.id == body_id).map(|e| e.command);
+    let small_body = body_id.0 >= 1_000_000;
+    let target = match extra {
+        Some(command) => command.trim_end_matches(';').to_string(),
+        None if small_body => format!("DES={}", body_id),
+        None => body_id.to_string(),
+    };
+    let command = match (overrides, extra) {
+        (Some(o), _) if !o.command_suffix.is_empty() => {
+            format!("'{};{}'", target, o.command_suffix.join(";"))
+        }
+        (_, Some(command)) => format!("'{}'", command),
+        _ if small_body => format!("'{};'", target),
+        _ => target,
+    };
 
-pub fn build_horizons_url(body_id: &str, start_utc: &str, stop_utc: &str) -> Result<Url> {
     let mut url = Url::parse("https://ssd.jpl.nasa.gov/api/horizons.api")?;
     {
         let mut qp = url.query_pairs_mut();
         qp.append_pair("format", "json");
         qp.append_pair("MAKE_EPHEM", "YES");
         qp.append_pair("OBJ_DATA", "NO");
-        qp.append_pair("EPHEM_TYPE", "VECTORS");
+        for (k, v) in table {
+            qp.append_pair(k, v);
+        }
 
-        qp.append_pair("COMMAND", body_id);
-        qp.append_pair("CENTER", "500@10");
-        qp.append_pair("REF_PLANE", "ECLIPTIC");
+        qp.append_pair("COMMAND", &command);
+        qp.append_pair("CENTER", center);
+        qp.append_pair("REF_PLANE", ref_plane);
         qp.append_pair("REF_SYSTEM", "ICRF");
         qp.append_pair("OUT_UNITS", "AU-D");
         qp.append_pair("CSV_FORMAT", "YES");
-        qp.append_pair("VEC_TABLE", "1");
-        qp.append_pair("TIME_TYPE", "UT");
+        if !table.iter().any(|(k, _)| *k == "TIME_TYPE") {
+            qp.append_pair("TIME_TYPE", "UT");
+        }
+
+        // Defaults a newer API might change, spelled out: no labels or extra columns that
+        // would shift the CSV, geometric states, calendar dates in the second column
+        if conservative_queries() {
+            for (k, v) in [("VEC_LABELS", "NO"), ("VEC_DELTA_T", "NO"), ("VEC_CORR", "NONE"), ("ELM_LABELS", "NO"), ("EXTRA_PREC", "NO")] {
+                if !table.iter().any(|(t, _)| *t == k) {
+                    qp.append_pair(k, v);
+                }
+            }
+        }
 
         qp.append_pair("START_TIME", &format!("'{}'", start_utc));
         qp.append_pair("STOP_TIME", &format!("'{}'", stop_utc));
-        qp.append_pair("STEP_SIZE", "'1 m'");
+        qp.append_pair("STEP_SIZE", &format!("'{}'", step_size));
     }
     Ok(url)
 }
 
-pub fn extract_table_lines(result_text: &str) -> Result<Vec<&str>> {
-    let so = result_text.find("$$SOE").ok_or_else(|| anyhow!("Missing $$SOE marker"))?;
-    let eo = result_text.find("$$EOE").ok_or_else(|| anyhow!("Missing $$EOE marker"))?;
+pub fn extract_table_lines(result_text: &str) -> Result<Vec<&str>, HorizonsError> {
+    let so = result_text.find("$$SOE").ok_or_else(|| HorizonsError::parse("missing $$SOE marker"))?;
+    let eo = result_text.find("$$EOE").ok_or_else(|| HorizonsError::parse("missing $$EOE marker"))?;
     if eo <= so {
-        return Err(anyhow!("$$EOE occurs before $$SOE"));
+        return Err(HorizonsError::parse("$$EOE occurs before $$SOE"));
     }
     let table = &result_text[(so + 5)..eo];
     Ok(table.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).collect())
 }
 
-pub fn parse_xyz_from_csv_row(row: &str) -> Result<Vec3> {
+/// Parses a `VEC_TABLE=2` CSV row: JDTDB, calendar date, X, Y, Z, VX, VY, VZ.
+pub fn parse_state_from_csv_row(row: &str) -> Result<(Vec3, Vec3), HorizonsError> {
     let cols: Vec<&str> = row
         .split(',')
         .map(|s| s.trim())
         .filter(|s| !s.is_empty())
         .collect();
 
-    if cols.len() < 5 {
-        return Err(anyhow!("Unexpected CSV format: {}", row));
+    if cols.len() < 8 {
+        return Err(HorizonsError::parse(format!("unexpected CSV format: {}", row)));
     }
 
-    let x = cols[cols.len() - 3].parse::<f64>().context("parse x")?;
-    let y = cols[cols.len() - 2].parse::<f64>().context("parse y")?;
-    let z = cols[cols.len() - 1].parse::<f64>().context("parse z")?;
-    Ok(Vec3 { x, y, z })
+    let n = cols.len();
+    let num = |i: usize, what: &str| parse_column(&cols, n - i, what);
+    let (x, y, z) = (num(6, "x")?, num(5, "y")?, num(4, "z")?);
+    let (vx, vy, vz) = (num(3, "vx")?, num(2, "vy")?, num(1, "vz")?);
+    Ok((Vec3 { x, y, z }, Vec3 { x: vx, y: vy, z: vz }))
 }
 
-pub async fn fetch_body_vec(client: &reqwest::Client, body_id: &str, start_utc: &str, stop_utc: &str) -> Result<Vec3> {
-    let url = build_horizons_url(body_id, start_utc, stop_utc)?;
-    let body = client.get(url).send().await?.error_for_status()?.text().await?;
-    let parsed: HorizonsJson = serde_json::from_str(&body).context("parse Horizons JSON")?;
-    if let Some(e) = parsed.error {
-        return Err(anyhow!("Horizons error: {}", e));
+fn parse_column(cols: &[&str], i: usize, what: &str) -> Result<f64, HorizonsError> {
+    cols[i].parse::<f64>().map_err(|e| HorizonsError::parse(format!("{} {:?}: {}", what, cols[i], e)))
+}
+
+/// Span of the element-history view.
+#[cfg(feature = "net")]
+const HISTORY_YEARS: f64 = 10.0;
+
+/// Osculating elements of one body over time, oldest first.
+#[derive(Debug, Clone)]
+pub struct ElementHistory {
+    pub body: &'static str,
+    pub rows: Arc<Vec<ElementsRow>>,
+}
+
+/// One row of an `EPHEM_TYPE=ELEMENTS` table.
+#[derive(Debug, Clone, Copy)]
+pub struct ElementsRow {
+    pub jd_tdb: f64,
+    pub elements: KeplerElements,
+    pub perihelion_au: f64,
+}
+
+impl ElementsRow {
+    /// Two-body position `days` after J2000 from these elements.
+    pub fn position_at(&self, days: f64) -> Option<Vec3> {
+        let (p, v) = elements_to_state(&self.elements, GM_SUN_AU3_D2);
+        propagate(p, v, days - (self.jd_tdb - J2000_JD), GM_SUN_AU3_D2).map(|(p, _)| p)
+    }
+
+    /// These elements `days` after J2000, only the anomaly having moved on.
+    pub fn elements_at(&self, days: f64) -> Option<KeplerElements> {
+        let (p, v) = elements_to_state(&self.elements, GM_SUN_AU3_D2);
+        let (p, v) = propagate(p, v, days - (self.jd_tdb - J2000_JD), GM_SUN_AU3_D2)?;
+        Some(state_to_elements(p, v, GM_SUN_AU3_D2))
     }
-    let lines = extract_table_lines(&parsed.result)?;
-    for line in lines {
-        if let Ok(v) = parse_xyz_from_csv_row(line) {
-            return Ok(v);
+
+    /// Days from J2000 to the row's epoch.
+    pub fn days_since_j2000(&self) -> f64 {
+        self.jd_tdb - J2000_JD
+    }
+}
+
+/// Parses an ELEMENTS CSV row: JDTDB, calendar date, EC, QR, IN, OM, W, Tp, N, MA, TA, A, AD, PR.
+pub fn parse_elements_row(row: &str) -> Result<ElementsRow, HorizonsError> {
+    let cols: Vec<&str> = row.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()).collect();
+    if cols.len() < 14 {
+        return Err(HorizonsError::parse(format!("unexpected ELEMENTS format: {}", row)));
+    }
+    let num = |i: usize, what: &str| parse_column(&cols, i, what);
+    Ok(ElementsRow {
+        jd_tdb: num(0, "JDTDB")?,
+        elements: KeplerElements {
+            a_au: num(11, "A")?,
+            e: num(2, "EC")?,
+            i_deg: num(4, "IN")?,
+            raan_deg: num(5, "OM")?,
+            arg_periapsis_deg: num(6, "W")?,
+            true_anomaly_deg: num(10, "TA")?,
+        },
+        perihelion_au: num(3, "QR")?,
+    })
+}
+
+/// Span and step of the observer tables behind the sky panel, interpolated in between.
+#[cfg(feature = "net")]
+const SKY_HOURS: i64 = 6;
+#[cfg(feature = "net")]
+const SKY_STEP: &str = "10 m";
+
+/// One row of an observer table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SkyRow {
+    pub utc: DateTime<Utc>,
+    pub pos: SkyPosition,
+}
+
+/// Parses an observer CSV row for `QUANTITIES='2,4'`: JDUT, the solar and lunar presence
+/// flags (often blank), RA, DEC, Azi, Elev.
+pub fn parse_observer_row(row: &str) -> Result<SkyRow, HorizonsError> {
+    let cols: Vec<&str> = row.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()).collect();
+    let n = cols.len();
+    if n < 5 {
+        return Err(HorizonsError::parse(format!("unexpected OBSERVER format: {}", row)));
+    }
+    let num = |i: usize, what: &str| parse_column(&cols, i, what);
+    Ok(SkyRow {
+        utc: row_time(row, true)?,
+        pos: SkyPosition {
+            ra_deg: num(n - 4, "RA")?,
+            dec_deg: num(n - 3, "DEC")?,
+            az_deg: num(n - 2, "Azi")?,
+            alt_deg: num(n - 1, "Elev")?,
+        },
+    })
+}
+
+/// Sky position at `utc` interpolated linearly between the bracketing rows (the shorter way
+/// round for RA and azimuth), or `None` outside the table.
+pub fn sky_at(rows: &[SkyRow], utc: DateTime<Utc>) -> Option<SkyPosition> {
+    let i = rows.windows(2).position(|w| w[0].utc <= utc && utc <= w[1].utc)?;
+    let (a, b) = (rows[i], rows[i + 1]);
+    let s = (utc - a.utc).num_milliseconds() as f64 / (b.utc - a.utc).num_milliseconds().max(1) as f64;
+    let lerp = |x: f64, y: f64| x + (y - x) * s;
+    let lerp_angle = |x: f64, y: f64| (x + ((y - x + 540.0).rem_euclid(360.0) - 180.0) * s).rem_euclid(360.0);
+    Some(SkyPosition {
+        ra_deg: lerp_angle(a.pos.ra_deg, b.pos.ra_deg),
+        dec_deg: lerp(a.pos.dec_deg, b.pos.dec_deg),
+        alt_deg: lerp(a.pos.alt_deg, b.pos.alt_deg),
+        az_deg: lerp_angle(a.pos.az_deg, b.pos.az_deg),
+    })
+}
+
+#[cfg(feature = "net")]
+pub async fn fetch_observer(
+    client: &reqwest::Client,
+    body_id: BodyId,
+    observer: &Observer,
+    start_utc: &str,
+    stop_utc: &str,
+    on_retry: OnRetry<'_>,
+) -> Result<Vec<SkyRow>, HorizonsError> {
+    let url = build_observer_url(body_id, observer, start_utc, stop_utc).map_err(HorizonsError::parse)?;
+    let result = result_text(&get_text(client, url, on_retry).await?)?;
+    extract_table_lines(&result)?.into_iter().map(parse_observer_row).collect()
+}
+
+/// Osculating elements of one body from `start_utc` to `stop_utc`, oldest first.
+#[cfg(feature = "net")]
+pub async fn fetch_elements(
+    client: &reqwest::Client,
+    body_id: BodyId,
+    start_utc: &str,
+    stop_utc: &str,
+    step_size: &str,
+    overrides: Option<&QueryOverrides>,
+    on_retry: OnRetry<'_>,
+) -> Result<Vec<ElementsRow>, HorizonsError> {
+    let url = build_elements_url(body_id, start_utc, stop_utc, step_size, overrides).map_err(HorizonsError::parse)?;
+    let result = result_text(&get_text(client, url, on_retry).await?)?;
+    extract_table_lines(&result)?.into_iter().map(parse_elements_row).collect()
+}
+
+/// Horizons API versions the queries and parsers were written against.
+pub const KNOWN_API_VERSIONS: &[&str] = &["1.0", "1.1", "1.2"];
+
+/// The API version last answered with, and what became of it.
+struct ApiSeen {
+    version: Option<String>,
+    /// An unknown version has been seen: queries pin every output setting the parsers rely
+    /// on rather than trusting the defaults.
+    conservative: bool,
+    /// Not yet reported.
+    warning: Option<String>,
+}
+
+static API_SEEN: Mutex<ApiSeen> = Mutex::new(ApiSeen { version: None, conservative: false, warning: None });
+
+/// The response's API version, from its signature block or else the `API VERSION:` line
+/// some results carry in their header.
+fn api_version(parsed: &HorizonsJson) -> Option<String> {
+    match &parsed.signature {
+        Some(sig) => Some(sig.version.clone()),
+        None => {
+            let line = parsed.result.lines().find_map(|l| l.trim().strip_prefix("API VERSION:"))?;
+            Some(line.trim().to_string())
         }
     }
-    Err(anyhow!("No parseable vector row for body {}", body_id))
 }
 
-pub async fn updater(state: Arc<Mutex<AppState>>) {
-    let client = reqwest::Client::builder()
+/// Records the version a response came from. The first unknown one switches later queries
+/// to the conservative form and leaves a warning for [`take_api_warning`].
+fn note_api_version(version: &str) {
+    let mut seen = API_SEEN.lock().unwrap();
+    if seen.version.as_deref() == Some(version) {
+        return;
+    }
+    seen.version = Some(version.to_string());
+    if !KNOWN_API_VERSIONS.contains(&version) && !seen.conservative {
+        seen.conservative = true;
+        let newest = KNOWN_API_VERSIONS[KNOWN_API_VERSIONS.len() - 1];
+        seen.warning = Some(format!(
+            "Horizons answers as API version {}, which this build doesn't know (newest {}); queries now pin their output format",
+            version, newest
+        ));
+    }
+}
+
+/// The warning about an unknown API version, once.
+pub fn take_api_warning() -> Option<String> {
+    API_SEEN.lock().unwrap().warning.take()
+}
+
+#[cfg(feature = "net")]
+fn conservative_queries() -> bool {
+    API_SEEN.lock().unwrap().conservative
+}
+
+/// The `result` text of a Horizons JSON response, or its `error`.
+fn result_text(response: &str) -> Result<String, HorizonsError> {
+    let parsed: HorizonsJson =
+        serde_json::from_str(response).map_err(|e| HorizonsError::parse(format!("Horizons JSON: {}", e)))?;
+    if let Some(version) = api_version(&parsed) {
+        note_api_version(&version);
+    }
+    match parsed.error {
+        Some(message) => Err(HorizonsError::Api { api: "Horizons", status: None, message }),
+        None => Ok(parsed.result),
+    }
+}
+
+/// Vectors of one body over a prefetched window at the query's step, oldest first, which
+/// [`Trajectory::state_at`] interpolates between refreshes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Trajectory {
+    /// When the table was fetched.
+    pub fetched: DateTime<Utc>,
+    pub rows: Vec<TrajectoryRow>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TrajectoryRow {
+    pub utc: DateTime<Utc>,
+    pub pos: Vec3,
+    pub vel: Vec3,
+}
+
+impl Trajectory {
+    /// Position and velocity at `utc` by cubic Hermite interpolation between the bracketing
+    /// rows, or `None` outside the window. With planet vectors every few minutes the error is
+    /// far below the precision Horizons prints.
+    pub fn state_at(&self, utc: DateTime<Utc>) -> Option<(Vec3, Vec3)> {
+        let i = self.rows.windows(2).position(|w| w[0].utc <= utc && utc <= w[1].utc)?;
+        let (a, b) = (self.rows[i], self.rows[i + 1]);
+        let h = (b.utc - a.utc).num_milliseconds() as f64 / 86_400_000.0; // days
+        let s = (utc - a.utc).num_milliseconds() as f64 / 86_400_000.0 / h;
+        let (s2, s3) = (s * s, s * s * s);
+
+        let pos = a.pos * (2.0 * s3 - 3.0 * s2 + 1.0)
+            + a.vel * ((s3 - 2.0 * s2 + s) * h)
+            + b.pos * (3.0 * s2 - 2.0 * s3)
+            + b.vel * ((s3 - s2) * h);
+        let vel = (a.pos - b.pos) * ((6.0 * s2 - 6.0 * s) / h)
+            + a.vel * (3.0 * s2 - 4.0 * s + 1.0)
+            + b.vel * (3.0 * s2 - 2.0 * s);
+        Some((pos, vel))
+    }
+}
+
+/// Time of a vector row from its leading Julian date, which is JDUT for `TIME_TYPE=UT`
+/// queries and JDTDB otherwise (TDB taken as UTC + 69.184 s, like [`days_since_j2000`]).
+fn row_time(row: &str, ut: bool) -> Result<DateTime<Utc>, HorizonsError> {
+    let jd = parse_column(&[row.split(',').next().unwrap_or("").trim()], 0, "JD")?;
+    let tdb_offset = if ut { 0.0 } else { TDB_MINUS_UTC_S };
+    let unix_ms = ((jd - 2_440_587.5) * 86_400.0 - tdb_offset) * 1000.0;
+    DateTime::from_timestamp_millis(unix_ms.round() as i64).ok_or_else(|| HorizonsError::parse(format!("JD {} out of range", jd)))
+}
+
+/// Vectors of one body from `start` to `stop` at the query's step, asked for on `scale`; the
+/// rows come back timed in UTC either way.
+#[cfg(feature = "net")]
+pub async fn fetch_trajectory(
+    client: &reqwest::Client,
+    body_id: BodyId,
+    start: DateTime<Utc>,
+    stop: DateTime<Utc>,
+    overrides: Option<&QueryOverrides>,
+    scale: TimeScale,
+    on_retry: OnRetry<'_>,
+) -> Result<Trajectory, HorizonsError> {
+    let format = |t: DateTime<Utc>| scale.from_utc(t).format("%Y-%b-%d %H:%M:%S%.3f").to_string();
+    let url = build_horizons_url(body_id, &format(start), &format(stop), overrides, scale).map_err(HorizonsError::parse)?;
+    let body = get_text(client, url, on_retry).await?;
+    parse_trajectory(&body, body_id)
+}
+
+/// The vector table of a Horizons JSON response to a `VECTORS` query for `body_id`.
+pub fn parse_trajectory(response: &str, body_id: BodyId) -> Result<Trajectory, HorizonsError> {
+    let result = result_text(response)?;
+    let ut = result.contains("JDUT");
+    let rows: Vec<TrajectoryRow> = extract_table_lines(&result)?
+        .into_iter()
+        .filter_map(|line| {
+            let (pos, vel) = parse_state_from_csv_row(line).ok()?;
+            Some(TrajectoryRow { utc: row_time(line, ut).ok()?, pos, vel })
+        })
+        .collect();
+    if rows.is_empty() {
+        return Err(HorizonsError::parse(format!("no vector row for body {}", body_id)));
+    }
+    Ok(Trajectory { fetched: Utc::now(), rows })
+}
+
+/// Prefetched trajectories kept on disk (`$XDG_CACHE_HOME/solar-rs/horizons.json`) so a
+/// restart shows current data at once; a body is fetched again only once its window has run
+/// out. Every process using the file (the app, notebooks, the C and gRPC APIs) merges into
+/// it rather than overwriting it.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ResponseCache {
+    /// Keyed by body id.
+    entries: BTreeMap<BodyId, CachedResponse>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedResponse {
+    /// Query overrides in effect, since they change what Horizons returns.
+    overrides: Option<QueryOverrides>,
+    trajectory: Trajectory,
+}
+
+impl ResponseCache {
+    pub fn path() -> Option<PathBuf> {
+        dirs::cache_dir().map(|d| d.join("solar-rs").join("horizons.json"))
+    }
+
+    /// The stored cache; empty if missing or unreadable.
+    pub fn load() -> Self {
+        Self::path().map(|p| Self::load_from(&p)).unwrap_or_default()
+    }
+
+    fn load_from(path: &Path) -> Self {
+        std::fs::read_to_string(path).ok().and_then(|text| serde_json::from_str(&text).ok()).unwrap_or_default()
+    }
+
+    /// Writes the cache merged with what is on disk, so bodies another process stored since
+    /// this one loaded the file are kept (and picked up here too).
+    pub fn save(&mut self) -> Result<()> {
+        let Some(path) = Self::path() else { return Ok(()) };
+        self.save_to(&path)
+    }
+
+    fn save_to(&mut self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        // Held from the read to the rename, so two saves can't both miss each other's bodies
+        let lock = std::fs::File::create(path.with_extension("json.lock"))?;
+        lock.lock()?;
+        self.merge(Self::load_from(path));
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string(self)?)?;
+        std::fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    /// Takes the bodies `other` has and this cache lacks, or fetched later than this one's.
+    fn merge(&mut self, other: ResponseCache) {
+        for (id, theirs) in other.entries {
+            match self.entries.get(&id) {
+                Some(ours) if ours.trajectory.fetched >= theirs.trajectory.fetched => {}
+                _ => {
+                    self.entries.insert(id, theirs);
+                }
+            }
+        }
+    }
+
+    /// Vectors for `id` interpolated to `now`, with the time they were fetched, if a
+    /// trajectory for the same query covers `now`.
+    pub fn get(
+        &self,
+        id: BodyId,
+        overrides: Option<&QueryOverrides>,
+        now: DateTime<Utc>,
+    ) -> Option<(Vec3, Vec3, DateTime<Utc>)> {
+        let e = self.entries.get(&id).filter(|e| e.overrides.as_ref() == overrides)?;
+        let (p, v) = e.trajectory.state_at(now)?;
+        Some((p, v, e.trajectory.fetched))
+    }
+
+    /// The last row of `id`'s trajectory for the same query, with the time it was fetched, to
+    /// propagate from past the end of the window.
+    pub fn last_row(&self, id: BodyId, overrides: Option<&QueryOverrides>) -> Option<(TrajectoryRow, DateTime<Utc>)> {
+        let e = self.entries.get(&id).filter(|e| e.overrides.as_ref() == overrides)?;
+        Some((*e.trajectory.rows.last()?, e.trajectory.fetched))
+    }
+
+    pub fn insert(&mut self, id: BodyId, overrides: Option<&QueryOverrides>, trajectory: Trajectory) {
+        let entry = CachedResponse { overrides: overrides.cloned(), trajectory };
+        self.entries.insert(id, entry);
+    }
+}
+
+/// Intervals per body in a range fetch, whatever its length: two years come at about 17 h a
+/// row, fine for the planets but too coarse to follow the inner moons.
+pub const RANGE_INTERVALS: u32 = 1000;
+
+/// Vector tables for every body over a date range, fetched in one batch (`F`) for scrubbing
+/// and playing through; positions between rows are interpolated as in the response cache.
+#[derive(Debug, Clone)]
+pub struct TrajectoryBuffer {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    /// Keyed by body id; bodies whose query failed are missing.
+    pub tracks: BTreeMap<BodyId, Trajectory>,
+}
+
+impl TrajectoryBuffer {
+    pub fn covers(&self, utc: DateTime<Utc>) -> bool {
+        self.from <= utc && utc <= self.to
+    }
+
+    /// Moves every buffered body in `state` to `utc`; the others keep what they had.
+    pub fn apply(&self, state: &mut DataState, utc: DateTime<Utc>) {
+        for b in &mut state.bodies {
+            if let Some((p, v)) = self.tracks.get(&b.id).and_then(|t| t.state_at(utc)) {
+                b.pos_au = Some(p);
+                b.vel_au_d = Some(v);
+            }
+        }
+    }
+}
+
+/// Horizons vectors for library users, through the same on-disk response cache as the app, so
+/// a notebook and a running TUI share their downloads.
+#[cfg(feature = "net")]
+pub struct EphemerisClient {
+    http: reqwest::Client,
+    config: Config,
+    cache: ResponseCache,
+}
+
+#[cfg(feature = "net")]
+impl EphemerisClient {
+    pub fn new(config: Config) -> Self {
+        EphemerisClient { http: http_client(&config), config, cache: ResponseCache::load() }
+    }
+
+    /// Position and velocity of `id` at `utc`: from the cache when a stored window covers it,
+    /// else fetched with `prefetch_hours` of vectors from `utc` on and stored (call
+    /// [`save`](Self::save) to keep them).
+    pub async fn state_at(&mut self, id: BodyId, utc: DateTime<Utc>) -> Result<(Vec3, Vec3)> {
+        let name = meta_by_id(id).map_or("", |m| m.name);
+        let overrides = self.config.overrides_for(name).cloned();
+        if let Some((p, v, _)) = self.cache.get(id, overrides.as_ref(), utc) {
+            return Ok((p, v));
+        }
+        if bandwidth::current().quota_reached(&self.config) {
+            return Err(anyhow::anyhow!("not cached, and the daily download quota is used up"));
+        }
+        let stop = utc + ChronoDuration::seconds((self.config.prefetch_hours * 3600.0) as i64);
+        let trajectory = fetch_trajectory(&self.http, id, utc, stop, overrides.as_ref(), self.config.time_scale, &|_| {}).await?;
+        let first = trajectory.rows[0];
+        let state = trajectory.state_at(utc).unwrap_or((first.pos, first.vel));
+        self.cache.insert(id, overrides.as_ref(), trajectory);
+        Ok(state)
+    }
+
+    /// Stores fetched vectors in the response cache, merged with what others have stored.
+    pub fn save(&mut self) -> Result<()> {
+        self.cache.save()
+    }
+}
+
+/// Long enough for a cellular link, short enough to fall back to the cache the same refresh.
+#[cfg(feature = "net")]
+const CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[cfg(feature = "net")]
+pub(crate) fn http_client(config: &Config) -> reqwest::Client {
+    reqwest::Client::builder()
         .user_agent("solar-rs/0.5 (ratatui)")
+        .dns_resolver(Arc::new(dns::Resolver::new(&config.dns_servers)))
+        .connect_timeout(CONNECT_TIMEOUT)
         .build()
-        .expect("reqwest client");
+        .expect("reqwest client")
+}
+
+/// Attempts per request; the wait before the second is `BACKOFF` and each later one doubles,
+/// with jitter so concurrent queries don't retry in lockstep.
+#[cfg(feature = "net")]
+const MAX_ATTEMPTS: u32 = 3;
+#[cfg(feature = "net")]
+const BACKOFF: Duration = Duration::from_secs(2);
+/// Longest wait between attempts, whatever `Retry-After` asks for.
+#[cfg(feature = "net")]
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// A failed attempt about to be retried, for the status line.
+#[cfg(feature = "net")]
+#[derive(Debug)]
+pub struct Retry<'a> {
+    pub error: &'a HorizonsError,
+    /// The attempt coming up, from 2.
+    pub attempt: u32,
+    pub wait: Duration,
+}
+
+#[cfg(feature = "net")]
+impl fmt::Display for Retry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}; retry {}/{} in {:.1} s", self.error, self.attempt, MAX_ATTEMPTS, self.wait.as_secs_f64())
+    }
+}
+
+/// Told about each retry before its wait.
+#[cfg(feature = "net")]
+pub type OnRetry<'a> = &'a (dyn Fn(&Retry) + Send + Sync);
+
+/// Wait after `failures` failed attempts: the server's `Retry-After` if it sent one, else
+/// exponential from `BACKOFF`, scaled by `jitter` in [0, 1) to between half and one and a half
+/// times that.
+#[cfg(feature = "net")]
+fn backoff(failures: u32, retry_after: Option<Duration>, jitter: f64) -> Duration {
+    let wait = retry_after.unwrap_or_else(|| BACKOFF.mul_f64(2f64.powi(failures as i32 - 1) * (0.5 + jitter)));
+    wait.min(MAX_BACKOFF)
+}
+
+/// Uniform in [0, 1), from the randomly keyed std hasher rather than a dependency.
+#[cfg(feature = "net")]
+fn jitter() -> f64 {
+    use std::hash::{BuildHasher, Hasher};
+    let bits = std::collections::hash_map::RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// GETs `url` from Horizons as text.
+#[cfg(feature = "net")]
+async fn get_text(client: &reqwest::Client, url: Url, on_retry: OnRetry<'_>) -> Result<String, HorizonsError> {
+    get_api_text(client, url, "Horizons", on_retry).await
+}
 
+/// GETs `url` from one of JPL's APIs as text, counting it against the download quota, with up
+/// to [`MAX_ATTEMPTS`] tries while the failures are transient.
+#[cfg(feature = "net")]
+pub(crate) async fn get_api_text(
+    client: &reqwest::Client,
+    url: Url,
+    api: &'static str,
+    on_retry: OnRetry<'_>,
+) -> Result<String, HorizonsError> {
+    let mut failures = 0;
     loop {
-        let now_label = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+        match get_once(client, url.clone(), api).await {
+            Err(error) if error.is_transient() && failures + 1 < MAX_ATTEMPTS => {
+                failures += 1;
+                let retry_after = match error {
+                    HorizonsError::RateLimited { retry_after, .. } => retry_after,
+                    _ => None,
+                };
+                let wait = backoff(failures, retry_after, jitter());
+                on_retry(&Retry { error: &error, attempt: failures + 1, wait });
+                sleep(wait).await;
+            }
+            result => return result,
+        }
+    }
+}
 
-        let start = Utc::now();
-        let stop = start + ChronoDuration::minutes(1);
-        let start_str = start.format("%Y-%b-%d %H:%M:%S").to_string();
-        let stop_str = stop.format("%Y-%b-%d %H:%M:%S").to_string();
+/// One GET. Network errors say where the request failed, name resolution, the connection or
+/// the transfer, since on a flaky link those call for different remedies.
+#[cfg(feature = "net")]
+async fn get_once(client: &reqwest::Client, url: Url, api: &'static str) -> Result<String, HorizonsError> {
+    let host = url.host_str().unwrap_or_default().to_string();
+    let network = |e: reqwest::Error| {
+        if let Some(dns) = dns::dns_failure(&e) {
+            return HorizonsError::Network(dns.to_string());
+        }
+        let mut cause: &dyn std::error::Error = &e;
+        while let Some(source) = cause.source() {
+            cause = source;
+        }
+        HorizonsError::Network(if e.is_timeout() {
+            format!("{} timed out", host)
+        } else if e.is_connect() {
+            format!("can't connect to {}: {}", host, cause)
+        } else {
+            format!("{} request failed: {}", api, cause)
+        })
+    };
+    let response = client.get(url).send().await.map_err(network)?;
+    let status = response.status();
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS || status == reqwest::StatusCode::SERVICE_UNAVAILABLE {
+        // Only the delay-seconds form; an HTTP date falls back to the backoff
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok()?.trim().parse().ok())
+            .map(Duration::from_secs);
+        return Err(HorizonsError::RateLimited { api, status: status.as_u16(), retry_after });
+    }
+    if !status.is_success() {
+        return Err(HorizonsError::Api { api, status: Some(status.as_u16()), message: status.to_string() });
+    }
+    let headers: usize = response.headers().iter().map(|(k, v)| k.as_str().len() + v.len() + 4).sum();
+    let body = response.text().await.map_err(network)?;
+    bandwidth::record((headers + body.len()) as u64);
+    Ok(body)
+}
 
-        let bodies_snapshot = {
-            let s = state.lock().unwrap();
-            s.bodies.iter().filter(|b| b.id != "10").map(|b| (b.name, b.id)).collect::<Vec<_>>()
-        };
+/// Monthly osculating elements over the last [`HISTORY_YEARS`] for the element-history view.
+/// The newest row also replaces the mean orbit in the diagnostics' predicted longitude.
+#[cfg(feature = "net")]
+pub async fn load_element_history(state: Arc<Mutex<DataState>>, body: &'static str, id: BodyId) {
+    let stop = Utc::now();
+    let start = stop - ChronoDuration::days((HISTORY_YEARS * 365.25) as i64);
+    let (config, offline) = {
+        let s = state.lock().unwrap();
+        (s.config.clone(), s.offline)
+    };
+    if offline {
+        state.lock().unwrap().popup = Some(format!("Offline; element history for {} is not fetched", body));
+        return;
+    }
+    if bandwidth::current().quota_reached(&config) {
+        state.lock().unwrap().popup = Some(format!("Daily download quota reached; element history for {} is not fetched", body));
+        return;
+    }
+    let overrides = config.overrides_for(body).cloned();
+    let client = http_client(&config);
+    let on_retry = |r: &Retry| state.lock().unwrap().status = format!("Elements for {}: {}", body, r);
+    let result = fetch_elements(
+        &client,
+        id,
+        &start.format("%Y-%b-%d").to_string(),
+        &stop.format("%Y-%b-%d").to_string(),
+        "30 d",
+        overrides.as_ref(),
+        &on_retry,
+    )
+    .await;
+
+    let mut s = state.lock().unwrap();
+    s.bandwidth = bandwidth::current();
+    match result {
+        Ok(rows) => {
+            if let Some(last) = rows.last() {
+                s.fresh_elements.insert(body, *last);
+            }
+            s.status = format!("Elements: {} rows for {}", rows.len(), body);
+            s.history = Some(ElementHistory { body, rows: Arc::new(rows) });
+        }
+        Err(e) => {
+            s.history = None;
+            s.popup = Some(format!("Element history for {} failed: {:#}", body, e));
+        }
+    }
+}
 
-        let mut new_positions: BTreeMap<&'static str, Vec3> = BTreeMap::new();
-        let mut status = "OK".to_string();
+/// Current osculating elements of every body orbiting the Sun, one `EPHEM_TYPE=ELEMENTS` query
+/// each, into `fresh_elements` for the elements panel. Moons are left to their vectors.
+#[cfg(feature = "net")]
+pub async fn load_elements(state: Arc<Mutex<DataState>>) {
+    let (bodies, config, offline) = {
+        let s = state.lock().unwrap();
+        let bodies: Vec<BodyId> = s
+            .bodies
+            .iter()
+            .map(|b| b.id)
+            .filter(|&id| id != BodyId::SUN && meta_by_id(id).is_some_and(|m| m.parent.is_none()))
+            .collect();
+        (bodies, s.config.clone(), s.offline)
+    };
+    if offline {
+        state.lock().unwrap().status = "Offline; elements computed from the vectors".into();
+        return;
+    }
+    if bandwidth::current().quota_reached(&config) {
+        state.lock().unwrap().status = "Daily download quota reached; elements computed from the vectors".into();
+        return;
+    }
+    let client = http_client(&config);
+    let start = Utc::now();
+    let format = |t: DateTime<Utc>| t.format("%Y-%b-%d %H:%M").to_string();
+    let (start_s, stop_s) = (format(start), format(start + ChronoDuration::days(1)));
 
-        for (name, id) in bodies_snapshot {
-            match fetch_body_vec(&client, id, &start_str, &stop_str).await {
-                Ok(v) => { new_positions.insert(name, v); }
-                Err(e) => status = format!("Fetch error ({}): {}", name, e),
+    let on_retry = |r: &Retry| state.lock().unwrap().status = format!("Elements: {}", r);
+    let fetch = |id: BodyId| {
+        let overrides = meta_by_id(id).and_then(|m| config.overrides_for(m.name));
+        fetch_elements(&client, id, &start_s, &stop_s, "1 d", overrides, &on_retry)
+    };
+    let results: Vec<_> = stream::iter(bodies)
+        .map(|id| async move { (id, fetch(id).await) })
+        .buffer_unordered(config.fetch_concurrency)
+        .collect()
+        .await;
+
+    let mut s = state.lock().unwrap();
+    s.bandwidth = bandwidth::current();
+    let mut fetched = 0;
+    for (id, result) in results {
+        let Some(name) = meta_by_id(id).map(|m| m.name) else { continue };
+        match result.map(|rows| rows.first().copied()) {
+            Ok(Some(row)) => {
+                s.fresh_elements.insert(name, row);
+                fetched += 1;
             }
-            sleep(Duration::from_millis(120)).await;
+            Ok(None) => s.alert(AlertLevel::Warning, format!("Elements for {}: empty table", name)),
+            Err(e) => s.alert(AlertLevel::Warning, format!("Elements for {} failed: {:#}", name, e)),
         }
+    }
+    s.status = format!("Elements: Horizons osculating elements for {} bodies", fetched);
+}
 
-        {
+/// Vector tables of every body from `from` to `to` at [`RANGE_INTERVALS`] steps, into
+/// `range` for scrubbing through. Bodies that fail are left to propagation.
+#[cfg(feature = "net")]
+pub async fn load_range(state: Arc<Mutex<DataState>>, from: DateTime<Utc>, to: DateTime<Utc>) {
+    let (bodies, config, offline) = {
+        let s = state.lock().unwrap();
+        let bodies: Vec<BodyId> = s.bodies.iter().map(|b| b.id).filter(|&id| id != BodyId::SUN).collect();
+        (bodies, s.config.clone(), s.offline)
+    };
+    if offline {
+        state.lock().unwrap().status = "Offline; the range plays by propagation".into();
+        return;
+    }
+    if bandwidth::current().quota_reached(&config) {
+        state.lock().unwrap().status = "Daily download quota reached; the range plays by propagation".into();
+        return;
+    }
+    let client = http_client(&config);
+    let on_retry = |r: &Retry| state.lock().unwrap().status = format!("Range: {}", r);
+
+    let total = bodies.len();
+    let fetches = bodies.into_iter().map(|id| {
+        let mut overrides = meta_by_id(id).and_then(|m| config.overrides_for(m.name)).cloned().unwrap_or_default();
+        overrides.step_size = Some(RANGE_INTERVALS.to_string());
+        let (client, scale, on_retry) = (&client, config.time_scale, &on_retry);
+        async move { (id, fetch_trajectory(client, id, from, to, Some(&overrides), scale, on_retry).await) }
+    });
+    let mut results = stream::iter(fetches).buffer_unordered(config.fetch_concurrency);
+    let (mut tracks, mut done) = (BTreeMap::new(), 0);
+    while let Some((id, result)) = results.next().await {
+        let mut s = state.lock().unwrap();
+        s.bandwidth = bandwidth::current();
+        done += 1;
+        match result {
+            Ok(trajectory) => {
+                tracks.insert(id, trajectory);
+            }
+            Err(e) => {
+                let name = meta_by_id(id).map_or("?", |m| m.name);
+                s.alert(AlertLevel::Warning, format!("Range for {} failed: {:#}", name, e));
+            }
+        }
+        s.status = format!("Fetching range… {}/{}", done, total);
+    }
+    drop(results);
+
+    let mut s = state.lock().unwrap();
+    s.status = format!(
+        "Range {} – {}: {} of {} bodies (Space plays, ←/→ scrub)",
+        from.format("%Y-%m-%d"),
+        to.format("%Y-%m-%d"),
+        tracks.len(),
+        total
+    );
+    s.range = Some(Arc::new(TrajectoryBuffer { from, to, tracks }));
+}
+
+/// Horizons observer tables for every body but Earth over the next [`SKY_HOURS`], from the
+/// configured observer, for the sky panel. Bodies that fail keep the local computation.
+#[cfg(feature = "net")]
+pub async fn load_sky(state: Arc<Mutex<DataState>>) {
+    let (ids, observer, concurrency, offline) = {
+        let s = state.lock().unwrap();
+        let ids: Vec<BodyId> = s.bodies.iter().map(|b| b.id).filter(|&id| id != BodyId::EARTH).collect();
+        (ids, s.config.observer, s.config.fetch_concurrency, s.offline)
+    };
+    let Some(observer) = observer else { return };
+    let config = state.lock().unwrap().config.clone();
+    if offline {
+        state.lock().unwrap().status = "Offline; sky panel computed locally".into();
+        return;
+    }
+    if bandwidth::current().quota_reached(&config) {
+        state.lock().unwrap().status = "Daily download quota reached; sky panel computed locally".into();
+        return;
+    }
+    let client = http_client(&config);
+    let start = Utc::now();
+    let format = |t: DateTime<Utc>| t.format("%Y-%b-%d %H:%M").to_string();
+    let (start_s, stop_s) = (format(start), format(start + ChronoDuration::hours(SKY_HOURS)));
+
+    let on_retry = |r: &Retry| state.lock().unwrap().status = format!("Sky: {}", r);
+    let fetch = |id: BodyId| fetch_observer(&client, id, &observer, &start_s, &stop_s, &on_retry);
+    let results: Vec<_> = stream::iter(ids)
+        .map(|id| async move { (id, fetch(id).await) })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+
+    let mut s = state.lock().unwrap();
+    s.bandwidth = bandwidth::current();
+    let mut fetched = 0;
+    for (id, result) in results {
+        match result {
+            Ok(rows) => {
+                s.sky.insert(id, Arc::new(rows));
+                fetched += 1;
+            }
+            Err(e) => {
+                let name = meta_by_id(id).map_or("?", |m| m.name);
+                s.alert(AlertLevel::Warning, format!("Observer table for {} failed: {:#}", name, e));
+            }
+        }
+    }
+    s.status = format!("Sky: Horizons observer tables for {} bodies", fetched);
+}
+
+/// First wait before a body whose Horizons query failed is queried again, doubling with each
+/// further failure up to [`MAX_REQUERY`]. Meanwhile the rest of the chain answers for it.
+const FIRST_REQUERY: Duration = Duration::from_secs(20);
+const MAX_REQUERY: Duration = Duration::from_secs(600);
+
+/// A body whose Horizons queries keep failing.
+#[derive(Debug, Clone)]
+pub struct FailingBody {
+    /// Failed queries in a row.
+    pub failures: u32,
+    pub last_error: String,
+    pub next_try: DateTime<Utc>,
+}
+
+/// Bodies whose last Horizons query failed, each queried again on its own backoff rather than
+/// on every refresh; the updater wakes early when one falls due.
+#[derive(Debug, Clone, Default)]
+pub struct RetryQueue(BTreeMap<BodyId, FailingBody>);
+
+impl RetryQueue {
+    pub fn get(&self, id: BodyId) -> Option<&FailingBody> {
+        self.0.get(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Still waiting out its backoff at `now`.
+    fn holds(&self, id: BodyId, now: DateTime<Utc>) -> bool {
+        self.0.get(&id).is_some_and(|f| f.next_try > now)
+    }
+
+    fn failed(&mut self, id: BodyId, error: String, now: DateTime<Utc>) {
+        let failures = self.0.get(&id).map_or(1, |f| f.failures + 1);
+        let next_try = now + ChronoDuration::from_std(requery_delay(failures)).unwrap_or_default();
+        self.0.insert(id, FailingBody { failures, last_error: error, next_try });
+    }
+
+    fn succeeded(&mut self, id: BodyId) {
+        self.0.remove(&id);
+    }
+
+    /// Keeps only bodies still in the list.
+    fn retain(&mut self, ids: &[BodyId]) {
+        self.0.retain(|id, _| ids.contains(id));
+    }
+
+    fn next_due(&self) -> Option<DateTime<Utc>> {
+        self.0.values().map(|f| f.next_try).min()
+    }
+}
+
+fn requery_delay(failures: u32) -> Duration {
+    FIRST_REQUERY.saturating_mul(1 << failures.saturating_sub(1).min(10)).min(MAX_REQUERY)
+}
+
+/// Everything one refresh round needs to resolve a body, shared by the concurrent requests.
+struct RefreshRound<'a> {
+    #[cfg(feature = "net")]
+    client: &'a reqwest::Client,
+    /// Where retries are reported while the round waits them out.
+    #[cfg(feature = "net")]
+    state: &'a Arc<Mutex<DataState>>,
+    responses: &'a ResponseCache,
+    config: &'a Config,
+    chain: &'a [Source],
+    spk: Option<&'a Spk>,
+    /// Epoch the vectors are for: now, or the time-travel epoch.
+    start: DateTime<Utc>,
+    /// Wall-clock time of the round, which the retry queue runs on.
+    now: DateTime<Utc>,
+    /// Offline, or the daily download quota is used up: Horizons only answers from the
+    /// response cache.
+    cache_only: bool,
+    /// Bodies whose failed queries aren't due for another try; Horizons answers them only
+    /// from the response cache.
+    retry: &'a RetryQueue,
+}
+
+/// The Sun's barycentric query: its vectors about the solar-system barycentre.
+fn barycentre_overrides() -> QueryOverrides {
+    QueryOverrides { center: Some("500@0".into()), ..Default::default() }
+}
+
+/// A body to refresh, with its vectors from the previous round.
+struct PendingBody {
+    name: &'static str,
+    id: BodyId,
+    cached: Option<Cached>,
+}
+
+/// How one body's refresh went: the failures in chain order, then the source that answered.
+struct BodyRefresh {
+    name: &'static str,
+    id: BodyId,
+    failures: Vec<(Source, anyhow::Error)>,
+    answer: Option<(Source, Vec3, Vec3, DateTime<Utc>)>,
+    /// Newly fetched Horizons vectors, for the response cache.
+    fetched: Option<Trajectory>,
+}
+
+impl RefreshRound<'_> {
+    /// One Horizons query covers the whole prefetch window; refreshes in between interpolate.
+    #[cfg(feature = "net")]
+    async fn fetch(&self, id: BodyId, overrides: Option<&QueryOverrides>) -> Result<Trajectory> {
+        let stop = self.start + ChronoDuration::seconds((self.config.prefetch_hours * 3600.0) as i64);
+        let name = meta_by_id(id).map_or("?", |m| m.name);
+        let on_retry = |r: &Retry| self.state.lock().unwrap().status = format!("Updating… {}: {}", name, r);
+        Ok(fetch_trajectory(self.client, id, self.start, stop, overrides, self.config.time_scale, &on_retry).await?)
+    }
+
+    /// Offline builds only answer from the response cache.
+    #[cfg(not(feature = "net"))]
+    async fn fetch(&self, _: BodyId, _: Option<&QueryOverrides>) -> Result<Trajectory> {
+        Err(anyhow::anyhow!("not cached, and this build has no network access"))
+    }
+
+    async fn body(&self, PendingBody { name, id, cached }: PendingBody) -> BodyRefresh {
+        let start = self.start;
+        let mut refresh = BodyRefresh { name, id, failures: Vec::new(), answer: None, fetched: None };
+        let moon = meta_by_id(id).is_some_and(|m| m.parent.is_some());
+        let planet = meta_by_id(id).is_some_and(|m| matches!(m.class, BodyClass::Terrestrial | BodyClass::GasGiant | BodyClass::IceGiant));
+        for &source in self.chain {
+            // Moons come from Horizons or a kernel only: the local theories are planetary,
+            // and two-body propagation about the Sun throws Io off within the hour
+            if moon && matches!(source, Source::Cache | Source::Keplerian | Source::Vsop87) {
+                continue;
+            }
+            // Sources with nothing for this body are passed over rather than reported failing
+            if (source == Source::Spk && self.spk.is_none()) || (source == Source::Vsop87 && !planet) {
+                continue;
+            }
+            let result = match source {
+                Source::Horizons => {
+                    let overrides = self.config.overrides_for(name);
+                    match self.responses.get(id, overrides, start) {
+                        Some(hit) => Ok(hit),
+                        None if self.cache_only || self.retry.holds(id, self.now) => continue,
+                        None => self.fetch(id, overrides)
+                            .await
+                            .map(|trajectory| {
+                                let first = trajectory.rows[0];
+                                let (p, v) = trajectory.state_at(start).unwrap_or((first.pos, first.vel));
+                                refresh.fetched = Some(trajectory);
+                                (p, v, start)
+                            }),
+                    }
+                }
+                Source::Cache => sources::from_cache(cached, start),
+                Source::Keplerian | Source::Vsop87 | Source::Spk => {
+                    sources::local_state(source, name, id, self.spk, start).map(|(p, v)| (p, v, start))
+                }
+            };
+            match result {
+                Ok((p, v, epoch)) => {
+                    refresh.answer = Some((source, p, v, epoch));
+                    break;
+                }
+                Err(e) => refresh.failures.push((source, e)),
+            }
+        }
+        refresh
+    }
+
+    /// The Sun about the solar-system barycentre, for the barycentric origin: from Horizons or
+    /// the kernel, whichever comes first in the chain and answers, otherwise from the masses
+    /// and heliocentric states of the planets and dwarf planets (as refreshed, or from mean
+    /// elements for those not shown), which misses only the pull of smaller bodies. Also
+    /// returns newly fetched vectors, for the response cache.
+    async fn sun_barycentric(&self, bodies: &[BodyState]) -> (Vec3, Vec3, Option<Trajectory>) {
+        let overrides = barycentre_overrides();
+        for &source in self.chain {
+            let answer = match source {
+                Source::Horizons => match self.responses.get(BodyId::SUN, Some(&overrides), self.start) {
+                    Some((p, v, _)) => Some((p, v, None)),
+                    None if self.cache_only => None,
+                    None => self.fetch(BodyId::SUN, Some(&overrides)).await.ok().map(|trajectory| {
+                        let first = trajectory.rows[0];
+                        let (p, v) = trajectory.state_at(self.start).unwrap_or((first.pos, first.vel));
+                        (p, v, Some(trajectory))
+                    }),
+                },
+                Source::Spk => self.spk.and_then(|k| k.sun_barycentric(self.start).ok()).map(|(p, v)| (p, v, None)),
+                _ => None,
+            };
+            if let Some(answer) = answer {
+                return answer;
+            }
+        }
+        let pulls: Vec<_> = BODIES
+            .iter()
+            .filter(|m| m.orbit_au.is_some())
+            .filter_map(|m| {
+                let shown = bodies.iter().find(|b| b.id == m.id).and_then(|b| b.pos_au.zip(b.vel_au_d));
+                let (p, v) = shown.or_else(|| ephemeris::state_at(m.name, self.start))?;
+                Some((m.mass_kg, p, v))
+            })
+            .collect();
+        let (p, v) = physics::sun_barycentric(&pulls);
+        (p, v, None)
+    }
+}
+
+pub async fn updater(state: Arc<Mutex<DataState>>) {
+    #[cfg(feature = "net")]
+    let (mut client, mut dns_servers) = {
+        let config = state.lock().unwrap().config.clone();
+        (http_client(&config), config.dns_servers.clone())
+    };
+    let mut responses = ResponseCache::load();
+    let mut was_quota_reached = false;
+    let mut retry = RetryQueue::default();
+
+    loop {
+        let now = Utc::now();
+
+        let (bodies_snapshot, config, chain, spk, offline, sim_epoch) = {
             let mut s = state.lock().unwrap();
-            for b in &mut s.bodies {
-                if b.id == "10" {
-                    b.pos_au = Some(Vec3 { x: 0.0, y: 0.0, z: 0.0 });
-                } else {
-                    b.pos_au = new_positions.get(b.name).copied().or(b.pos_au);
+            // The origin needs no query, so it shows at once rather than after the first round
+            for b in s.bodies.iter_mut().filter(|b| b.id == BodyId::SUN) {
+                b.pos_au = Some(Vec3::ZERO);
+                b.vel_au_d = Some(Vec3::ZERO);
+            }
+            let valid_at = events::epoch(&s);
+            let bodies = s
+                .bodies
+                .iter()
+                .filter(|b| b.id != BodyId::SUN)
+                .map(|b| {
+                    let cached = match (b.pos_au, b.vel_au_d, valid_at, b.source) {
+                        (Some(pos), Some(vel), Some(at), Some(provenance)) => Some(Cached { pos, vel, at, provenance }),
+                        _ => None,
+                    };
+                    PendingBody { name: b.name, id: b.id, cached }
+                })
+                .collect::<Vec<_>>();
+            let chain = s.source.map_or_else(|| s.config.sources.clone(), |src| vec![src]);
+            (bodies, s.config.clone(), chain, s.spk.clone(), s.offline, s.sim_epoch)
+        };
+        // In time travel every source answers for the chosen epoch instead
+        let start = sim_epoch.unwrap_or(now);
+        let now_label = start.to_rfc3339_opts(SecondsFormat::Secs, true);
+        #[cfg(feature = "net")]
+        if config.dns_servers != dns_servers {
+            client = http_client(&config);
+            dns_servers = config.dns_servers.clone();
+        }
+
+        let quota_reached = bandwidth::current().quota_reached(&config);
+        if quota_reached && !was_quota_reached && !offline {
+            let quota = config.daily_quota_mb.unwrap_or_default();
+            let msg = format!("Daily download quota of {} MB reached; using cached and local data until midnight", quota);
+            state.lock().unwrap().alert(AlertLevel::Warning, msg);
+        }
+        was_quota_reached = quota_reached;
+
+        let round = RefreshRound {
+            #[cfg(feature = "net")]
+            client: &client,
+            #[cfg(feature = "net")]
+            state: &state,
+            responses: &responses,
+            config: &config,
+            chain: &chain,
+            spk: spk.as_deref(),
+            start,
+            now,
+            cache_only: offline || quota_reached,
+            retry: &retry,
+        };
+
+        let mut status = match (offline, quota_reached) {
+            (true, _) => "Offline".to_string(),
+            (false, true) => "Quota reached, offline".to_string(),
+            (false, false) => "OK".to_string(),
+        };
+        let mut fetched = Vec::new();
+        // (body, the Horizons failure if its query failed), for the retry queue after the round
+        let mut queried = Vec::new();
+        let ids: Vec<BodyId> = bodies_snapshot.iter().map(|b| b.id).collect();
+        let (total, mut done) = (bodies_snapshot.len(), 0);
+
+        let mut refreshes = stream::iter(bodies_snapshot)
+            .map(|body| round.body(body))
+            .buffer_unordered(config.fetch_concurrency);
+        while let Some(refresh) = refreshes.next().await {
+            let name = refresh.name;
+            // Published as each body arrives, so the table and map fill in while slower
+            // queries are still out; the round's status replaces the progress at the end
+            let mut s = state.lock().unwrap();
+            s.bandwidth = bandwidth::current();
+            done += 1;
+            s.status = format!("Updating… {}/{}", done, total);
+            for (source, e) in &refresh.failures {
+                s.alert(AlertLevel::Warning, format!("Fetch error ({}): {} ({})", name, e, source.label()));
+            }
+            if let Some((source, e)) = refresh.failures.first() {
+                status = format!("Fetch error ({}): {} ({})", name, e, source.label());
+            }
+            match refresh.answer {
+                Some((source, p, v, epoch)) => {
+                    if let Some(b) = s.bodies.iter_mut().find(|b| b.name == name) {
+                        b.pos_au = Some(p);
+                        b.vel_au_d = Some(v);
+                        b.source = Some(Provenance { source, epoch });
+                        // Disk-cache hits keep their fetch epoch and aren't news
+                        if !source.is_fallback() && epoch == start {
+                            b.updated = Some(Instant::now());
+                        }
+                    }
+                    if !refresh.failures.is_empty() {
+                        status = format!("{} → {}", status, source.label());
+                    }
+                }
+                None if !refresh.failures.is_empty() => {
+                    s.alert(AlertLevel::Error, format!("{}: no source answered, keeping old position", name));
+                }
+                None => {}
+            }
+            let horizons_failure = refresh.failures.iter().find(|(source, _)| *source == Source::Horizons);
+            match (&refresh.fetched, horizons_failure) {
+                (Some(_), _) => queried.push((refresh.id, None)),
+                (None, Some((_, e))) => queried.push((refresh.id, Some(format!("{:#}", e)))),
+                (None, None) => {}
+            }
+            if let Some(trajectory) = refresh.fetched {
+                fetched.push((name, refresh.id, trajectory));
+            }
+        }
+        drop(refreshes);
+
+        let sun_barycentric = match config.origin {
+            Origin::Barycentric => {
+                let bodies = state.lock().unwrap().bodies.clone();
+                let (p, v, trajectory) = round.sun_barycentric(&bodies).await;
+                if let Some(trajectory) = trajectory {
+                    fetched.push(("Sun", BodyId::SUN, trajectory));
                 }
+                Some((p, v))
+            }
+            Origin::Heliocentric => None,
+        };
+
+        for (id, failure) in queried {
+            match failure {
+                Some(e) => retry.failed(id, e, now),
+                None => retry.succeeded(id),
+            }
+        }
+        retry.retain(&ids);
+
+        if !fetched.is_empty() {
+            for (name, id, trajectory) in fetched {
+                // The Sun is only ever fetched about the barycentre
+                let overrides = if id == BodyId::SUN { Some(barycentre_overrides()) } else { config.overrides_for(name).cloned() };
+                responses.insert(id, overrides.as_ref(), trajectory);
+            }
+            if let Err(e) = responses.save() {
+                state.lock().unwrap().alert(AlertLevel::Warning, format!("Horizons cache not saved: {:#}", e));
             }
+        }
+
+        {
+            let mut s = state.lock().unwrap();
             s.last_update_utc = Some(now_label);
             s.status = status;
+            s.sun_barycentric = sun_barycentric;
+            if let Some(warning) = take_api_warning() {
+                s.alert(AlertLevel::Warning, warning);
+            }
+            s.retry_queue = retry.clone();
+            events::refresh(&mut s);
+            trails::record(&mut s);
         }
 
-        sleep(Duration::from_secs(5)).await;
+        // Early for a failed body falling due, though never more than once a second
+        let until_retry = retry.next_due().and_then(|t| (t - Utc::now()).to_std().ok());
+        let wait = until_retry.map_or(config.refresh_interval(), |d| d.max(Duration::from_secs(1)).min(config.refresh_interval()));
+        sleep(wait).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TimeScale;
+    use crate::ephemeris;
+    use crate::types::meta_by_name;
+
+    #[test]
+    fn trajectory_interpolates_between_rows() {
+        let t0 = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let rows = (0..=6)
+            .map(|i| {
+                let utc = t0 + ChronoDuration::minutes(10 * i);
+                let (pos, vel) = ephemeris::state_at("Mercury", utc).unwrap();
+                TrajectoryRow { utc, pos, vel }
+            })
+            .collect();
+        let trajectory = Trajectory { fetched: t0, rows };
+
+        let t = t0 + ChronoDuration::seconds(1_234);
+        let (p, v) = trajectory.state_at(t).unwrap();
+        let (p_ref, v_ref) = ephemeris::state_at("Mercury", t).unwrap();
+        assert!((p - p_ref).norm() < 1e-10, "position off by {} AU", (p - p_ref).norm());
+        assert!((v - v_ref).norm() < 1e-7, "velocity off by {} AU/d", (v - v_ref).norm());
+        assert!(trajectory.state_at(t0 + ChronoDuration::hours(2)).is_none());
+    }
+
+    #[test]
+    fn saves_merge_with_the_stored_cache() {
+        let path = std::env::temp_dir().join(format!("solar-rs-test-{}-horizons.json", std::process::id()));
+        let t0 = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let trajectory = |name, fetched| {
+            let (pos, vel) = ephemeris::state_at(name, t0).unwrap();
+            Trajectory { fetched, rows: vec![TrajectoryRow { utc: t0, pos, vel }] }
+        };
+        let fetched = |cache: &ResponseCache, id| cache.last_row(id, None).map(|(_, f)| f);
+        let (mercury, venus) = (meta_by_name("Mercury").unwrap().id, meta_by_name("Venus").unwrap().id);
+
+        // Two processes load the same file, then each fetches and saves
+        let mut notebook = ResponseCache::load_from(&path);
+        let mut app = ResponseCache::load_from(&path);
+        notebook.insert(mercury, None, trajectory("Mercury", t0 + ChronoDuration::hours(1)));
+        notebook.save_to(&path).unwrap();
+        app.insert(mercury, None, trajectory("Mercury", t0));
+        app.insert(venus, None, trajectory("Venus", t0));
+        app.save_to(&path).unwrap();
+
+        let stored = ResponseCache::load_from(&path);
+        assert_eq!(fetched(&stored, mercury), Some(t0 + ChronoDuration::hours(1)));
+        assert_eq!(fetched(&stored, venus), Some(t0));
+        assert_eq!(fetched(&app, mercury), Some(t0 + ChronoDuration::hours(1)));
+        std::fs::remove_file(&path).ok();
+        std::fs::remove_file(path.with_extension("json.lock")).ok();
+    }
+
+    #[cfg(feature = "net")]
+    #[test]
+    fn backoff_doubles_with_jitter_and_honours_retry_after() {
+        assert_eq!(backoff(1, None, 0.5), BACKOFF);
+        assert_eq!(backoff(2, None, 0.0), BACKOFF);
+        assert_eq!(backoff(3, None, 0.99), BACKOFF.mul_f64(4.0 * 1.49));
+        assert_eq!(backoff(1, Some(Duration::from_secs(7)), 0.9), Duration::from_secs(7));
+        assert_eq!(backoff(9, None, 0.5), MAX_BACKOFF);
+        assert!((0..100).map(|_| jitter()).all(|j| (0.0..1.0).contains(&j)));
+
+        let busy = HorizonsError::RateLimited { api: "Horizons", status: 503, retry_after: None };
+        let unknown = HorizonsError::Api { api: "Horizons", status: None, message: "No matches found.".into() };
+        assert!(busy.is_transient() && !unknown.is_transient());
+        assert_eq!(busy.to_string(), "Horizons is busy: HTTP 503");
+    }
+
+    #[test]
+    fn elements_move_on_in_anomaly_only() {
+        // Mars, 2000-Jan-01 12:00 TDB
+        let csv = "2451545.000000000, A.D. 2000-Jan-01 12:00:00.0000, 9.33e-02, 1.381, 1.8497, 49.558, 286.50, 2451940.5, 0.524, 19.41, 16.5, 1.5237, 1.666, 686.98,";
+        let row = parse_elements_row(csv).unwrap();
+        let later = row.elements_at(row.days_since_j2000() + 343.49).unwrap();
+        assert!((later.a_au - row.elements.a_au).abs() < 1e-9 && (later.e - row.elements.e).abs() < 1e-9);
+        assert!((later.i_deg - row.elements.i_deg).abs() < 1e-9);
+        // Half an orbit on, the mean anomaly has gone round 180°
+        let mean = |el: &KeplerElements| crate::orbital_mechanics::mean_from_true_anomaly_deg(el.true_anomaly_deg, el.e);
+        assert!(((mean(&later) - mean(&row.elements)).rem_euclid(360.0) - 180.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn api_version_from_signature_or_header() {
+        let signed: HorizonsJson = serde_json::from_str(r#"{"signature":{"source":"NASA/JPL Horizons API","version":"1.2"},"result":""}"#).unwrap();
+        assert_eq!(api_version(&signed).as_deref(), Some("1.2"));
+        let header: HorizonsJson = serde_json::from_str(r#"{"result":"API VERSION: 1.1\nAPI SOURCE: NASA/JPL Horizons API\n"}"#).unwrap();
+        assert_eq!(api_version(&header).as_deref(), Some("1.1"));
+        let bare: HorizonsJson = serde_json::from_str(r#"{"result":"$$SOE\n$$EOE"}"#).unwrap();
+        assert_eq!(api_version(&bare), None);
+    }
+
+    #[test]
+    fn retry_queue_backs_off_per_body() {
+        let t0 = DateTime::parse_from_rfc3339("2026-01-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let (mars, venus) = (BodyId(499), BodyId(299));
+        let mut queue = RetryQueue::default();
+        queue.failed(mars, "timed out".into(), t0);
+        assert!(queue.holds(mars, t0 + ChronoDuration::seconds(19)) && !queue.holds(mars, t0 + ChronoDuration::seconds(20)));
+        queue.failed(mars, "timed out".into(), t0);
+        queue.failed(mars, "timed out".into(), t0);
+        assert_eq!(queue.get(mars).map(|f| f.failures), Some(3));
+        assert_eq!(queue.next_due(), Some(t0 + ChronoDuration::seconds(80)));
+        assert!(!queue.holds(venus, t0));
+        assert_eq!(requery_delay(30), MAX_REQUERY);
+
+        queue.succeeded(mars);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn row_time_from_julian_date() {
+        let row = "2451545.000000000, A.D. 2000-Jan-01 12:00:00.0000, 0, 0, 0, 0, 0, 0,";
+        assert_eq!(row_time(row, true).unwrap().to_rfc3339(), "2000-01-01T12:00:00+00:00");
+        assert_eq!(row_time(row, false).unwrap().to_rfc3339(), "2000-01-01T11:58:50.816+00:00");
+
+        // What the header shows for that instant on either scale reads the row back
+        let utc = row_time(row, false).unwrap();
+        assert!((TimeScale::Tdb.julian_date(utc) - 2_451_545.0).abs() < 1e-8);
+        assert!((TimeScale::Ut.julian_date(utc) - (2_451_545.0 - TDB_MINUS_UTC_S / 86_400.0)).abs() < 1e-8);
+    }
+
+    #[test]
+    fn observer_rows_interpolate_across_north() {
+        let a = parse_observer_row("2461330.000000000, , , 150.00000, 10.00000, 359.00000, 20.00000,").unwrap();
+        let b = parse_observer_row("2461330.006944444,*,m, 150.10000, 10.10000, 3.00000, 21.00000,").unwrap();
+        let mid = sky_at(&[a, b], a.utc + (b.utc - a.utc) / 2).unwrap();
+        assert!((mid.az_deg - 1.0).abs() < 1e-6, "az {}", mid.az_deg);
+        assert!((mid.alt_deg - 20.5).abs() < 1e-6, "alt {}", mid.alt_deg);
+        assert!(sky_at(&[a, b], b.utc + ChronoDuration::minutes(1)).is_none());
     }
 }
@@ -0,0 +1,69 @@
+//! Observation journal: timestamped notes per body, appended as JSON lines to a local file.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{io::Write, path::PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub utc: DateTime<Utc>,
+    pub body: String,
+    pub seeing: String,
+    pub instrument: String,
+    pub comments: String,
+    /// RA/Dec at the time of the note (topocentric when that is switched on), when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ra_deg: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dec_deg: Option<f64>,
+}
+
+pub const NOTE_FIELDS: [&str; 3] = ["Seeing", "Instrument", "Comments"];
+
+/// A note being typed; Tab moves between the fields.
+#[derive(Debug, Clone)]
+pub struct NoteDraft {
+    pub body: &'static str,
+    pub field: usize,
+    pub values: [String; 3],
+}
+
+impl NoteDraft {
+    pub fn new(body: &'static str) -> Self {
+        NoteDraft { body, field: 0, values: Default::default() }
+    }
+
+    pub fn into_entry(self, ra_dec: Option<(f64, f64)>) -> JournalEntry {
+        let [seeing, instrument, comments] = self.values.map(|v| v.trim().to_string());
+        JournalEntry {
+            utc: Utc::now(),
+            body: self.body.to_string(),
+            seeing,
+            instrument,
+            comments,
+            ra_deg: ra_dec.map(|(ra, _)| ra),
+            dec_deg: ra_dec.map(|(_, dec)| dec),
+        }
+    }
+}
+
+pub fn journal_path() -> PathBuf {
+    dirs::data_dir().unwrap_or_else(std::env::temp_dir).join("solar-rs").join("journal.jsonl")
+}
+
+/// All entries, oldest first. Unreadable lines are skipped rather than failing the load.
+pub fn load() -> Vec<JournalEntry> {
+    let Ok(text) = std::fs::read_to_string(journal_path()) else { return Vec::new() };
+    text.lines().filter_map(|l| serde_json::from_str(l).ok()).collect()
+}
+
+pub fn append(entry: &JournalEntry) -> Result<()> {
+    let path = journal_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)?;
+    Ok(())
+}
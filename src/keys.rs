@@ -0,0 +1,82 @@
+//! Vim-style layer between key events and their bindings: a numeric prefix runs the next
+//! command that many times ("5+" zooms in five steps, "3]" focuses out three levels), and `.`
+//! repeats the last view command, with a new count if one is typed first.
+
+use crossterm::event::KeyCode;
+
+/// Counts beyond this are clamped; every counted command saturates well before it.
+const MAX_COUNT: usize = 999;
+
+/// Commands that `.` repeats: zoom, focus, selection and the view cycles.
+fn is_repeatable(code: KeyCode) -> bool {
+    matches!(
+        code,
+        KeyCode::Char('+' | '=' | '-' | '0' | '[' | ']' | 'v' | 'c' | 'C') | KeyCode::Up | KeyCode::Down
+    )
+}
+
+#[derive(Debug, Default)]
+pub struct KeyInterpreter {
+    count: Option<usize>,
+    last: Option<(KeyCode, usize)>,
+}
+
+impl KeyInterpreter {
+    /// The command to run for `code` and how many times, or `None` while a count is being
+    /// typed (or there is nothing to repeat yet). Esc with a count pending only drops it.
+    pub fn feed(&mut self, code: KeyCode) -> Option<(KeyCode, usize)> {
+        match code {
+            KeyCode::Char(d @ '0'..='9') if d != '0' || self.count.is_some() => {
+                let digit = d as usize - '0' as usize;
+                self.count = Some((self.count.unwrap_or(0) * 10 + digit).min(MAX_COUNT));
+                None
+            }
+            KeyCode::Esc if self.count.is_some() => {
+                self.count = None;
+                None
+            }
+            KeyCode::Char('.') => {
+                let (last, n) = self.last?;
+                let n = self.count.take().unwrap_or(n);
+                self.last = Some((last, n));
+                Some((last, n))
+            }
+            _ => {
+                let n = self.count.take().unwrap_or(1);
+                if is_repeatable(code) {
+                    self.last = Some((code, n));
+                }
+                Some((code, n))
+            }
+        }
+    }
+
+    /// Count typed so far, shown in the header like vim's `showcmd`.
+    pub fn pending(&self) -> Option<usize> {
+        self.count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed(keys: &mut KeyInterpreter, s: &str) -> Option<(KeyCode, usize)> {
+        s.chars().map(|c| keys.feed(KeyCode::Char(c))).last().flatten()
+    }
+
+    #[test]
+    fn counts_and_repeat() {
+        let mut keys = KeyInterpreter::default();
+        assert_eq!(feed(&mut keys, "0"), Some((KeyCode::Char('0'), 1)));
+        assert_eq!(feed(&mut keys, "10+"), Some((KeyCode::Char('+'), 10)));
+        assert_eq!(feed(&mut keys, "d"), Some((KeyCode::Char('d'), 1)));
+        assert_eq!(feed(&mut keys, "."), Some((KeyCode::Char('+'), 10)));
+        assert_eq!(feed(&mut keys, "3."), Some((KeyCode::Char('+'), 3)));
+        assert_eq!(feed(&mut keys, "."), Some((KeyCode::Char('+'), 3)));
+        assert_eq!(feed(&mut keys, "5"), None);
+        assert_eq!(keys.feed(KeyCode::Esc), None);
+        assert_eq!(keys.pending(), None);
+        assert_eq!(feed(&mut keys, "99999]"), Some((KeyCode::Char(']'), MAX_COUNT)));
+    }
+}
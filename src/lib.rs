@@ -0,0 +1,39 @@
+//! Solar-system state for the `solar-rs` terminal app, usable on its own: the built-in body
+//! registry, JPL Horizons and SPK readers, VSOP87 and mean-element ephemerides, two-body
+//! propagation, reference frames and orbital events. Build with `--no-default-features` to
+//! leave out the terminal UI and network clients.
+
+pub mod types;
+pub mod config;
+pub mod horizons;
+pub mod physics;
+pub mod gravity;
+pub mod orbital_mechanics;
+pub mod frames;
+pub mod ephemeris;
+pub mod vsop87;
+pub mod compare;
+pub mod events;
+pub mod export;
+pub mod journal;
+pub mod markers;
+pub mod pins;
+pub mod trails;
+pub mod tours;
+pub mod snapshot;
+pub mod milankovitch;
+pub mod observer;
+pub mod picker;
+pub mod rotation;
+pub mod sources;
+pub mod spk;
+pub mod names;
+pub mod power;
+pub mod bandwidth;
+pub mod cneos;
+#[cfg(feature = "net")]
+pub mod dns;
+#[cfg(feature = "net")]
+pub mod sbdb;
+#[cfg(all(test, feature = "validation"))]
+mod validation;
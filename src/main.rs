@@ -1,10 +1,27 @@
-mod types;
-mod horizons;
+#[cfg(feature = "net")]
+mod telescope;
+#[cfg(feature = "net")]
+mod stellarium;
+mod frame;
+mod keys;
+mod record;
+mod undo;
+#[cfg(all(unix, feature = "net"))]
+mod share;
+#[cfg(all(unix, feature = "net"))]
+mod daemon;
+#[cfg(all(unix, feature = "net"))]
+mod serve;
+#[cfg(all(unix, feature = "grpc"))]
+mod grpc;
+#[cfg(feature = "redis")]
+mod publish;
 mod ui;
+mod watch;
 
-use anyhow::Result;
+use anyhow::{anyhow, Context, Result};
 use crossterm::{
-    event::{self, Event, KeyCode},
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseButton, MouseEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -12,29 +29,455 @@ use ratatui::{backend::CrosstermBackend, Terminal};
 use std::{
     io::{self, Stdout},
     sync::{Arc, Mutex},
-    time::Duration,
+    time::Instant,
 };
 
-use crate::types::{AppState, BodyState, BODIES, FOCUS_LEVELS};
-use crate::horizons::updater;
-use crate::ui::draw_ui;
+use solar_rs::types::{self, AlertLevel, BodyId, DataState, DisplayProfile, Vec3, ViewState, DEFAULT_FOCUS, EINK_REDRAW, FOCUS_LEVELS};
+use solar_rs::config::{apply_config, load_config, ConfigPaths, Origin, Power};
+use solar_rs::frames::{ecliptic_to_equatorial, ra_dec_deg};
+use solar_rs::horizons::{updater, ResponseCache};
+#[cfg(feature = "net")]
+use solar_rs::{frames::true_of_date, horizons::{load_element_history, load_elements, load_sky, sky_at}, physics::days_since_j2000, sbdb, cneos};
+use solar_rs::journal::{NoteDraft, NOTE_FIELDS};
+use solar_rs::picker::{DatePicker, Purpose};
+use solar_rs::sources::Source;
+use solar_rs::snapshot::Snapshot;
+use solar_rs::spk::Spk;
+use solar_rs::tours::{self, TourPlayer, TourRequest};
+use solar_rs::{bandwidth, compare, events, export, gravity, journal, markers, milankovitch, names, observer, pins, power};
+use crate::keys::KeyInterpreter;
+use crate::record::Recorder;
+use crate::undo::{UndoHistory, ViewSnapshot};
+use crate::ui::{draw_ui, is_box_drag, MapProjection};
+use crate::watch::watch_config;
 
-fn setup_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
+type Backend = CrosstermBackend<Recorder<Stdout>>;
+
+/// Takes over the terminal, recording what is drawn to `record` (asciicast) if given.
+fn setup_terminal(record: Option<&str>) -> Result<Terminal<Backend>> {
+    let mut out = match record {
+        Some(path) => Recorder::to_file(io::stdout(), path.as_ref()).with_context(|| format!("--record {}", path))?,
+        None => Recorder::new(io::stdout()),
+    };
     enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    Ok(Terminal::new(CrosstermBackend::new(stdout))?)
+    execute!(out, EnterAlternateScreen)?;
+    Ok(Terminal::new(CrosstermBackend::new(out))?)
 }
 
-fn restore_terminal(mut terminal: Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
+fn restore_terminal(mut terminal: Terminal<Backend>) -> Result<()> {
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(terminal.backend_mut(), DisableMouseCapture, LeaveAlternateScreen)?;
     terminal.show_cursor()?;
     Ok(())
 }
 
+/// `solar-rs export`: writes trajectories for external viewers from what is already collected,
+/// without querying Horizons (see `solar_rs::export`).
+fn export_trajectories(state: &DataState) -> Result<()> {
+    let format = export::Format::parse(&arg_value("--format").unwrap_or_else(|| "czml".into()))?;
+    let start = match arg_value("--start") {
+        Some(text) => events::parse_epoch(&text).ok_or_else(|| anyhow!("--start: not a date: {:?}", text))?,
+        None => chrono::Utc::now(),
+    };
+    let number = |name: &str, default: f64| -> Result<f64> {
+        match arg_value(name) {
+            Some(v) => v.parse().ok().filter(|n: &f64| *n > 0.0).ok_or_else(|| anyhow!("{}: not a positive number: {:?}", name, v)),
+            None => Ok(default),
+        }
+    };
+    let hours = number("--hours", 72.0)?;
+    let step = chrono::Duration::seconds((number("--step-minutes", 60.0)? * 60.0).max(1.0) as i64);
+    let stop = start + chrono::Duration::seconds((hours * 3600.0) as i64);
+
+    let tracks = export::tracks(state, &ResponseCache::load(), start, stop, step);
+    if !tracks.iter().any(|t| t.id == BodyId::EARTH) {
+        return Err(anyhow!("Earth has no cached or local vectors over that span; nothing to export"));
+    }
+    let doc = match format {
+        export::Format::Czml => export::czml(&tracks),
+        export::Format::GeoJson => export::geojson(&tracks),
+    };
+    let text = serde_json::to_string_pretty(&doc)?;
+    match arg_value("--out") {
+        Some(path) => {
+            std::fs::write(&path, text)?;
+            eprintln!("solar-rs export: {} bodies to {}", tracks.len() - 1, path);
+        }
+        // Not `println!`, which panics when piped into `head`
+        None => std::io::Write::write_all(&mut std::io::stdout().lock(), format!("{}\n", text).as_bytes())?,
+    }
+    Ok(())
+}
+
+/// `solar-rs compare --file <csv>`: residuals of the file's positions against a local source,
+/// `--source` if it is one, else `reference_source` (see `solar_rs::compare`).
+fn compare_file(state: &DataState) -> Result<()> {
+    let path = arg_value("--file").ok_or_else(|| anyhow!("compare: --file <csv> is required"))?;
+    let text = std::fs::read_to_string(&path).map_err(|e| anyhow!("{}: {}", path, e))?;
+    let rows = compare::parse(&text, has_arg("--km")).map_err(|e| anyhow!("{}: {:#}", path, e))?;
+    let source = state.source.unwrap_or(state.config.reference_source);
+    if !source.is_local() {
+        return Err(anyhow!("compare: --source must be a local source (vsop87, keplerian or spk), not {}", source.label()));
+    }
+    let residuals = compare::residuals(state, source, &rows);
+    print!("{}", compare::report(&rows, &residuals, source));
+    Ok(())
+}
+
+/// `render-frame`: one frame from a snapshot fixture on stdout (see [`frame::load`]).
+fn render_frame(state: &mut DataState, view: &mut ViewState) -> Result<()> {
+    let path = arg_value("--state").ok_or_else(|| anyhow!("render-frame: --state <snapshot.json> is required"))?;
+    let size = frame::parse_size(&arg_value("--size").unwrap_or_else(|| "120x40".into()))?;
+    frame::load(state, view, Snapshot::read(path.as_ref())?);
+    let text = frame::render(state, view, size, has_arg("--ansi"))?;
+    // Not `print!`, which panics when piped into `head`; a reader that stops early is fine
+    match std::io::Write::write_all(&mut std::io::stdout().lock(), text.as_bytes()) {
+        Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+/// Every `--flag` can also be set as `SOLAR_RS_FLAG` (e.g. `--no-share` → `SOLAR_RS_NO_SHARE`),
+/// which is how containers configure the app.
+fn env_name(flag: &str) -> String {
+    format!("SOLAR_RS_{}", flag.trim_start_matches('-').replace('-', "_").to_uppercase())
+}
+
 fn has_arg(name: &str) -> bool {
     std::env::args().any(|a| a == name)
+        || std::env::var(env_name(name)).is_ok_and(|v| !matches!(v.as_str(), "" | "0" | "false"))
+}
+
+fn arg_value(name: &str) -> Option<String> {
+    let mut args = std::env::args();
+    while let Some(a) = args.next() {
+        if a == name {
+            return args.next();
+        }
+        if let Some(v) = a.strip_prefix(name).and_then(|rest| rest.strip_prefix('=')) {
+            return Some(v.to_string());
+        }
+    }
+    std::env::var(env_name(name)).ok()
+}
+
+/// Keys while an observation note is open: type into the current field, Tab to move on,
+/// Enter to save to the journal, Esc to discard.
+fn note_key(state: &mut DataState, view: &mut ViewState, code: KeyCode) {
+    let Some(draft) = view.note.as_mut() else { return };
+    match code {
+        KeyCode::Esc => view.note = None,
+        KeyCode::Tab => draft.field = (draft.field + 1) % NOTE_FIELDS.len(),
+        KeyCode::BackTab => draft.field = (draft.field + NOTE_FIELDS.len() - 1) % NOTE_FIELDS.len(),
+        KeyCode::Backspace => {
+            draft.values[draft.field].pop();
+        }
+        KeyCode::Char(c) => draft.values[draft.field].push(c),
+        KeyCode::Enter => {
+            let Some(draft) = view.note.take() else { return };
+            let ra_dec = state
+                .bodies
+                .iter()
+                .find(|b| b.name == draft.body && b.id != BodyId::EARTH)
+                .and_then(|b| observer::line_of_sight(state, view, b, chrono::Utc::now()))
+                .map(|d| ra_dec_deg(ecliptic_to_equatorial(d)));
+            let entry = draft.into_entry(ra_dec);
+            match journal::append(&entry) {
+                Ok(()) => {
+                    state.status = format!("Logged note for {}", entry.body);
+                    state.journal.push(entry);
+                }
+                Err(e) => {
+                    let msg = format!("Could not write {}: {:#}", journal::journal_path().display(), e);
+                    state.alert(AlertLevel::Error, msg.clone());
+                    state.popup = Some(msg);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Keys while find-by-name is open: Enter selects the first body whose name in any language
+/// starts with the query, or else looks the name up in the Small-Body Database to add it; Esc
+/// cancels.
+fn find_key(state: &Arc<Mutex<DataState>>, view: &mut ViewState, code: KeyCode) {
+    let Some(query) = view.find.as_mut() else { return };
+    match code {
+        KeyCode::Esc => view.find = None,
+        KeyCode::Backspace => {
+            query.pop();
+        }
+        KeyCode::Char(c) => query.push(c),
+        KeyCode::Enter => {
+            let Some(query) = view.find.take() else { return };
+            let mut s = state.lock().unwrap();
+            match names::find_body(&s.bodies, &query) {
+                Some(i) => {
+                    view.selected = i;
+                    sync_stellarium(state, &s, view);
+                }
+                #[cfg(feature = "net")]
+                None => {
+                    s.status = format!("Looking up \"{}\" in the Small-Body Database…", query);
+                    tokio::spawn(sbdb::add_body(state.clone(), query));
+                }
+                #[cfg(not(feature = "net"))]
+                None => s.status = format!("No body named \"{}\"", query),
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Keys while a timeline marker is being named: Enter drops it at the epoch on screen, Esc
+/// cancels.
+fn marker_key(state: &mut DataState, view: &mut ViewState, code: KeyCode) {
+    let Some(name) = view.marker.as_mut() else { return };
+    match code {
+        KeyCode::Esc => view.marker = None,
+        KeyCode::Backspace => {
+            name.pop();
+        }
+        KeyCode::Char(c) => name.push(c),
+        KeyCode::Enter => {
+            let Some(name) = view.marker.take().map(|n| n.trim().to_string()).filter(|n| !n.is_empty()) else { return };
+            let utc = view.goto.as_ref().map(|g| g.utc).or_else(|| events::epoch(state)).unwrap_or_else(chrono::Utc::now);
+            markers::insert(&mut state.markers, markers::Marker { name: name.clone(), utc });
+            match markers::save(&state.markers) {
+                Ok(()) => state.status = format!("Marked \"{}\" at {}", name, utc.format("%Y-%m-%d %H:%M")),
+                Err(e) => {
+                    let msg = format!("Could not write {}: {:#}", markers::markers_path().display(), e);
+                    state.alert(AlertLevel::Error, msg.clone());
+                    state.popup = Some(msg);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Keys while a map pin is being named: Enter drops it (moving any pin of that name), Esc
+/// cancels.
+fn pin_key(state: &mut DataState, view: &mut ViewState, code: KeyCode) {
+    let Some((_, name)) = view.pin.as_mut() else { return };
+    match code {
+        KeyCode::Esc => view.pin = None,
+        KeyCode::Backspace => {
+            name.pop();
+        }
+        KeyCode::Char(c) => name.push(c),
+        KeyCode::Enter => {
+            let Some((p, name)) = view.pin.take().map(|(p, n)| (p, n.trim().to_string())).filter(|(_, n)| !n.is_empty()) else { return };
+            pins::drop_pin(&mut state.pins, &name, p);
+            save_pins(state, format!("Pinned \"{}\" at {:+.3} {:+.3} AU", name, p.x, p.y));
+        }
+        _ => {}
+    }
+}
+
+/// Starts the named tour from the config's `tours` directory, in place of any playback.
+fn play_tour(state: &mut DataState, view: &mut ViewState, paths: Option<&ConfigPaths>, name: &str) {
+    let Some(paths) = paths else { return };
+    let started = tours::load(&paths.tour(name)).and_then(|t| TourPlayer::new(name, &t, &state.bodies, Instant::now()));
+    match started {
+        Ok(t) => {
+            view.playback = None;
+            view.tour = Some(t);
+            state.tour_playing = Some(name.to_string());
+            state.status = format!("Playing tour \"{}\" (y or Esc to stop)", name);
+        }
+        Err(e) => state.notify(AlertLevel::Warning, format!("{:#}", e)),
+    }
+}
+
+/// Hands the map back where the tour left its zoom, at live time.
+fn stop_tour(state: &mut DataState, view: &mut ViewState, why: &str) {
+    if view.tour.take().is_some() {
+        view.camera = None;
+        view.goto = None;
+        state.tour_playing = None;
+        state.status = why.into();
+    }
+}
+
+/// Writes the pins after a change, reporting `done` or why the file couldn't be written.
+fn save_pins(state: &mut DataState, done: String) {
+    match pins::save(&state.pins) {
+        Ok(()) => state.status = done,
+        Err(e) => {
+            let msg = format!("Could not write {}: {:#}", pins::pins_path().display(), e);
+            state.alert(AlertLevel::Error, msg.clone());
+            state.popup = Some(msg);
+        }
+    }
+}
+
+/// Keys while the date picker is open: ←/→ (or Tab) move between fields, ↑/↓ step the one
+/// selected and PgUp/PgDn step it by ten, `n` picks now, Enter confirms and Esc cancels.
+fn picker_key(state: &Arc<Mutex<DataState>>, view: &mut ViewState, code: KeyCode) {
+    let Some(picker) = view.picker.as_mut() else { return };
+    match code {
+        KeyCode::Esc => view.picker = None,
+        KeyCode::Left | KeyCode::BackTab => picker.prev_field(),
+        KeyCode::Right | KeyCode::Tab => picker.next_field(),
+        KeyCode::Up => picker.step(1),
+        KeyCode::Down => picker.step(-1),
+        KeyCode::PageUp => picker.step(10),
+        KeyCode::PageDown => picker.step(-10),
+        // Back to the present: time travel ends, a range bound moves to now
+        KeyCode::Char('n') if picker.purpose == Purpose::TimeTravel => {
+            view.picker = None;
+            let mut s = state.lock().unwrap();
+            if s.sim_epoch.take().is_some() {
+                s.status = "Back to now, fetching current vectors…".into();
+            }
+        }
+        KeyCode::Char('n') => *picker = DatePicker { utc: chrono::Utc::now(), ..picker.clone() },
+        KeyCode::Enter => {
+            let utc = picker.utc;
+            match picker.purpose {
+                Purpose::TimeTravel => {
+                    view.picker = None;
+                    view.goto = None;
+                    view.playback = None;
+                    let mut s = state.lock().unwrap();
+                    s.sim_epoch = Some(utc);
+                    s.status = format!("Travelling to {} UTC, fetching vectors…", utc.format("%Y-%m-%d %H:%M"));
+                }
+                Purpose::RangeStart => {
+                    view.picker = Some(DatePicker::new(utc + chrono::Duration::days(365), Purpose::RangeEnd(utc)));
+                }
+                Purpose::RangeEnd(from) if utc <= from => {
+                    state.lock().unwrap().status = "The range must end after it starts".into();
+                }
+                Purpose::RangeEnd(from) => {
+                    view.picker = None;
+                    start_range(state, view, from, utc);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Fetches vector tables over `from`–`to` and starts a paused playback at its beginning.
+fn start_range(state: &Arc<Mutex<DataState>>, view: &mut ViewState, from: chrono::DateTime<chrono::Utc>, to: chrono::DateTime<chrono::Utc>) {
+    view.playback = Some(events::Playback::over(from, to));
+    let mut s = state.lock().unwrap();
+    #[cfg(feature = "net")]
+    {
+        s.status = format!("Fetching {} – {} for every body…", from.format("%Y-%m-%d"), to.format("%Y-%m-%d"));
+        tokio::spawn(solar_rs::horizons::load_range(state.clone(), from, to));
+    }
+    #[cfg(not(feature = "net"))]
+    {
+        s.status = "This build has no network access; the range plays by propagation".into();
+    }
+}
+
+/// Zooms and pans the map to take in the box with corners `a` and `b`, as far as the zoom
+/// limits allow.
+fn zoom_to_box(view: &mut ViewState, map: &MapProjection, a: Vec3, b: Vec3) {
+    let (factor, shift) = map.fit(a, b);
+    view.zoom = clamp_zoom(view.zoom * factor);
+    view.pan = view.pan + shift;
+}
+
+/// Starts a new measurement once one is complete.
+fn add_measure_point(view: &mut ViewState, p: Vec3) {
+    if view.measure.len() >= 2 {
+        view.measure.clear();
+    }
+    view.measure.push(p);
+}
+
+/// Enter in the events panel: shows the selected entry at its time. An event is centred on the
+/// body for a node passage and on Earth for an appulse or occultation, with the focus widened
+/// to take in the bodies involved; a marker keeps the view as it is.
+fn go_to_event(s: &mut DataState, view: &mut ViewState) {
+    view.playback = None;
+    let selected = events::timeline(s).get(view.event_selected).map(|entry| match *entry {
+        events::TimelineEntry::Event(e) => Ok(e.clone()),
+        events::TimelineEntry::Marker(m) => Err(m.clone()),
+    });
+    let e = match selected {
+        Some(Ok(e)) => e,
+        Some(Err(m)) => {
+            s.status = format!("At \"{}\" (Esc returns to now)", m.name);
+            view.goto = Some(events::GoTo { utc: m.utc, label: format!("\"{}\"", m.name), bodies: Vec::new() });
+            return;
+        }
+        None => return,
+    };
+    let mut at = s.clone();
+    events::shift_to(&mut at, e.utc);
+    let id = |name: &str| at.bodies.iter().find(|b| b.name == name).map(|b| b.id);
+    let pos = |id: Option<BodyId>| id.and_then(|id| at.body(id)?.pos_au);
+    let (centre, reach) = match e.other {
+        Some(other) => {
+            let earth = Some(BodyId::EARTH);
+            (earth, pos(earth).zip(pos(id(other))).map(|(earth, p)| (p - earth).norm()))
+        }
+        None => (id(e.body), pos(id(e.body)).map(|p| p.norm())),
+    };
+    if let Some(reach) = reach {
+        view.focus_index = FOCUS_LEVELS.iter().position(|&(_, au)| au >= reach * 1.05).unwrap_or(FOCUS_LEVELS.len() - 1);
+        view.zoom = 1.0;
+    }
+    view.relative_to = centre;
+    let label = format!("{} {}", e.body, e.kind.label());
+    s.status = format!("At {} (Esc returns to now)", label);
+    view.goto = Some(events::GoTo { utc: e.utc, label, bodies: std::iter::once(e.body).chain(e.other).collect() });
+}
+
+/// Delete in the events panel: removes the selected entry if it is a marker.
+fn delete_marker(s: &mut DataState, view: &ViewState) {
+    let Some(&events::TimelineEntry::Marker(m)) = events::timeline(s).get(view.event_selected) else { return };
+    let Some(i) = s.markers.iter().position(|k| k == m) else { return };
+    let removed = s.markers.remove(i);
+    match markers::save(&s.markers) {
+        Ok(()) => s.status = format!("Removed marker \"{}\"", removed.name),
+        Err(e) => s.alert(AlertLevel::Error, format!("Could not write {}: {:#}", markers::markers_path().display(), e)),
+    }
+}
+
+/// Points Stellarium at the selected body, if configured. Failures only touch the status line.
+#[cfg(feature = "net")]
+fn sync_stellarium(state: &Arc<Mutex<DataState>>, s: &DataState, view: &ViewState) {
+    let Some(config) = s.config.stellarium.clone() else { return };
+    // Stellarium observes from Earth, so there is nothing to centre for it.
+    let Some(body) = s.bodies.get(view.selected).filter(|b| b.id != BodyId::EARTH).map(|b| b.name) else { return };
+    let epoch = events::epoch(s).unwrap_or_else(chrono::Utc::now);
+    let state = state.clone();
+    tokio::spawn(async move {
+        if let Err(e) = stellarium::sync(&config, body, epoch).await {
+            state.lock().unwrap().notify(AlertLevel::Warning, format!("Stellarium sync failed: {:#}", e));
+        }
+    });
+}
+
+#[cfg(not(feature = "net"))]
+fn sync_stellarium(_: &Arc<Mutex<DataState>>, _: &DataState, _: &ViewState) {}
+
+/// Whether every body but Earth has an observer table covering now.
+#[cfg(feature = "net")]
+fn sky_tables_current(s: &DataState) -> bool {
+    let now = chrono::Utc::now();
+    s.bodies
+        .iter()
+        .filter(|b| b.id != BodyId::EARTH)
+        .all(|b| s.sky.get(&b.id).is_some_and(|rows| sky_at(rows, now).is_some()))
+}
+
+/// Whether every body orbiting the Sun has Horizons elements from within the last day.
+#[cfg(feature = "net")]
+fn elements_current(s: &DataState) -> bool {
+    let today = days_since_j2000(chrono::Utc::now());
+    s.bodies
+        .iter()
+        .filter(|b| b.id != BodyId::SUN && types::meta_by_id(b.id).is_some_and(|m| m.parent.is_none()))
+        .all(|b| s.fresh_elements.get(b.name).is_some_and(|row| (today - row.days_since_j2000()).abs() < 1.0))
 }
 
 fn clamp_zoom(z: f64) -> f64 {
@@ -44,67 +487,782 @@ fn clamp_zoom(z: f64) -> f64 {
 #[tokio::main]
 async fn main() -> Result<()> {
     let use_unicode_icons = has_arg("--unicode");
+    let config_paths = ConfigPaths::resolve(arg_value("--config").map(Into::into));
+    let profile = arg_value("--profile");
+    let spk = arg_value("--spk").map(|path| Spk::open(path.as_ref())).transpose()?.map(Arc::new);
+    // A kernel is only worth loading to use it, so it becomes the source unless one is named
+    let source = match arg_value("--source") {
+        Some(name) => Some(Source::parse(&name)?),
+        None => spk.as_ref().map(|_| Source::Spk),
+    };
+    let config = load_config(config_paths.as_ref(), profile.as_deref())?;
+    for b in &config.extra_bodies {
+        if let Some(color) = b.color.as_deref().filter(|c| c.parse::<ratatui::style::Color>().is_err()) {
+            return Err(anyhow!("extra body {}: unknown color {:?}", b.name, color));
+        }
+    }
+    for b in &config.extra_bodies {
+        types::register_extra_body(b);
+    }
+    let bodies = config.body_states(&[]);
 
-    let bodies = BODIES
-        .iter()
-        .map(|m| BodyState { name: m.name, id: m.id, pos_au: None })
-        .collect::<Vec<_>>();
-
-    let state = Arc::new(Mutex::new(AppState {
-        bodies,
-        last_update_utc: None,
-        status: "Starting…".into(),
-        use_unicode_icons,
-        zoom: 1.0,
-        focus_index: FOCUS_LEVELS.len() - 1, // default: Neptune fit
+    let config = Arc::new(config);
+    let mut view = ViewState::new(config.clone(), use_unicode_icons);
+    if let Some(name) = arg_value("--display-profile") {
+        view.display = DisplayProfile::parse(&name)?;
+    }
+    if let Some(name) = arg_value("--center") {
+        let i = names::find_body(&bodies, &name).ok_or_else(|| anyhow!("--center: no body named {:?}", name))?;
+        view.relative_to = Some(bodies[i].id).filter(|&id| id != BodyId::SUN);
+    }
+    let state = Arc::new(Mutex::new(DataState {
+        journal: journal::load(),
+        markers: markers::load(),
+        pins: pins::load(),
+        // Played on the first frame, for kiosks
+        tour_request: arg_value("--tour").map(TourRequest::Play),
+        source,
+        spk,
+        offline: has_arg("--offline"),
+        profile,
+        bandwidth: bandwidth::current(),
+        ..DataState::new(config, bodies)
     }));
 
+    // Hot-reload; skipped when there is no config directory to watch
+    let _watcher = config_paths.clone().and_then(|p| watch_config(p, state.clone()).ok());
+
+    match std::env::args().nth(1).as_deref() {
+        Some("export") => return export_trajectories(&state.lock().unwrap()),
+        Some("compare") => return compare_file(&state.lock().unwrap()),
+        Some("render-frame") => return render_frame(&mut state.lock().unwrap(), &mut view),
+        _ => {}
+    }
+
+    #[cfg(feature = "redis")]
+    publish::start(state.clone(), arg_value("--redis"));
+
+    #[cfg(all(unix, feature = "net"))]
+    match std::env::args().nth(1).as_deref() {
+        Some("daemon") => return daemon::run(state).await,
+        Some("serve") => {
+            let listen = arg_value("--listen").unwrap_or_else(|| "127.0.0.1:8080".into());
+            return serve::run(state, &listen).await;
+        }
+        #[cfg(feature = "grpc")]
+        Some("grpc") => {
+            let listen = arg_value("--listen").unwrap_or_else(|| "127.0.0.1:50051".into());
+            return grpc::run(state, &listen).await;
+        }
+        _ => {}
+    }
+
+    #[cfg(all(unix, feature = "net"))]
+    if has_arg("--no-share") {
+        tokio::spawn(updater(state.clone()));
+    } else {
+        share::start(state.clone()).await;
+    }
+    #[cfg(not(all(unix, feature = "net")))]
     tokio::spawn(updater(state.clone()));
 
-    let mut terminal = setup_terminal()?;
+    tokio::spawn(power::monitor(state.clone()));
+    #[cfg(all(unix, feature = "net"))]
+    if let Some(listen) = arg_value("--control") {
+        serve::start_control(state.clone(), config_paths.clone(), listen);
+    }
+
+    let mut terminal = setup_terminal(arg_value("--record").as_deref())?;
+    let mut keys = KeyInterpreter::default();
+    let mut undo = UndoHistory::default();
+
+    // E-ink: full redraws every `EINK_REDRAW`, otherwise only in answer to input
+    let mut full_redraw_at: Option<Instant> = None;
+    let mut dirty = true;
+    // Where the last frame drew the orbit map, for placing mouse clicks on it
+    let mut map: Option<MapProjection> = None;
+    // One field sample at a time, so an older one can't land after a newer one
+    let mut field_task: Option<tokio::task::JoinHandle<()>> = None;
+    // Started the first time the close-approach panel opens
+    #[cfg(feature = "net")]
+    let mut neo_task: Option<tokio::task::JoinHandle<()>> = None;
 
     loop {
-        let snapshot = { state.lock().unwrap().clone() };
-        terminal.draw(|f| draw_ui(f, &snapshot))?;
+        let (mut snapshot, request) = {
+            let mut s = state.lock().unwrap();
+            let request = s.tour_request.take();
+            (s.clone(), request)
+        };
+        view.sync(&snapshot);
+        match request {
+            Some(TourRequest::Play(name)) => play_tour(&mut state.lock().unwrap(), &mut view, config_paths.as_ref(), &name),
+            Some(TourRequest::Stop) => stop_tour(&mut state.lock().unwrap(), &mut view, "Tour stopped"),
+            None => {}
+        }
+        let now = Instant::now();
+        if let Some(p) = &mut view.playback {
+            p.tick(now);
+            view.goto = Some(p.goto());
+        }
+        // A tour sets the epoch before the shift, then the camera from the shifted bodies
+        if view.tour.as_ref().is_some_and(|t| t.frame(now).is_none()) {
+            stop_tour(&mut state.lock().unwrap(), &mut view, "Tour ended");
+        }
+        if let Some(t) = &view.tour {
+            let label = format!("tour \"{}\"", t.name);
+            view.goto = t.frame(now).and_then(|f| f.epoch(chrono::Utc::now())).map(|utc| events::GoTo { utc, label, bodies: Vec::new() });
+        }
+        if let Some(e) = &view.goto {
+            events::shift_to(&mut snapshot, e.utc);
+            // Within a fetched range the tables take over from propagation
+            if let Some(range) = snapshot.range.clone().filter(|r| r.covers(e.utc)) {
+                range.apply(&mut snapshot, e.utc);
+            }
+        }
+        if let Some(f) = view.tour.as_ref().and_then(|t| t.frame(now)) {
+            let (span, centre) = f.camera(&snapshot.bodies);
+            (view.focus_index, view.zoom) = tours::focus_and_zoom(span);
+            view.camera = Some(centre);
+        }
+        if view.gravity_field && snapshot.config.power != Power::Low && field_task.as_ref().is_none_or(|t| t.is_finished()) {
+            let window = ui::field_window(&snapshot, &view);
+            if snapshot.gravity_field.as_ref().is_none_or(|f| f.window != window) {
+                let attractors = gravity::attractors(&snapshot.bodies);
+                let state = state.clone();
+                field_task = Some(tokio::task::spawn_blocking(move || {
+                    let field = gravity::sample(window, &attractors);
+                    state.lock().unwrap().gravity_field = Some(Arc::new(field));
+                }));
+            }
+        }
+        let eink = view.display == DisplayProfile::Eink;
+        let due = full_redraw_at.is_none_or(|t| t.elapsed() >= EINK_REDRAW);
+        if !eink || dirty || due {
+            if eink && due {
+                terminal.clear()?;
+                full_redraw_at = Some(Instant::now());
+            }
+            terminal.draw(|f| map = draw_ui(f, &snapshot, &view))?;
+            dirty = false;
+        }
 
-        if event::poll(Duration::from_millis(50))? {
-            if let Event::Key(k) = event::read()? {
-                match k.code {
+        let throttled = snapshot.thermal.is_some_and(|t| t.throttled());
+        let timeout = match full_redraw_at {
+            Some(t) if eink => EINK_REDRAW.saturating_sub(t.elapsed()),
+            _ => snapshot.config.frame_interval(throttled),
+        };
+        if event::poll(timeout)? {
+            let event = event::read()?;
+            dirty = true;
+            if let Event::Mouse(m) = event {
+                if view.mouse && matches!(m.kind, MouseEventKind::Moved | MouseEventKind::Drag(_)) {
+                    view.pointer = Some((m.column, m.row));
+                }
+                // A click places a measure point, a drag zooms to the box it drew
+                match m.kind {
+                    MouseEventKind::Down(MouseButton::Left) => view.drag_from = Some((m.column, m.row)),
+                    MouseEventKind::Up(MouseButton::Left) => {
+                        let (Some(from), Some(map)) = (view.drag_from.take(), map) else { continue };
+                        let to = (m.column, m.row);
+                        let Some((a, b)) = map.point_at(from.0, from.1).zip(map.point_at(to.0, to.1)) else { continue };
+                        if is_box_drag(from, to) {
+                            let before = ViewSnapshot::of(&view, &state.lock().unwrap());
+                            zoom_to_box(&mut view, &map, a, b);
+                            undo.record(before, ViewSnapshot::of(&view, &state.lock().unwrap()));
+                        } else {
+                            add_measure_point(&mut view, b);
+                        }
+                    }
+                    _ => {}
+                }
+                continue;
+            }
+            if let Event::Key(k) = event {
+                if view.note.is_some() {
+                    note_key(&mut state.lock().unwrap(), &mut view, k.code);
+                    continue;
+                }
+                if view.find.is_some() {
+                    find_key(&state, &mut view, k.code);
+                    continue;
+                }
+                if view.marker.is_some() {
+                    marker_key(&mut state.lock().unwrap(), &mut view, k.code);
+                    continue;
+                }
+                if view.pin.is_some() {
+                    pin_key(&mut state.lock().unwrap(), &mut view, k.code);
+                    continue;
+                }
+                // Time travel is an epoch change undo steps back through
+                if view.picker.is_some() {
+                    let before = ViewSnapshot::of(&view, &state.lock().unwrap());
+                    picker_key(&state, &mut view, k.code);
+                    undo.record(before, ViewSnapshot::of(&view, &state.lock().unwrap()));
+                    continue;
+                }
+                let command = keys.feed(k.code);
+                view.count = keys.pending();
+                let Some((code, n)) = command else { continue };
+                let ctrl = k.modifiers.contains(KeyModifiers::CONTROL);
+                let shift = k.modifiers.contains(KeyModifiers::SHIFT);
+                let before = ViewSnapshot::of(&view, &state.lock().unwrap());
+                match code {
                     KeyCode::Char('q') => break,
 
+                    // undo / redo view changes; `continue` so they aren't recorded as changes
+                    KeyCode::Char('u') => {
+                        let mut s = state.lock().unwrap();
+                        let steps = (0..n).take_while(|_| undo.undo(&mut view, &mut s)).count();
+                        s.status = if steps > 0 { format!("Undid {} view change(s)", steps) } else { "Nothing to undo".into() };
+                        continue;
+                    }
+                    KeyCode::Char('r') if ctrl => {
+                        let mut s = state.lock().unwrap();
+                        let steps = (0..n).take_while(|_| undo.redo(&mut view, &mut s)).count();
+                        s.status = if steps > 0 { format!("Redid {} view change(s)", steps) } else { "Nothing to redo".into() };
+                        continue;
+                    }
+
+                    KeyCode::Esc if view.tour.is_some() => {
+                        stop_tour(&mut state.lock().unwrap(), &mut view, "Tour stopped");
+                    }
+                    // dismiss popup, then the measurement, then leave an event jumped to
+                    KeyCode::Esc => {
+                        let mut s = state.lock().unwrap();
+                        if s.popup.take().is_none() && std::mem::take(&mut view.measure).is_empty() && view.goto.take().is_some() {
+                            view.playback = None;
+                            s.status = "Back to now".into();
+                        }
+                    }
+                    // jump to the selected event
+                    KeyCode::Enter if view.show_events => {
+                        go_to_event(&mut state.lock().unwrap(), &mut view);
+                    }
+                    KeyCode::Delete if view.show_events => {
+                        delete_marker(&mut state.lock().unwrap(), &view);
+                    }
+                    // remove the pin under the mouse pointer
+                    KeyCode::Delete if view.mouse => {
+                        let (Some((column, row)), Some(map)) = (view.pointer, map) else { continue };
+                        let mut s = state.lock().unwrap();
+                        let Some(i) = s.pins.iter().position(|p| map.is_under(p.pos_au, column, row)) else { continue };
+                        let removed = s.pins.remove(i);
+                        save_pins(&mut s, format!("Removed pin \"{}\"", removed.name));
+                    }
+                    // play the nth camera tour by name, or stop the one playing
+                    KeyCode::Char('y') => {
+                        let mut s = state.lock().unwrap();
+                        if view.tour.is_some() {
+                            stop_tour(&mut s, &mut view, "Tour stopped");
+                            continue;
+                        }
+                        let Some(paths) = &config_paths else { continue };
+                        match paths.list_tours().get(n - 1) {
+                            Some(name) => play_tour(&mut s, &mut view, Some(paths), name),
+                            None => s.status = format!("No tour {} in {}", n, paths.tours_dir().display()),
+                        }
+                    }
+                    // name a timeline marker at the epoch on screen
+                    KeyCode::Char('M') => {
+                        view.marker = Some(String::new());
+                    }
+                    // time-lapse: start from the epoch on screen, then pause and resume
+                    KeyCode::Char(' ') => {
+                        let mut s = state.lock().unwrap();
+                        match view.playback.as_mut() {
+                            Some(p) => {
+                                p.paused = !p.paused;
+                                s.status = if p.paused { "Paused".into() } else { format!("Playing at {}", p.speed_label()) };
+                            }
+                            None => {
+                                let utc = view.goto.as_ref().map(|g| g.utc).or_else(|| events::epoch(&s)).unwrap_or_else(chrono::Utc::now);
+                                let p = events::Playback::new(utc);
+                                s.status = format!("Playing at {} (Space pause, < > speed, Esc stop)", p.speed_label());
+                                view.playback = Some(p);
+                            }
+                        }
+                    }
+                    KeyCode::Char('>') | KeyCode::Char('<') if view.playback.is_some() => {
+                        let Some(p) = view.playback.as_mut() else { continue };
+                        for _ in 0..n {
+                            if code == KeyCode::Char('>') { p.faster() } else { p.slower() }
+                        }
+                        state.lock().unwrap().status = format!("Playback at {}", p.speed_label());
+                    }
+                    // scrub the playback clock, by a 200th of a fetched range or else a day
+                    KeyCode::Left | KeyCode::Right if view.playback.is_some() && !shift => {
+                        let Some(p) = view.playback.as_mut() else { continue };
+                        let step = p.bounds.map_or(chrono::Duration::days(1), |(from, to)| (to - from) / 200);
+                        let step = step * n as i32;
+                        p.paused = true;
+                        p.step(if code == KeyCode::Left { -step } else { step });
+                    }
+                    // fetch vector tables over a date range to play through
+                    KeyCode::Char('F') => {
+                        let at = view.goto.as_ref().map(|g| g.utc).or_else(|| events::epoch(&state.lock().unwrap())).unwrap_or_else(chrono::Utc::now);
+                        view.picker = Some(DatePicker::new(at, Purpose::RangeStart));
+                    }
+                    // travel to another epoch, starting from the one shown
+                    KeyCode::Char('T') => {
+                        let at = view.goto.as_ref().map(|g| g.utc).or_else(|| events::epoch(&state.lock().unwrap())).unwrap_or_else(chrono::Utc::now);
+                        view.picker = Some(DatePicker::new(at, Purpose::TimeTravel));
+                    }
+
                     // zoom in
                     KeyCode::Char('+') | KeyCode::Char('=') => {
-                        let mut s = state.lock().unwrap();
-                        s.zoom = clamp_zoom(s.zoom * 1.25);
+                        view.zoom = clamp_zoom(view.zoom * 1.25f64.powi(n as i32));
                     }
                     // zoom out
                     KeyCode::Char('-') => {
-                        let mut s = state.lock().unwrap();
-                        s.zoom = clamp_zoom(s.zoom / 1.25);
+                        view.zoom = clamp_zoom(view.zoom / 1.25f64.powi(n as i32));
                     }
                     // reset zoom
                     KeyCode::Char('0') => {
-                        let mut s = state.lock().unwrap();
-                        s.zoom = 1.0;
-                        s.focus_index = FOCUS_LEVELS.len() - 1;
+                        view.zoom = 1.0;
+                        view.focus_index = DEFAULT_FOCUS;
+                        view.pan = Vec3::ZERO;
+                    }
+                    // pan the map a fifth of its half-width per step, and back
+                    KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down if shift => {
+                        let (_, focus_au) = FOCUS_LEVELS[view.focus_index];
+                        let step = focus_au.max(0.1) / view.zoom * 0.2 * n as f64;
+                        view.pan = view.pan + match code {
+                            KeyCode::Left => Vec3 { x: -step, y: 0.0, z: 0.0 },
+                            KeyCode::Right => Vec3 { x: step, y: 0.0, z: 0.0 },
+                            KeyCode::Up => Vec3 { x: 0.0, y: step, z: 0.0 },
+                            _ => Vec3 { x: 0.0, y: -step, z: 0.0 },
+                        };
+                    }
+                    KeyCode::Home => {
+                        view.pan = Vec3::ZERO;
                     }
                     // focus in reminder: smaller max orbit
                     KeyCode::Char('[') => {
-                        let mut s = state.lock().unwrap();
-                        if s.focus_index > 0 {
-                            s.focus_index -= 1;
-                        }
+                        view.focus_index = view.focus_index.saturating_sub(n);
                     }
                     // focus out: larger max orbit
                     KeyCode::Char(']') => {
+                        view.focus_index = (view.focus_index + n).min(FOCUS_LEVELS.len() - 1);
+                    }
+
+                    // event selection while the events panel is open, else table selection
+                    KeyCode::Up if view.show_events => {
+                        view.event_selected = view.event_selected.saturating_sub(n);
+                    }
+                    KeyCode::Down if view.show_events => {
+                        let entries = events::timeline(&state.lock().unwrap()).len();
+                        view.event_selected = (view.event_selected + n).min(entries.saturating_sub(1));
+                    }
+                    KeyCode::Up => {
+                        view.selected = view.selected.saturating_sub(n);
+                        sync_stellarium(&state, &state.lock().unwrap(), &view);
+                    }
+                    KeyCode::Down => {
+                        let s = state.lock().unwrap();
+                        view.selected = (view.selected + n).min(s.bodies.len().saturating_sub(1));
+                        sync_stellarium(&state, &s, &view);
+                    }
+                    // toggle table and map relative to the selected body
+                    KeyCode::Char('r') => {
+                        let s = state.lock().unwrap();
+                        view.relative_to = match view.relative_to {
+                            Some(_) => None,
+                            None => s.bodies.get(view.selected).map(|b| b.id),
+                        };
+                        view.pan = Vec3::ZERO;
+                    }
+                    // keep the map centred on the selected body as it moves, or stop
+                    KeyCode::Char('f') => {
+                        let s = state.lock().unwrap();
+                        view.follow = match view.follow {
+                            Some(_) => None,
+                            None => s.bodies.get(view.selected).map(|b| b.id).filter(|&id| id != BodyId::SUN),
+                        };
+                        view.pan = Vec3::ZERO;
+                    }
+                    // cycle the reference body: the Sun, then every other body in table order
+                    KeyCode::Char('C') => {
+                        let s = state.lock().unwrap();
+                        for _ in 0..n {
+                            let from = view.relative_to.and_then(|id| s.bodies.iter().position(|b| b.id == id)).map_or(0, |i| i + 1);
+                            view.relative_to = s.bodies[from..].iter().map(|b| b.id).find(|&id| id != BodyId::SUN);
+                        }
+                        view.pan = Vec3::ZERO;
+                    }
+                    // cycle named profiles (none → first → … → last → none)
+                    KeyCode::Char('p') => {
+                        let Some(paths) = &config_paths else { continue };
+                        let names = paths.list_profiles();
                         let mut s = state.lock().unwrap();
-                        if s.focus_index + 1 < FOCUS_LEVELS.len() {
-                            s.focus_index += 1;
+                        let next = match s.profile.as_ref().and_then(|p| names.iter().position(|n| n == p)) {
+                            Some(i) => names.get(i + 1).cloned(),
+                            None => names.first().cloned(),
+                        };
+                        match load_config(Some(paths), next.as_deref()) {
+                            Ok(config) => {
+                                apply_config(&mut s, config);
+                                s.status = format!("Profile: {}", next.as_deref().unwrap_or("default"));
+                                s.profile = next;
+                            }
+                            Err(e) => {
+                                s.alert(AlertLevel::Error, format!("Profile {}: {:#}", next.as_deref().unwrap_or("default"), e));
+                                s.popup = Some(format!("{:#}", e));
+                            }
                         }
                     }
+                    // cycle map panel views
+                    KeyCode::Char('v') => {
+                        for _ in 0..n {
+                            view.map_view = view.map_view.next();
+                        }
+                    }
+                    // find a body by name, in any language
+                    KeyCode::Char('/') => {
+                        view.find = Some(String::new());
+                    }
+                    // cycle body coloring
+                    KeyCode::Char('c') => {
+                        for _ in 0..n {
+                            view.color_by = view.color_by.next();
+                        }
+                    }
+                    // long-range occultation / appulse search, in the background
+                    KeyCode::Char('S') => {
+                        let mut s = state.lock().unwrap();
+                        let Some(epoch) = events::epoch(&s) else { continue };
+                        if s.search_progress.is_some() {
+                            continue;
+                        }
+                        let bodies = events::fetched_states(&s);
+                        s.search_progress = Some(0.0);
+                        view.show_events = true;
+                        view.show_diagnostics = false;
+                        view.show_history = false;
+                        view.show_alerts = false;
+                        view.show_sky = false;
+                        view.show_neo = false;
+                        view.show_divergence = false;
+                        view.show_elements = false;
+                        let state = state.clone();
+                        tokio::task::spawn_blocking(move || {
+                            let found = events::search_conjunctions(&bodies, epoch, events::SEARCH_YEARS, |f| {
+                                state.lock().unwrap().search_progress = Some(f);
+                            });
+                            let mut s = state.lock().unwrap();
+                            s.notify(AlertLevel::Info, format!("Search: {} occultations/appulses in {} years", found.len(), events::SEARCH_YEARS));
+                            s.events.retain(|e| !e.kind.is_search_result());
+                            s.events.extend(found);
+                            s.events.sort_by_key(|e| e.utc);
+                            s.search_progress = None;
+                        });
+                    }
+                    // export the events list
+                    KeyCode::Char('x') => {
+                        let mut s = state.lock().unwrap();
+                        let path = events::export_path();
+                        match events::export_csv(&s.events, &path) {
+                            Ok(()) => s.status = format!("Exported {} events to {}", s.events.len(), path.display()),
+                            Err(e) => s.notify(AlertLevel::Error, format!("Export failed: {}", e)),
+                        }
+                    }
+                    // observation note for the selected body
+                    KeyCode::Char('o') => {
+                        if let Some(name) = state.lock().unwrap().bodies.get(view.selected).map(|b| b.name) {
+                            view.note = Some(NoteDraft::new(name));
+                        }
+                    }
+                    // point the configured telescope at the selected body
+                    #[cfg(feature = "net")]
+                    KeyCode::Char('g') => {
+                        let mut s = state.lock().unwrap();
+                        let Some(scope) = s.config.telescope.clone() else {
+                            s.status = "No [telescope] configured".into();
+                            continue;
+                        };
+                        let Some(body) = s.bodies.get(view.selected).filter(|b| b.id != BodyId::EARTH) else { continue };
+                        let now = chrono::Utc::now();
+                        let Some(d) = observer::line_of_sight(&s, &view, body, now) else { continue };
+                        let b = body.name;
+                        let of_date = true_of_date(ecliptic_to_equatorial(d), days_since_j2000(now));
+                        let (ra, dec) = ra_dec_deg(of_date);
+                        s.status = format!("Slewing to {}…", b);
+                        let state = state.clone();
+                        tokio::spawn(async move {
+                            let result = telescope::goto(&scope, ra, dec).await;
+                            let mut s = state.lock().unwrap();
+                            match result {
+                                Ok(()) => s.notify(AlertLevel::Info, format!("Slewing to {} (RA {:.3}h Dec {:+.2}°)", b, ra / 15.0, dec)),
+                                Err(e) => {
+                                    s.alert(AlertLevel::Error, format!("GoTo {} failed: {:#}", b, e));
+                                    s.popup = Some(format!("GoTo failed: {:#}", e));
+                                }
+                            }
+                        });
+                    }
+                    #[cfg(not(feature = "net"))]
+                    KeyCode::Char('g') => {
+                        state.lock().unwrap().status = "GoTo needs a build with the net feature".into();
+                    }
+                    // Milankovitch screen, computed on the blocking pool the first time
+                    KeyCode::Char('m') => {
+                        view.show_milankovitch = !view.show_milankovitch;
+                        if view.show_milankovitch && state.lock().unwrap().milankovitch.is_none() {
+                            let state = state.clone();
+                            tokio::task::spawn_blocking(move || {
+                                let samples = milankovitch::series(-100.0, 100.0, 0.1);
+                                state.lock().unwrap().milankovitch = Some(Arc::new(samples));
+                            });
+                        }
+                    }
+                    // direction-of-motion arrows on the orbit map
+                    KeyCode::Char('V') => {
+                        view.velocity_arrows = !view.velocity_arrows;
+                    }
+                    // where the selected body is heading
+                    KeyCode::Char('w') => {
+                        view.predicted_path = !view.predicted_path;
+                    }
+                    // Braille sub-cell rings and circles on the orbit map
+                    KeyCode::Char('B') => {
+                        view.braille = !view.braille;
+                    }
+                    // a measure point at the selected body, for measuring without the mouse
+                    KeyCode::Char('X') => {
+                        let mut s = state.lock().unwrap();
+                        let Some(b) = s.bodies.get(view.selected) else { continue };
+                        s.status = match b.pos_au {
+                            Some(p) => {
+                                add_measure_point(&mut view, p);
+                                format!("Measure point at {}", b.name)
+                            }
+                            None => format!("No position for {} yet", b.name),
+                        };
+                    }
+                    // name a pin at the mouse pointer on the map, else at the selected body
+                    KeyCode::Char('i') => {
+                        let at_pointer = view.pointer.filter(|_| view.mouse).zip(map).and_then(|((column, row), map)| map.point_at(column, row));
+                        let at_body = state.lock().unwrap().bodies.get(view.selected).and_then(|b| b.pos_au);
+                        if let Some(p) = at_pointer.or(at_body) {
+                            view.pin = Some((p, String::new()));
+                        }
+                    }
+                    // zoom to the box between the two measure points
+                    KeyCode::Char('Z') => match (view.measure.as_slice(), map) {
+                        (&[a, b], Some(map)) => zoom_to_box(&mut view, &map, a, b),
+                        (_, None) => state.lock().unwrap().status = "Zoom to box needs the orbit map on screen".into(),
+                        _ => state.lock().unwrap().status = "Place two measure points first (X, or click with P on)".into(),
+                    },
+                    // capture the mouse for the map's coordinate readout, or give it back
+                    // to the terminal for selecting text
+                    KeyCode::Char('P') => {
+                        view.mouse = !view.mouse;
+                        view.pointer = None;
+                        view.drag_from = None;
+                        if view.mouse {
+                            execute!(io::stdout(), EnableMouseCapture)?;
+                        } else {
+                            execute!(io::stdout(), DisableMouseCapture)?;
+                        }
+                    }
+                    // net gravity field glyphs on the orbit map, sampled in the background
+                    KeyCode::Char('G') => {
+                        view.gravity_field = !view.gravity_field;
+                    }
+                    // sphere of influence and Roche limit around the selected planet
+                    KeyCode::Char('R') => {
+                        view.show_limits = !view.show_limits;
+                    }
+                    // Pluto, Ceres and the other dwarf planets
+                    KeyCode::Char('D') => {
+                        let mut s = state.lock().unwrap();
+                        let mut c = (*s.config).clone();
+                        c.dwarf_planets = !c.dwarf_planets;
+                        s.status = format!("Dwarf planets {}", if c.dwarf_planets { "shown" } else { "hidden" });
+                        apply_config(&mut s, c);
+                    }
+                    // time scale of Horizons queries and the header's Julian Date
+                    KeyCode::Char('U') => {
+                        let mut s = state.lock().unwrap();
+                        let mut c = (*s.config).clone();
+                        c.time_scale = c.time_scale.toggle();
+                        s.status = format!("Horizons queries and JD in {}", c.time_scale.label());
+                        apply_config(&mut s, c);
+                    }
+                    KeyCode::Char('s') => {
+                        let mut s = state.lock().unwrap();
+                        let mut c = (*s.config).clone();
+                        c.spacecraft = !c.spacecraft;
+                        s.status = format!("Spacecraft {}", if c.spacecraft { "shown" } else { "hidden" });
+                        apply_config(&mut s, c);
+                    }
+                    KeyCode::Char('b') => {
+                        let mut s = state.lock().unwrap();
+                        let mut c = (*s.config).clone();
+                        c.origin = match c.origin {
+                            Origin::Heliocentric => Origin::Barycentric,
+                            Origin::Barycentric => Origin::Heliocentric,
+                        };
+                        s.status = format!("Origin: {}", if c.origin == Origin::Barycentric { "barycentre (from the next refresh)" } else { "Sun" });
+                        apply_config(&mut s, c);
+                    }
+                    // apparent (light-time + aberration) vs geometric positions
+                    KeyCode::Char('a') => {
+                        view.apparent = !view.apparent;
+                    }
+                    // RA/Dec in the detail pane: J2000 or true equator and equinox of date
+                    KeyCode::Char('j') => {
+                        view.of_date = !view.of_date;
+                    }
+                    // topocentric correction (needs an [observer] location)
+                    KeyCode::Char('t') => {
+                        let mut s = state.lock().unwrap();
+                        if s.config.observer.is_none() {
+                            s.status = "No [observer] configured".into();
+                            continue;
+                        }
+                        view.topocentric = !view.topocentric;
+                        s.status = format!("Positions {}", if view.topocentric { "topocentric" } else { "geocentric" });
+                    }
+                    // orbit diagnostics panel
+                    KeyCode::Char('d') => {
+                        view.show_diagnostics = !view.show_diagnostics;
+                        view.show_events = false;
+                        view.show_history = false;
+                        view.show_alerts = false;
+                        view.show_sky = false;
+                        view.show_neo = false;
+                        view.show_divergence = false;
+                        view.show_elements = false;
+                    }
+                    // osculating-element history of the selected body
+                    #[cfg(feature = "net")]
+                    KeyCode::Char('h') => {
+                        view.show_history = !view.show_history;
+                        view.show_diagnostics = false;
+                        view.show_events = false;
+                        view.show_alerts = false;
+                        view.show_sky = false;
+                        view.show_neo = false;
+                        view.show_divergence = false;
+                        view.show_elements = false;
+                        let mut s = state.lock().unwrap();
+                        let Some(b) = s.bodies.get(view.selected).filter(|b| b.id != BodyId::SUN) else { continue };
+                        let (name, id) = (b.name, b.id);
+                        if view.show_history && s.history.as_ref().is_none_or(|h| h.body != name) {
+                            s.history = None;
+                            s.status = format!("Fetching elements for {}…", name);
+                            tokio::spawn(load_element_history(state.clone(), name, id));
+                        }
+                    }
+                    #[cfg(not(feature = "net"))]
+                    KeyCode::Char('h') => {
+                        state.lock().unwrap().status = "Element history needs a build with the net feature".into();
+                    }
+                    // upcoming events panel
+                    KeyCode::Char('e') => {
+                        view.show_events = !view.show_events;
+                        view.show_diagnostics = false;
+                        view.show_history = false;
+                        view.show_alerts = false;
+                        view.show_sky = false;
+                        view.show_neo = false;
+                        view.show_divergence = false;
+                        view.show_elements = false;
+                    }
+                    // alert history panel, newest first
+                    KeyCode::Char('l') => {
+                        view.show_alerts = !view.show_alerts;
+                        view.show_diagnostics = false;
+                        view.show_events = false;
+                        view.show_history = false;
+                        view.show_sky = false;
+                        view.show_neo = false;
+                        view.show_divergence = false;
+                        view.show_elements = false;
+                        view.alert_scroll = 0;
+                    }
+                    // where each body stands in the observer's sky
+                    KeyCode::Char('A') => {
+                        view.show_sky = !view.show_sky;
+                        view.show_diagnostics = false;
+                        view.show_events = false;
+                        view.show_history = false;
+                        view.show_alerts = false;
+                        view.show_neo = false;
+                        view.show_divergence = false;
+                        view.show_elements = false;
+                        let mut s = state.lock().unwrap();
+                        if view.show_sky && s.config.observer.is_none() {
+                            s.status = "Set [observer] lat_deg and lon_deg in the config for the sky panel".into();
+                        }
+                        #[cfg(feature = "net")]
+                        if view.show_sky && s.config.observer.is_some() && !sky_tables_current(&s) {
+                            s.status = "Fetching observer tables…".into();
+                            tokio::spawn(load_sky(state.clone()));
+                        }
+                    }
+                    // upcoming near-Earth object close approaches, kept current once opened
+                    KeyCode::Char('N') => {
+                        view.show_neo = !view.show_neo;
+                        view.show_diagnostics = false;
+                        view.show_events = false;
+                        view.show_history = false;
+                        view.show_alerts = false;
+                        view.show_sky = false;
+                        view.show_divergence = false;
+                        view.show_elements = false;
+                        #[cfg(feature = "net")]
+                        if view.show_neo && neo_task.is_none() {
+                            state.lock().unwrap().status = "Fetching close approaches from CNEOS…".into();
+                            neo_task = Some(tokio::spawn(cneos::refresh_daily(state.clone())));
+                        }
+                        #[cfg(not(feature = "net"))]
+                        if view.show_neo {
+                            state.lock().unwrap().status = "Close approaches need a build with the net feature".into();
+                        }
+                    }
+                    // osculating elements of every body, from Horizons where fetched
+                    KeyCode::Char('E') => {
+                        view.show_elements = !view.show_elements;
+                        view.show_diagnostics = false;
+                        view.show_events = false;
+                        view.show_history = false;
+                        view.show_alerts = false;
+                        view.show_sky = false;
+                        view.show_neo = false;
+                        view.show_divergence = false;
+                        #[cfg(feature = "net")]
+                        {
+                            let mut s = state.lock().unwrap();
+                            if view.show_elements && !elements_current(&s) {
+                                s.status = "Fetching osculating elements…".into();
+                                tokio::spawn(load_elements(state.clone()));
+                            }
+                        }
+                    }
+                    // divergence of the shown positions from the reference source
+                    KeyCode::Char('K') => {
+                        view.show_divergence = !view.show_divergence;
+                        view.show_elements = false;
+                        view.show_diagnostics = false;
+                        view.show_events = false;
+                        view.show_history = false;
+                        view.show_alerts = false;
+                        view.show_sky = false;
+                        view.show_neo = false;
+                    }
+                    KeyCode::PageUp => {
+                        let alerts = state.lock().unwrap().alerts.len();
+                        view.alert_scroll = (view.alert_scroll + 10 * n).min(alerts.saturating_sub(1));
+                    }
+                    KeyCode::PageDown => {
+                        view.alert_scroll = view.alert_scroll.saturating_sub(10 * n);
+                    }
 
                     _ => {}
                 }
+                undo.record(before, ViewSnapshot::of(&view, &state.lock().unwrap()));
             }
         }
     }
@@ -0,0 +1,58 @@
+//! Named markers on the timeline ("launch", "flyby"), dropped with `M` at the epoch on screen
+//! and listed with the events, for storyboarding a mission. Kept in a local JSON file so they
+//! outlast the session.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Marker {
+    pub name: String,
+    pub utc: DateTime<Utc>,
+}
+
+pub fn markers_path() -> PathBuf {
+    dirs::data_dir().unwrap_or_else(std::env::temp_dir).join("solar-rs").join("markers.json")
+}
+
+/// All markers, earliest first; none if the file is missing or unreadable.
+pub fn load() -> Vec<Marker> {
+    let Ok(text) = std::fs::read_to_string(markers_path()) else { return Vec::new() };
+    let mut markers: Vec<Marker> = serde_json::from_str(&text).unwrap_or_default();
+    markers.sort_by_key(|m| m.utc);
+    markers
+}
+
+/// Rewrites the file with `markers`, which are few enough to keep whole.
+pub fn save(markers: &[Marker]) -> Result<()> {
+    let path = markers_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(markers)?)?;
+    Ok(())
+}
+
+/// Adds a marker in time order, after any others at the same epoch.
+pub fn insert(markers: &mut Vec<Marker>, marker: Marker) {
+    let at = markers.partition_point(|m| m.utc <= marker.utc);
+    markers.insert(at, marker);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn insert_keeps_time_order() {
+        let at = |d| Utc.with_ymd_and_hms(2030, 1, d, 0, 0, 0).unwrap();
+        let mut markers = vec![Marker { name: "launch".into(), utc: at(1) }, Marker { name: "flyby".into(), utc: at(9) }];
+        insert(&mut markers, Marker { name: "burn".into(), utc: at(5) });
+        insert(&mut markers, Marker { name: "burn 2".into(), utc: at(5) });
+        let names: Vec<&str> = markers.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["launch", "burn", "burn 2", "flyby"]);
+    }
+}
@@ -0,0 +1,236 @@
+//! Body names beyond the IAU English ones: the common name in a handful of languages (with a
+//! romanization for non-Latin scripts) and alternative names, so the table reads in the
+//! user's language and find-by-name accepts any of them.
+
+use crate::types::{BodyId, BodyState};
+
+/// A body's name in one language, keyed by ISO 639-1 code.
+pub struct Local {
+    pub lang: &'static str,
+    pub native: &'static str,
+    /// Latin-script form for non-Latin scripts (Hepburn, Hanyu Pinyin, ISO 9-style).
+    pub romanized: Option<&'static str>,
+}
+
+pub struct BodyNames {
+    /// IAU name, as in [`crate::types::BODIES`].
+    pub body: &'static str,
+    /// Other names in use (Latin, poetic), matched by find but never displayed.
+    pub alt: &'static [&'static str],
+    pub local: &'static [Local],
+}
+
+const fn l(lang: &'static str, native: &'static str) -> Local {
+    Local { lang, native, romanized: None }
+}
+
+const fn r(lang: &'static str, native: &'static str, romanized: &'static str) -> Local {
+    Local { lang, native, romanized: Some(romanized) }
+}
+
+#[rustfmt::skip]
+pub const NAMES: &[BodyNames] = &[
+    BodyNames { body: "Sun", alt: &["Sol", "Helios"], local: &[
+        l("de", "Sonne"), l("es", "Sol"), l("fr", "Soleil"), l("it", "Sole"), l("pt", "Sol"),
+        r("ru", "Солнце", "Solntse"), r("ja", "太陽", "Taiyō"), r("zh", "太阳", "Tàiyáng"),
+    ] },
+    BodyNames { body: "Mercury", alt: &["Mercurius"], local: &[
+        l("de", "Merkur"), l("es", "Mercurio"), l("fr", "Mercure"), l("it", "Mercurio"), l("pt", "Mercúrio"),
+        r("ru", "Меркурий", "Merkuriy"), r("ja", "水星", "Suisei"), r("zh", "水星", "Shuǐxīng"),
+    ] },
+    BodyNames { body: "Venus", alt: &[], local: &[
+        l("de", "Venus"), l("es", "Venus"), l("fr", "Vénus"), l("it", "Venere"), l("pt", "Vênus"),
+        r("ru", "Венера", "Venera"), r("ja", "金星", "Kinsei"), r("zh", "金星", "Jīnxīng"),
+    ] },
+    BodyNames { body: "Earth", alt: &["Terra", "Tellus"], local: &[
+        l("de", "Erde"), l("es", "Tierra"), l("fr", "Terre"), l("it", "Terra"), l("pt", "Terra"),
+        r("ru", "Земля", "Zemlya"), r("ja", "地球", "Chikyū"), r("zh", "地球", "Dìqiú"),
+    ] },
+    BodyNames { body: "Mars", alt: &[], local: &[
+        l("de", "Mars"), l("es", "Marte"), l("fr", "Mars"), l("it", "Marte"), l("pt", "Marte"),
+        r("ru", "Марс", "Mars"), r("ja", "火星", "Kasei"), r("zh", "火星", "Huǒxīng"),
+    ] },
+    BodyNames { body: "Jupiter", alt: &["Iuppiter", "Jove"], local: &[
+        l("de", "Jupiter"), l("es", "Júpiter"), l("fr", "Jupiter"), l("it", "Giove"), l("pt", "Júpiter"),
+        r("ru", "Юпитер", "Yupiter"), r("ja", "木星", "Mokusei"), r("zh", "木星", "Mùxīng"),
+    ] },
+    BodyNames { body: "Saturn", alt: &["Saturnus"], local: &[
+        l("de", "Saturn"), l("es", "Saturno"), l("fr", "Saturne"), l("it", "Saturno"), l("pt", "Saturno"),
+        r("ru", "Сатурн", "Saturn"), r("ja", "土星", "Dosei"), r("zh", "土星", "Tǔxīng"),
+    ] },
+    BodyNames { body: "Uranus", alt: &[], local: &[
+        l("de", "Uranus"), l("es", "Urano"), l("fr", "Uranus"), l("it", "Urano"), l("pt", "Urano"),
+        r("ru", "Уран", "Uran"), r("ja", "天王星", "Tennōsei"), r("zh", "天王星", "Tiānwángxīng"),
+    ] },
+    BodyNames { body: "Neptune", alt: &["Neptunus"], local: &[
+        l("de", "Neptun"), l("es", "Neptuno"), l("fr", "Neptune"), l("it", "Nettuno"), l("pt", "Netuno"),
+        r("ru", "Нептун", "Neptun"), r("ja", "海王星", "Kaiōsei"), r("zh", "海王星", "Hǎiwángxīng"),
+    ] },
+    BodyNames { body: "Ceres", alt: &["1 Ceres"], local: &[
+        l("de", "Ceres"), l("es", "Ceres"), l("fr", "Cérès"), l("it", "Cerere"), l("pt", "Ceres"),
+        r("ru", "Церера", "Tserera"), r("ja", "ケレス", "Keresu"), r("zh", "谷神星", "Gǔshénxīng"),
+    ] },
+    BodyNames { body: "Pluto", alt: &["134340 Pluto"], local: &[
+        l("de", "Pluto"), l("es", "Plutón"), l("fr", "Pluton"), l("it", "Plutone"), l("pt", "Plutão"),
+        r("ru", "Плутон", "Pluton"), r("ja", "冥王星", "Meiōsei"), r("zh", "冥王星", "Míngwángxīng"),
+    ] },
+    BodyNames { body: "Haumea", alt: &["136108 Haumea"], local: &[
+        l("de", "Haumea"), l("es", "Haumea"), l("fr", "Hauméa"), l("it", "Haumea"), l("pt", "Haumea"),
+        r("ru", "Хаумеа", "Khaumea"), r("ja", "ハウメア", "Haumea"), r("zh", "妊神星", "Rènshénxīng"),
+    ] },
+    BodyNames { body: "Makemake", alt: &["136472 Makemake"], local: &[
+        l("de", "Makemake"), l("es", "Makemake"), l("fr", "Makémaké"), l("it", "Makemake"), l("pt", "Makemake"),
+        r("ru", "Макемаке", "Makemake"), r("ja", "マケマケ", "Makemake"), r("zh", "鸟神星", "Niǎoshénxīng"),
+    ] },
+    BodyNames { body: "Eris", alt: &["136199 Eris"], local: &[
+        l("de", "Eris"), l("es", "Eris"), l("fr", "Éris"), l("it", "Eris"), l("pt", "Éris"),
+        r("ru", "Эрида", "Erida"), r("ja", "エリス", "Erisu"), r("zh", "阋神星", "Xìshénxīng"),
+    ] },
+    BodyNames { body: "Moon", alt: &["Luna", "Selene"], local: &[
+        l("de", "Mond"), l("es", "Luna"), l("fr", "Lune"), l("it", "Luna"), l("pt", "Lua"),
+        r("ru", "Луна", "Luna"), r("ja", "月", "Tsuki"), r("zh", "月球", "Yuèqiú"),
+    ] },
+    BodyNames { body: "Io", alt: &["Jupiter I"], local: &[
+        l("de", "Io"), l("es", "Ío"), l("fr", "Io"), l("it", "Io"), l("pt", "Io"),
+        r("ru", "Ио", "Io"), r("ja", "イオ", "Io"), r("zh", "木卫一", "Mùwèiyī"),
+    ] },
+    BodyNames { body: "Europa", alt: &["Jupiter II"], local: &[
+        l("de", "Europa"), l("es", "Europa"), l("fr", "Europe"), l("it", "Europa"), l("pt", "Europa"),
+        r("ru", "Европа", "Yevropa"), r("ja", "エウロパ", "Europa"), r("zh", "木卫二", "Mùwèi'èr"),
+    ] },
+    BodyNames { body: "Ganymede", alt: &["Jupiter III"], local: &[
+        l("de", "Ganymed"), l("es", "Ganímedes"), l("fr", "Ganymède"), l("it", "Ganimede"), l("pt", "Ganimedes"),
+        r("ru", "Ганимед", "Ganimed"), r("ja", "ガニメデ", "Ganimede"), r("zh", "木卫三", "Mùwèisān"),
+    ] },
+    BodyNames { body: "Callisto", alt: &["Jupiter IV"], local: &[
+        l("de", "Kallisto"), l("es", "Calisto"), l("fr", "Callisto"), l("it", "Callisto"), l("pt", "Calisto"),
+        r("ru", "Каллисто", "Kallisto"), r("ja", "カリスト", "Karisuto"), r("zh", "木卫四", "Mùwèisì"),
+    ] },
+    BodyNames { body: "Titan", alt: &["Saturn VI"], local: &[
+        l("de", "Titan"), l("es", "Titán"), l("fr", "Titan"), l("it", "Titano"), l("pt", "Titã"),
+        r("ru", "Титан", "Titan"), r("ja", "タイタン", "Taitan"), r("zh", "土卫六", "Tǔwèiliù"),
+    ] },
+    BodyNames { body: "Triton", alt: &["Neptune I"], local: &[
+        l("de", "Triton"), l("es", "Tritón"), l("fr", "Triton"), l("it", "Tritone"), l("pt", "Tritão"),
+        r("ru", "Тритон", "Triton"), r("ja", "トリトン", "Toriton"), r("zh", "海卫一", "Hǎiwèiyī"),
+    ] },
+    BodyNames { body: "Voyager 1", alt: &[], local: &[
+        l("de", "Voyager 1"), l("es", "Voyager 1"), l("fr", "Voyager 1"), l("it", "Voyager 1"), l("pt", "Voyager 1"),
+        r("ru", "Вояджер-1", "Voyadzher-1"), r("ja", "ボイジャー1号", "Boijā 1-gō"), r("zh", "旅行者1号", "Lǚxíngzhě 1 hào"),
+    ] },
+    BodyNames { body: "Voyager 2", alt: &[], local: &[
+        l("de", "Voyager 2"), l("es", "Voyager 2"), l("fr", "Voyager 2"), l("it", "Voyager 2"), l("pt", "Voyager 2"),
+        r("ru", "Вояджер-2", "Voyadzher-2"), r("ja", "ボイジャー2号", "Boijā 2-gō"), r("zh", "旅行者2号", "Lǚxíngzhě 2 hào"),
+    ] },
+    BodyNames { body: "New Horizons", alt: &[], local: &[
+        l("de", "New Horizons"), l("es", "New Horizons"), l("fr", "New Horizons"), l("it", "New Horizons"), l("pt", "New Horizons"),
+        r("ru", "Новые горизонты", "Novye gorizonty"), r("ja", "ニュー・ホライズンズ", "Nyū Horaizunzu"), r("zh", "新视野号", "Xīnshìyě hào"),
+    ] },
+    BodyNames { body: "JWST", alt: &["James Webb Space Telescope", "Webb"], local: &[
+        l("de", "JWST"), l("es", "JWST"), l("fr", "JWST"), l("it", "JWST"), l("pt", "JWST"),
+        r("ru", "Уэбб", "Uebb"), r("ja", "ウェッブ", "Webbu"), r("zh", "韦布", "Wéibù"),
+    ] },
+    BodyNames { body: "Parker", alt: &["Parker Solar Probe", "PSP", "Solar Probe Plus"], local: &[
+        l("de", "Parker"), l("es", "Parker"), l("fr", "Parker"), l("it", "Parker"), l("pt", "Parker"),
+        r("ru", "Паркер", "Parker"), r("ja", "パーカー", "Pākā"), r("zh", "帕克", "Pàkè"),
+    ] },
+];
+
+pub fn names_of(body: &str) -> Option<&'static BodyNames> {
+    NAMES.iter().find(|n| n.body == body)
+}
+
+/// Language for body names: the configured one, else the locale from `LC_ALL`, `LC_MESSAGES`
+/// or `LANG` (`de_DE.UTF-8` → `de`), else English.
+pub fn resolve_language(configured: Option<&str>) -> String {
+    let from_env = || {
+        ["LC_ALL", "LC_MESSAGES", "LANG"].iter().find_map(|k| std::env::var(k).ok().filter(|v| !v.is_empty()))
+    };
+    let raw = configured.map(str::to_string).or_else(from_env).unwrap_or_default();
+    let code = raw.split(['_', '-', '.', '@']).next().unwrap_or("").to_lowercase();
+    match code.as_str() {
+        "" | "c" | "posix" => "en".to_string(),
+        _ => code,
+    }
+}
+
+/// Name of `body` in `lang`, falling back to the IAU name.
+pub fn display_name(body: &'static str, lang: &str) -> &'static str {
+    names_of(body)
+        .and_then(|n| n.local.iter().find(|l| l.lang == lang))
+        .map_or(body, |l| l.native)
+}
+
+/// Lowercase with diacritics stripped, so "kaiosei" finds "Kaiōsei" and "jupiter" "Júpiter".
+fn fold(s: &str) -> String {
+    s.chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            'á' | 'à' | 'â' | 'ä' | 'ā' | 'ǎ' => 'a',
+            'é' | 'è' | 'ê' | 'ë' | 'ē' | 'ě' => 'e',
+            'í' | 'ì' | 'î' | 'ï' | 'ī' | 'ǐ' => 'i',
+            'ó' | 'ò' | 'ô' | 'ö' | 'ō' | 'ǒ' => 'o',
+            'ú' | 'ù' | 'û' | 'ü' | 'ū' | 'ǔ' | 'ǖ' | 'ǘ' | 'ǚ' | 'ǜ' => 'u',
+            'ñ' => 'n',
+            'ç' => 'c',
+            'ё' => 'е',
+            c => c,
+        })
+        .collect()
+}
+
+/// Whether `query` is a prefix of any of `body`'s names in any language (including
+/// romanizations and alternatives), ignoring case and diacritics, or its Horizons id.
+pub fn matches(body: &str, id: BodyId, query: &str) -> bool {
+    let q = fold(query.trim());
+    if q.is_empty() {
+        return false;
+    }
+    if q == id.to_string() {
+        return true;
+    }
+    let hit = |name: &str| fold(name).starts_with(&q);
+    hit(body)
+        || names_of(body).is_some_and(|n| {
+            n.alt.iter().any(|a| hit(a))
+                || n.local.iter().any(|l| hit(l.native) || l.romanized.is_some_and(hit))
+        })
+}
+
+/// Index of the first body in table order that [`matches`] `query`.
+pub fn find_body(bodies: &[BodyState], query: &str) -> Option<usize> {
+    bodies.iter().position(|b| matches(b.name, b.id, query))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_body_has_names() {
+        for m in crate::types::BODIES {
+            assert!(names_of(m.name).is_some(), "{} has no names", m.name);
+        }
+    }
+
+    #[test]
+    fn matches_across_scripts_and_accents() {
+        assert!(matches("Neptune", BodyId(899), "kaiosei"));
+        assert!(matches("Neptune", BodyId(899), "海王"));
+        assert!(matches("Jupiter", BodyId(599), "JUPI"));
+        assert!(matches("Jupiter", BodyId(599), "giove"));
+        assert!(matches("Earth", BodyId::EARTH, "земля"));
+        assert!(matches("Mars", BodyId(499), "499"));
+        assert!(!matches("Mars", BodyId(499), "ma rte"));
+        assert!(!matches("Venus", BodyId(299), ""));
+    }
+
+    #[test]
+    fn language_from_locale() {
+        assert_eq!(resolve_language(Some("de_DE.UTF-8")), "de");
+        assert_eq!(resolve_language(Some("pt-BR")), "pt");
+        assert_eq!(resolve_language(Some("C")), "en");
+        assert_eq!(display_name("Mars", "ja"), "火星");
+        assert_eq!(display_name("Mars", "xx"), "Mars");
+    }
+}
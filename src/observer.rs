@@ -0,0 +1,113 @@
+//! The observer's place on the rotating Earth, for topocentric positions. Parallax is the
+//! observer's offset from Earth's centre seen from the target: under 9″ for the Sun and
+//! planets, but up to about a degree for the Moon.
+
+use chrono::{DateTime, Utc};
+
+use crate::config::Observer;
+use crate::frames::{
+    apparent_offset, ecliptic_to_equatorial, equatorial_to_ecliptic, mean_obliquity_deg, nutation_deg, ra_dec_deg,
+    true_of_date,
+};
+use crate::physics::{days_since_j2000, AU_KM};
+use crate::types::{BodyId, BodyState, DataState, Vec3, ViewState};
+
+/// WGS 84 equatorial radius and flattening.
+const EARTH_RADIUS_KM: f64 = 6_378.137;
+const EARTH_FLATTENING: f64 = 1.0 / 298.257_223_563;
+
+/// Greenwich mean sidereal time in degrees (IAU 1982, Meeus eq. 12.4), taking UT1 as UTC.
+pub fn gmst_deg(utc: DateTime<Utc>) -> f64 {
+    const J2000_UNIX_S: f64 = 946_728_000.0;
+    let d = (utc.timestamp_millis() as f64 / 1000.0 - J2000_UNIX_S) / 86_400.0;
+    let t = d / 36_525.0;
+    (280.460_618_37 + 360.985_647_366_29 * d + t * t * (0.000_387_933 - t / 38_710_000.0)).rem_euclid(360.0)
+}
+
+/// Observer position relative to Earth's centre, J2000 ecliptic AU. Built in the equator of
+/// date; precession since J2000 turns it by a fraction of a degree, which changes even the
+/// Moon's parallax by only a few tens of arcseconds, so it is ignored.
+pub fn geocentric_offset_au(o: &Observer, utc: DateTime<Utc>) -> Vec3 {
+    let lat = o.lat_deg.to_radians();
+    let (s, c) = lat.sin_cos();
+    let b = 1.0 - EARTH_FLATTENING;
+    let cc = 1.0 / (c * c + b * b * s * s).sqrt();
+    let h = o.height_m / 1000.0 / EARTH_RADIUS_KM;
+    let rho_cos = (cc + h) * c; // ρ cos φ′
+    let rho_sin = (b * b * cc + h) * s; // ρ sin φ′
+
+    let lst = (gmst_deg(utc) + o.lon_deg).to_radians();
+    let k = EARTH_RADIUS_KM / AU_KM;
+    equatorial_to_ecliptic(Vec3 { x: k * rho_cos * lst.cos(), y: k * rho_cos * lst.sin(), z: k * rho_sin })
+}
+
+/// Heliocentric position of the point the view is taken from: the observer when topocentric
+/// correction is on and a location is configured, otherwise Earth's centre.
+pub fn viewpoint(data: &DataState, view: &ViewState, earth: Vec3, utc: DateTime<Utc>) -> Vec3 {
+    match data.config.observer.filter(|_| view.topocentric) {
+        Some(o) => earth + geocentric_offset_au(&o, utc),
+        None => earth,
+    }
+}
+
+/// Vector from the viewpoint to `body`, geometric or apparent (light-time and aberration
+/// corrected) depending on the `apparent` toggle. Earth's rotation (diurnal aberration,
+/// ≤ 0.3″) is left out.
+pub fn line_of_sight(data: &DataState, view: &ViewState, body: &BodyState, utc: DateTime<Utc>) -> Option<Vec3> {
+    let earth = data.bodies.iter().find(|b| b.id == BodyId::EARTH)?;
+    let d = body.pos_au? - viewpoint(data, view, earth.pos_au?, utc);
+    if !view.apparent {
+        return Some(d);
+    }
+    Some(apparent_offset(d, body.vel_au_d?, earth.vel_au_d?))
+}
+
+/// Where a body stands in the observer's sky: apparent RA/Dec on the true equator of date,
+/// refracted altitude and azimuth from north through east, all in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SkyPosition {
+    pub ra_deg: f64,
+    pub dec_deg: f64,
+    pub alt_deg: f64,
+    pub az_deg: f64,
+}
+
+/// Greenwich apparent sidereal time in degrees: GMST plus the equation of the equinoxes.
+pub fn gast_deg(utc: DateTime<Utc>) -> f64 {
+    let days = days_since_j2000(utc);
+    let (dpsi, _) = nutation_deg(days);
+    (gmst_deg(utc) + dpsi * mean_obliquity_deg(days).to_radians().cos()).rem_euclid(360.0)
+}
+
+/// Geometric altitude and azimuth of a true-of-date RA/Dec for the observer.
+pub fn alt_az_deg(o: &Observer, ra_deg: f64, dec_deg: f64, utc: DateTime<Utc>) -> (f64, f64) {
+    let h = (gast_deg(utc) + o.lon_deg - ra_deg).to_radians();
+    let (lat, dec) = (o.lat_deg.to_radians(), dec_deg.to_radians());
+    let alt = (lat.sin() * dec.sin() + lat.cos() * dec.cos() * h.cos()).asin();
+    let az = (-h.sin() * dec.cos()).atan2(dec.sin() * lat.cos() - dec.cos() * lat.sin() * h.cos());
+    (alt.to_degrees(), az.to_degrees().rem_euclid(360.0))
+}
+
+/// Refraction to add to a geometric altitude under standard conditions (Sæmundsson 1986),
+/// about 0.57° at the horizon; none well below it.
+pub fn refraction_deg(alt_deg: f64) -> f64 {
+    if alt_deg < -1.0 {
+        return 0.0;
+    }
+    (1.02 / (alt_deg + 10.3 / (alt_deg + 5.11)).to_radians().tan() / 60.0).max(0.0)
+}
+
+/// Where `body` stands in the configured observer's sky at `utc`: always topocentric and
+/// apparent, whatever the view toggles, since this is where to point a telescope.
+pub fn sky_position(data: &DataState, body: &BodyState, utc: DateTime<Utc>) -> Option<SkyPosition> {
+    let o = data.config.observer?;
+    let earth = data.bodies.iter().find(|b| b.id == BodyId::EARTH)?;
+    if body.id == BodyId::EARTH {
+        return None;
+    }
+    let d = body.pos_au? - (earth.pos_au? + geocentric_offset_au(&o, utc));
+    let d = apparent_offset(d, body.vel_au_d?, earth.vel_au_d?);
+    let (ra_deg, dec_deg) = ra_dec_deg(true_of_date(ecliptic_to_equatorial(d), days_since_j2000(utc)));
+    let (alt, az_deg) = alt_az_deg(&o, ra_deg, dec_deg, utc);
+    Some(SkyPosition { ra_deg, dec_deg, alt_deg: alt + refraction_deg(alt), az_deg })
+}
@@ -0,0 +1,200 @@
+use std::f64::consts::TAU;
+
+use crate::types::Vec3;
+
+/// Below this, eccentricity / node-vector length are treated as zero (circular / equatorial).
+const EPS: f64 = 1e-11;
+
+/// Classical osculating elements. Angles in degrees, normalised to [0, 360).
+///
+/// For circular orbits ω is 0 and ν is the argument of latitude; for equatorial orbits Ω is 0
+/// and ω (or ν, if also circular) is measured from the reference x-axis.
+#[derive(Debug, Clone, Copy)]
+pub struct KeplerElements {
+    pub a_au: f64,
+    pub e: f64,
+    pub i_deg: f64,
+    pub raan_deg: f64,          // Ω, longitude of the ascending node
+    pub arg_periapsis_deg: f64, // ω
+    pub true_anomaly_deg: f64,  // ν
+}
+
+/// Signed angle from `from` to `to`, measured in the orbital plane with normal `h_hat`.
+fn plane_angle(from: Vec3, to: Vec3, h_hat: Vec3) -> f64 {
+    from.cross(to).dot(h_hat).atan2(from.dot(to)).rem_euclid(TAU)
+}
+
+/// Converts a state vector (AU, AU/day) to osculating elements about a body with GM `mu` (AU³/d²).
+pub fn state_to_elements(p: Vec3, v: Vec3, mu: f64) -> KeplerElements {
+    let r = p.norm();
+    let h = p.cross(v);
+    let h_norm = h.norm();
+    let h_hat = h * (1.0 / h_norm);
+    let node = Vec3 { x: -h.y, y: h.x, z: 0.0 }; // ẑ × h
+    let e_vec = v.cross(h) * (1.0 / mu) - p * (1.0 / r);
+    let e = e_vec.norm();
+    let energy = v.dot(v) / 2.0 - mu / r;
+
+    let inclined = node.norm() > EPS * h_norm;
+    let eccentric = e > EPS;
+    let x_axis = Vec3 { x: 1.0, y: 0.0, z: 0.0 };
+
+    let raan = if inclined { node.y.atan2(node.x).rem_euclid(TAU) } else { 0.0 };
+    let line_of_nodes = if inclined { node } else { x_axis };
+    let arg_periapsis = if eccentric { plane_angle(line_of_nodes, e_vec, h_hat) } else { 0.0 };
+    let true_anomaly = if eccentric {
+        plane_angle(e_vec, p, h_hat)
+    } else {
+        plane_angle(line_of_nodes, p, h_hat)
+    };
+
+    KeplerElements {
+        a_au: -mu / (2.0 * energy),
+        e,
+        i_deg: (h.z / h_norm).clamp(-1.0, 1.0).acos().to_degrees(),
+        raan_deg: raan.to_degrees(),
+        arg_periapsis_deg: arg_periapsis.to_degrees(),
+        true_anomaly_deg: true_anomaly.to_degrees(),
+    }
+}
+
+/// Inverse of [`state_to_elements`]: position (AU) and velocity (AU/day) in the reference frame.
+pub fn elements_to_state(el: &KeplerElements, mu: f64) -> (Vec3, Vec3) {
+    let (i, raan, w, nu) = (
+        el.i_deg.to_radians(),
+        el.raan_deg.to_radians(),
+        el.arg_periapsis_deg.to_radians(),
+        el.true_anomaly_deg.to_radians(),
+    );
+    let semi_latus = el.a_au * (1.0 - el.e * el.e);
+    let r = semi_latus / (1.0 + el.e * nu.cos());
+
+    // Perifocal frame: x toward periapsis, z along the angular momentum.
+    let pos_pf = (r * nu.cos(), r * nu.sin());
+    let k = (mu / semi_latus).sqrt();
+    let vel_pf = (-k * nu.sin(), k * (el.e + nu.cos()));
+
+    let (so, co) = raan.sin_cos();
+    let (sw, cw) = w.sin_cos();
+    let (si, ci) = i.sin_cos();
+    let rotate = |(px, py): (f64, f64)| Vec3 {
+        x: (co * cw - so * sw * ci) * px + (-co * sw - so * cw * ci) * py,
+        y: (so * cw + co * sw * ci) * px + (-so * sw + co * cw * ci) * py,
+        z: (sw * si) * px + (cw * si) * py,
+    };
+
+    (rotate(pos_pf), rotate(vel_pf))
+}
+
+/// Solves Kepler's equation M = E − e·sin E for the eccentric anomaly (radians).
+pub fn eccentric_anomaly(mean_anomaly: f64, e: f64) -> f64 {
+    let m = mean_anomaly.rem_euclid(TAU);
+    let mut ecc = if e < 0.8 { m } else { std::f64::consts::PI };
+    for _ in 0..50 {
+        let step = (ecc - e * ecc.sin() - m) / (1.0 - e * ecc.cos());
+        ecc -= step;
+        if step.abs() < 1e-14 {
+            break;
+        }
+    }
+    ecc
+}
+
+pub fn true_from_mean_anomaly_deg(mean_anomaly_deg: f64, e: f64) -> f64 {
+    let ecc = eccentric_anomaly(mean_anomaly_deg.to_radians(), e);
+    let nu = 2.0 * ((1.0 + e).sqrt() * (ecc / 2.0).sin()).atan2((1.0 - e).sqrt() * (ecc / 2.0).cos());
+    nu.to_degrees().rem_euclid(360.0)
+}
+
+pub fn mean_from_true_anomaly_deg(true_anomaly_deg: f64, e: f64) -> f64 {
+    let nu = true_anomaly_deg.to_radians();
+    let ecc = 2.0 * ((1.0 - e).sqrt() * (nu / 2.0).sin()).atan2((1.0 + e).sqrt() * (nu / 2.0).cos());
+    (ecc - e * ecc.sin()).to_degrees().rem_euclid(360.0)
+}
+
+/// Two-body propagation of a state vector by `dt_days`. `None` for unbound (e ≥ 1) orbits.
+pub fn propagate(p: Vec3, v: Vec3, dt_days: f64, mu: f64) -> Option<(Vec3, Vec3)> {
+    let mut el = state_to_elements(p, v, mu);
+    if el.e >= 1.0 || el.a_au <= 0.0 {
+        return None;
+    }
+    let mean_motion_deg = (mu / el.a_au.powi(3)).sqrt().to_degrees();
+    let m = mean_from_true_anomaly_deg(el.true_anomaly_deg, el.e) + mean_motion_deg * dt_days;
+    el.true_anomaly_deg = true_from_mean_anomaly_deg(m, el.e);
+    Some(elements_to_state(&el, mu))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physics::GM_SUN_AU3_D2;
+
+    fn assert_close(actual: f64, expected: f64, tol: f64, what: &str) {
+        assert!((actual - expected).abs() <= tol, "{}: {} vs {} (tol {})", what, actual, expected, tol);
+    }
+
+    fn angle_diff(a: f64, b: f64) -> f64 {
+        ((a - b + 180.0).rem_euclid(360.0) - 180.0).abs()
+    }
+
+    #[test]
+    fn earth_at_j2000_matches_mean_elements() {
+        // Horizons heliocentric ecliptic state of Earth at 2000-Jan-01 12:00 TDB.
+        let p = Vec3 { x: -1.771_351_029_694_605e-1, y: 9.672_416_861_070_428e-1, z: -4.092_421_117_973_766e-6 };
+        let v = Vec3 { x: -1.720_762_505_701_73e-2, y: -3.158_782_705_778_585e-3, z: 1.050_630_211_376_594e-7 };
+        let el = state_to_elements(p, v, GM_SUN_AU3_D2);
+
+        // Osculating Earth (not the EMB) wobbles around the mean orbit, hence the loose bounds.
+        assert_close(el.a_au, 1.0, 0.001, "a");
+        assert_close(el.e, 0.0167, 0.001, "e");
+        assert!(el.i_deg < 0.01, "i = {}", el.i_deg);
+    }
+
+    #[test]
+    fn round_trips_inclined_eccentric_orbit() {
+        let el = KeplerElements {
+            a_au: 2.77,
+            e: 0.3,
+            i_deg: 10.6,
+            raan_deg: 80.3,
+            arg_periapsis_deg: 73.6,
+            true_anomaly_deg: 200.0,
+        };
+        let (p, v) = elements_to_state(&el, GM_SUN_AU3_D2);
+        let back = state_to_elements(p, v, GM_SUN_AU3_D2);
+
+        assert_close(back.a_au, el.a_au, 1e-9, "a");
+        assert_close(back.e, el.e, 1e-9, "e");
+        assert_close(back.i_deg, el.i_deg, 1e-9, "i");
+        assert!(angle_diff(back.raan_deg, el.raan_deg) < 1e-7, "Ω {}", back.raan_deg);
+        assert!(angle_diff(back.arg_periapsis_deg, el.arg_periapsis_deg) < 1e-7, "ω {}", back.arg_periapsis_deg);
+        assert!(angle_diff(back.true_anomaly_deg, el.true_anomaly_deg) < 1e-7, "ν {}", back.true_anomaly_deg);
+    }
+
+    #[test]
+    fn circular_equatorial_orbit_has_no_nan() {
+        let speed = GM_SUN_AU3_D2.sqrt();
+        let el = state_to_elements(
+            Vec3 { x: 0.0, y: 1.0, z: 0.0 },
+            Vec3 { x: -speed, y: 0.0, z: 0.0 },
+            GM_SUN_AU3_D2,
+        );
+
+        assert_close(el.a_au, 1.0, 1e-12, "a");
+        assert!(el.e < 1e-12, "e = {}", el.e);
+        assert_eq!(el.raan_deg, 0.0);
+        assert_eq!(el.arg_periapsis_deg, 0.0);
+        assert_close(el.true_anomaly_deg, 90.0, 1e-9, "true longitude");
+    }
+
+    #[test]
+    fn retrograde_orbit_inclination() {
+        let speed = GM_SUN_AU3_D2.sqrt();
+        let el = state_to_elements(
+            Vec3 { x: 1.0, y: 0.0, z: 0.0 },
+            Vec3 { x: 0.0, y: -speed, z: 0.0 },
+            GM_SUN_AU3_D2,
+        );
+        assert_close(el.i_deg, 180.0, 1e-9, "i");
+    }
+}
@@ -0,0 +1,189 @@
+use chrono::{DateTime, Utc};
+
+use crate::orbital_mechanics::{
+    elements_to_state, propagate, state_to_elements, true_from_mean_anomaly_deg, KeplerElements,
+};
+use crate::types::{BodyClass, BodyMeta, Vec3};
+
+pub const AU_KM: f64 = 149_597_870.7;
+pub const SUN_MASS_KG: f64 = 1.98847e30;
+pub const GM_SUN_M3_S2: f64 = 1.327_124_400_18e20;
+/// Gaussian gravitational constant squared: GM☉ in AU³/day².
+pub const GM_SUN_AU3_D2: f64 = 2.959_122_082_855_911e-4;
+pub const SOLAR_CONSTANT_W_M2: f64 = 1361.0; // total solar irradiance at 1 AU
+pub const G_KM3_KG_S2: f64 = 6.6743e-20;
+pub const SECONDS_PER_DAY: f64 = 86_400.0;
+/// TDB (≈ TT) minus UTC, taken as constant: 32.184 s plus the 37 leap seconds since 2017.
+pub const TDB_MINUS_UTC_S: f64 = 69.184;
+pub const SPEED_OF_LIGHT_AU_D: f64 = 299_792.458 * SECONDS_PER_DAY / AU_KM;
+const STEFAN_BOLTZMANN: f64 = 5.670_374_419e-8;
+
+/// Approximate Hill-sphere radius at heliocentric distance `r_au`: r · ∛(m / 3M☉).
+/// `None` for the Sun itself.
+pub fn hill_radius_au(meta: &BodyMeta, r_au: f64) -> Option<f64> {
+    meta.orbit_au?;
+    Some(r_au * (meta.mass_kg / (3.0 * SUN_MASS_KG)).cbrt())
+}
+
+/// Laplace sphere of influence at heliocentric distance `r_au`: r · (m / M☉)^(2/5), inside
+/// which the body's pull dominates the Sun's for patched-conic trajectories. `None` for the
+/// Sun and for moons.
+pub fn soi_radius_au(meta: &BodyMeta, r_au: f64) -> Option<f64> {
+    meta.orbit_au?;
+    Some(r_au * (meta.mass_kg / SUN_MASS_KG).powf(0.4))
+}
+
+/// Density of the satellite the Roche limit is given for: ice, as in Saturn's rings.
+pub const ROCHE_SATELLITE_DENSITY_KG_M3: f64 = 1000.0;
+
+/// Fluid Roche limit for an icy satellite, 2.44 R (ρ / ρₛ)^(1/3), inside which tides pull it
+/// apart. `None` without a known mass and radius.
+pub fn roche_limit_au(meta: &BodyMeta) -> Option<f64> {
+    if meta.mass_kg <= 0.0 || meta.radius_km <= 0.0 {
+        return None;
+    }
+    let r_m = meta.radius_km * 1e3;
+    let density = meta.mass_kg / (4.0 / 3.0 * std::f64::consts::PI * r_m * r_m * r_m);
+    Some(2.44 * meta.radius_km * (density / ROCHE_SATELLITE_DENSITY_KG_M3).cbrt() / AU_KM)
+}
+
+/// The Sun's position and velocity about the solar-system barycentre from the heliocentric
+/// states of the bodies pulling on it, as `(mass_kg, pos, vel)`: the barycentre lies at
+/// Σmᵢrᵢ / (M☉ + Σmᵢ) from the Sun.
+pub fn sun_barycentric(bodies: &[(f64, Vec3, Vec3)]) -> (Vec3, Vec3) {
+    let total = SUN_MASS_KG + bodies.iter().map(|b| b.0).sum::<f64>();
+    bodies.iter().fold((Vec3::ZERO, Vec3::ZERO), |(p, v), &(m, r, w)| (p - r * (m / total), v - w * (m / total)))
+}
+
+/// Solar irradiance at `r_au` by the inverse-square law.
+pub fn irradiance_w_m2(r_au: f64) -> f64 {
+    SOLAR_CONSTANT_W_M2 / (r_au * r_au)
+}
+
+/// Blackbody equilibrium temperature for a fast rotator (heat spread over the whole sphere).
+pub fn equilibrium_temp_k(meta: &BodyMeta, r_au: f64) -> f64 {
+    (irradiance_w_m2(r_au) * (1.0 - meta.bond_albedo) / (4.0 * STEFAN_BOLTZMANN)).powf(0.25)
+}
+
+/// Differential solar pull across the body's radius: 2·GM☉·R / r³.
+pub fn solar_tidal_accel_m_s2(meta: &BodyMeta, r_au: f64) -> f64 {
+    let r_m = r_au * AU_KM * 1e3;
+    2.0 * GM_SUN_M3_S2 * meta.radius_km * 1e3 / (r_m * r_m * r_m)
+}
+
+/// Apparent magnitude seen from `delta_au` away, H + 5 log10(r Δ), ignoring the phase
+/// angle (which dims Mercury and Venus by several magnitudes near inferior conjunction).
+pub fn apparent_magnitude(meta: &BodyMeta, r_au: f64, delta_au: f64) -> f64 {
+    match meta.class {
+        BodyClass::Star => meta.abs_mag + 5.0 * delta_au.log10(),
+        _ => meta.abs_mag + 5.0 * (r_au * delta_au).log10(),
+    }
+}
+
+/// One-way light time over `dist_au`, in seconds.
+pub fn light_time_s(dist_au: f64) -> f64 {
+    dist_au / SPEED_OF_LIGHT_AU_D * SECONDS_PER_DAY
+}
+
+pub fn au_per_day_to_km_s(v_au_d: f64) -> f64 {
+    v_au_d * AU_KM / SECONDS_PER_DAY
+}
+
+/// Speed of a circular heliocentric orbit at `r_au`: √(GM☉ / r).
+pub fn circular_speed_km_s(r_au: f64) -> f64 {
+    (GM_SUN_M3_S2 * 1e-9 / (r_au * AU_KM)).sqrt()
+}
+
+/// Speed needed to escape the Sun from `r_au`: √2 × circular speed.
+pub fn solar_escape_speed_km_s(r_au: f64) -> f64 {
+    std::f64::consts::SQRT_2 * circular_speed_km_s(r_au)
+}
+
+/// Escape speed from the body's own surface: √(2Gm / R).
+pub fn surface_escape_speed_km_s(meta: &BodyMeta) -> f64 {
+    (2.0 * G_KM3_KG_S2 * meta.mass_kg / meta.radius_km).sqrt()
+}
+
+/// Conserved two-body quantities and the shape of the orbit they imply.
+#[derive(Debug, Clone, Copy)]
+pub struct OrbitDiagnostics {
+    pub energy: f64,   // specific orbital energy (AU²/d²)
+    pub ang_mom: f64,  // specific angular momentum |r × v| (AU²/d)
+    pub a_au: f64,     // semi-major axis (negative for hyperbolic)
+    pub e: f64,        // eccentricity
+    pub i_deg: f64,    // inclination to the reference plane
+}
+
+pub fn orbit_diagnostics(p: Vec3, v: Vec3) -> OrbitDiagnostics {
+    let el = state_to_elements(p, v, GM_SUN_AU3_D2);
+    OrbitDiagnostics {
+        energy: v.dot(v) / 2.0 - GM_SUN_AU3_D2 / p.norm(),
+        ang_mom: p.cross(v).norm(),
+        a_au: el.a_au,
+        e: el.e,
+        i_deg: el.i_deg,
+    }
+}
+
+/// J2000 mean orbital elements (Standish, ecliptic of J2000) to judge live values against.
+/// The "Earth" row is the Earth–Moon barycenter.
+#[derive(Debug, Clone, Copy)]
+pub struct ReferenceOrbit {
+    pub name: &'static str,
+    pub a_au: f64,
+    pub e: f64,
+    pub i_deg: f64,
+    pub mean_longitude_deg: f64,  // L
+    pub long_perihelion_deg: f64, // ϖ = Ω + ω
+    pub long_node_deg: f64,       // Ω
+}
+
+pub const REFERENCE_ORBITS: &[ReferenceOrbit] = &[
+    ReferenceOrbit { name: "Mercury", a_au: 0.38709927,  e: 0.20563593, i_deg: 7.00497902,  mean_longitude_deg: 252.25032350, long_perihelion_deg: 77.45779628,  long_node_deg: 48.33076593 },
+    ReferenceOrbit { name: "Venus",   a_au: 0.72333566,  e: 0.00677672, i_deg: 3.39467605,  mean_longitude_deg: 181.97909950, long_perihelion_deg: 131.60246718, long_node_deg: 76.67984255 },
+    ReferenceOrbit { name: "Earth",   a_au: 1.00000261,  e: 0.01671123, i_deg: -0.00001531, mean_longitude_deg: 100.46457166, long_perihelion_deg: 102.93768193, long_node_deg: 0.0 },
+    ReferenceOrbit { name: "Mars",    a_au: 1.52371034,  e: 0.09339410, i_deg: 1.84969142,  mean_longitude_deg: -4.55343205,  long_perihelion_deg: -23.94362959, long_node_deg: 49.55953891 },
+    ReferenceOrbit { name: "Jupiter", a_au: 5.20288700,  e: 0.04838624, i_deg: 1.30439695,  mean_longitude_deg: 34.39644051,  long_perihelion_deg: 14.72847983,  long_node_deg: 100.47390909 },
+    ReferenceOrbit { name: "Saturn",  a_au: 9.53667594,  e: 0.05386179, i_deg: 2.48599187,  mean_longitude_deg: 49.95424423,  long_perihelion_deg: 92.59887831,  long_node_deg: 113.66242448 },
+    ReferenceOrbit { name: "Uranus",  a_au: 19.18916464, e: 0.04725744, i_deg: 0.77263783,  mean_longitude_deg: 313.23810451, long_perihelion_deg: 170.95427630, long_node_deg: 74.01692503 },
+    ReferenceOrbit { name: "Neptune", a_au: 30.06992276, e: 0.00859048, i_deg: 1.77004347,  mean_longitude_deg: -55.12002969, long_perihelion_deg: 44.96476227,  long_node_deg: 131.78422574 },
+    ReferenceOrbit { name: "Pluto",   a_au: 39.48211675, e: 0.24882730, i_deg: 17.14001206, mean_longitude_deg: 238.92903833, long_perihelion_deg: 224.06891629, long_node_deg: 110.30393684 },
+];
+
+impl ReferenceOrbit {
+    /// The mean elements at J2000, treated as an osculating orbit.
+    pub fn elements_at_j2000(&self) -> KeplerElements {
+        KeplerElements {
+            a_au: self.a_au,
+            e: self.e,
+            i_deg: self.i_deg,
+            raan_deg: self.long_node_deg,
+            arg_periapsis_deg: self.long_perihelion_deg - self.long_node_deg,
+            true_anomaly_deg: true_from_mean_anomaly_deg(
+                self.mean_longitude_deg - self.long_perihelion_deg,
+                self.e,
+            ),
+        }
+    }
+
+    /// Two-body position `days` after J2000 — good to a fraction of a degree for a few decades.
+    pub fn position_at(&self, days: f64) -> Vec3 {
+        let (p, v) = elements_to_state(&self.elements_at_j2000(), GM_SUN_AU3_D2);
+        propagate(p, v, days, GM_SUN_AU3_D2).map_or(p, |(p, _)| p)
+    }
+}
+
+/// Days since J2000.0 (2000-01-01 12:00 TT), approximating TT as UTC + 69.184 s.
+pub fn days_since_j2000(utc: DateTime<Utc>) -> f64 {
+    const J2000_UNIX_S: f64 = 946_728_000.0; // 2000-01-01T12:00:00 as if it were UTC
+    (utc.timestamp_millis() as f64 / 1000.0 + TDB_MINUS_UTC_S - J2000_UNIX_S) / SECONDS_PER_DAY
+}
+
+/// Julian Date of `utc` on the UT scale (JD 2440587.5 is the Unix epoch).
+pub fn julian_date(utc: DateTime<Utc>) -> f64 {
+    utc.timestamp_millis() as f64 / 1000.0 / SECONDS_PER_DAY + 2_440_587.5
+}
+
+pub fn reference_orbit(name: &str) -> Option<&'static ReferenceOrbit> {
+    REFERENCE_ORBITS.iter().find(|r| r.name == name)
+}
@@ -0,0 +1,126 @@
+//! Calendar-style date/time picker for time travel (`T`) and range fetches (`F`): fields are
+//! moved between with ←/→ and stepped with ↑/↓, so no timestamp has to be typed.
+
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Months, NaiveDate, Timelike, Utc};
+
+pub const FIELDS: [&str; 5] = ["year", "month", "day", "hour", "minute"];
+
+/// What the picked time is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Purpose {
+    /// The time-travel epoch.
+    TimeTravel,
+    /// The start of a range to fetch; Enter moves on to its end.
+    RangeStart,
+    /// The end of a range starting at the given time.
+    RangeEnd(DateTime<Utc>),
+}
+
+impl Purpose {
+    pub fn title(self) -> String {
+        match self {
+            Purpose::TimeTravel => "Travel to (UTC)".into(),
+            Purpose::RangeStart => "Range start (UTC)".into(),
+            Purpose::RangeEnd(from) => format!("Range end, from {} (UTC)", from.format("%Y-%m-%d")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DatePicker {
+    pub utc: DateTime<Utc>, // to the minute
+    pub field: usize,       // index into `FIELDS`
+    pub purpose: Purpose,
+}
+
+impl DatePicker {
+    /// Opens on `utc` with the day selected.
+    pub fn new(utc: DateTime<Utc>, purpose: Purpose) -> Self {
+        let utc = utc.with_second(0).and_then(|t| t.with_nanosecond(0)).unwrap_or(utc);
+        DatePicker { utc, field: 2, purpose }
+    }
+
+    pub fn next_field(&mut self) {
+        self.field = (self.field + 1).min(FIELDS.len() - 1);
+    }
+
+    pub fn prev_field(&mut self) {
+        self.field = self.field.saturating_sub(1);
+    }
+
+    /// Steps the selected field by `n`, carrying into the larger ones; a month step from the
+    /// 31st lands on the last day of a shorter month.
+    pub fn step(&mut self, n: i32) {
+        let stepped = match self.field {
+            0 => add_months(self.utc, n.saturating_mul(12)),
+            1 => add_months(self.utc, n),
+            2 => self.utc.checked_add_signed(ChronoDuration::days(n.into())),
+            3 => self.utc.checked_add_signed(ChronoDuration::hours(n.into())),
+            _ => self.utc.checked_add_signed(ChronoDuration::minutes(n.into())),
+        };
+        if let Some(t) = stepped {
+            self.utc = t;
+        }
+    }
+
+    /// Weeks of the picked month, Monday first, with the day numbers that fall in each.
+    pub fn month_grid(&self) -> Vec<[Option<u32>; 7]> {
+        let Some(first) = NaiveDate::from_ymd_opt(self.utc.year(), self.utc.month(), 1) else { return Vec::new() };
+        let offset = first.weekday().num_days_from_monday() as usize;
+        let mut weeks = Vec::new();
+        let mut week = [None; 7];
+        let mut col = offset;
+        for day in first.iter_days().take_while(|d| d.month() == first.month()) {
+            week[col] = Some(day.day());
+            col += 1;
+            if col == 7 {
+                weeks.push(week);
+                week = [None; 7];
+                col = 0;
+            }
+        }
+        if col > 0 {
+            weeks.push(week);
+        }
+        weeks
+    }
+}
+
+fn add_months(t: DateTime<Utc>, n: i32) -> Option<DateTime<Utc>> {
+    if n >= 0 {
+        t.checked_add_months(Months::new(n as u32))
+    } else {
+        t.checked_sub_months(Months::new(n.unsigned_abs()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn steps_carry_and_clamp() {
+        let mut p = DatePicker::new(Utc.with_ymd_and_hms(2024, 1, 31, 23, 59, 30).unwrap(), Purpose::TimeTravel);
+        assert_eq!(p.utc.second(), 0);
+        p.field = 1;
+        p.step(1);
+        assert_eq!(p.utc, Utc.with_ymd_and_hms(2024, 2, 29, 23, 59, 0).unwrap());
+        p.field = 4;
+        p.step(1);
+        assert_eq!(p.utc, Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap());
+        p.field = 0;
+        p.step(-10);
+        assert_eq!(p.utc.year(), 2014);
+    }
+
+    #[test]
+    fn month_grid_starts_on_monday() {
+        // February 2026 starts on a Sunday and fills five rows
+        let p = DatePicker::new(Utc.with_ymd_and_hms(2026, 2, 10, 0, 0, 0).unwrap(), Purpose::TimeTravel);
+        let grid = p.month_grid();
+        assert_eq!(grid[0], [None, None, None, None, None, None, Some(1)]);
+        assert_eq!(grid.len(), 5);
+        assert_eq!(grid[4][5], Some(28));
+    }
+}
@@ -0,0 +1,74 @@
+//! Named pins at fixed heliocentric points on the orbit map ("expected comet position", "L4
+//! camp"), dropped with `i` and drawn with their own glyph and color. Kept in a local JSON file,
+//! which can also be edited by hand, so they outlast the session.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::types::Vec3;
+
+/// Glyph for a pin that doesn't choose one.
+pub const DEFAULT_GLYPH: char = '⚑';
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pin {
+    pub name: String,
+    /// Heliocentric ecliptic J2000, AU.
+    pub pos_au: Vec3,
+    #[serde(default = "default_glyph")]
+    pub glyph: char,
+    /// A color name or `#rrggbb`, as for body colors; magenta if unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+fn default_glyph() -> char {
+    DEFAULT_GLYPH
+}
+
+pub fn pins_path() -> PathBuf {
+    dirs::data_dir().unwrap_or_else(std::env::temp_dir).join("solar-rs").join("pins.json")
+}
+
+/// All pins; none if the file is missing or unreadable.
+pub fn load() -> Vec<Pin> {
+    let Ok(text) = std::fs::read_to_string(pins_path()) else { return Vec::new() };
+    serde_json::from_str(&text).unwrap_or_default()
+}
+
+/// Rewrites the file with `pins`, which are few enough to keep whole.
+pub fn save(pins: &[Pin]) -> Result<()> {
+    let path = pins_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(pins)?)?;
+    Ok(())
+}
+
+/// Adds a pin, moving any other of the same name (ignoring case) to the new point but
+/// keeping its glyph and color.
+pub fn drop_pin(pins: &mut Vec<Pin>, name: &str, pos_au: Vec3) {
+    match pins.iter_mut().find(|p| p.name.eq_ignore_ascii_case(name)) {
+        Some(p) => p.pos_au = pos_au,
+        None => pins.push(Pin { name: name.to_string(), pos_au, glyph: DEFAULT_GLYPH, color: None }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dropping_a_known_name_moves_the_pin() {
+        let mut pins: Vec<Pin> = serde_json::from_str(r#"[{"name": "L4 camp", "pos_au": {"x": 1.0, "y": 2.0, "z": 0.0}, "glyph": "△"}]"#).unwrap();
+        assert_eq!(pins[0].color, None);
+        let to = Vec3 { x: -2.6, y: 4.5, z: 0.0 };
+        drop_pin(&mut pins, "l4 CAMP", to);
+        drop_pin(&mut pins, "comet", Vec3::ZERO);
+        assert_eq!(pins.len(), 2);
+        assert_eq!((pins[0].pos_au, pins[0].glyph), (to, '△'));
+        assert_eq!(pins[1].glyph, DEFAULT_GLYPH);
+    }
+}
@@ -0,0 +1,92 @@
+//! Low-power operation for always-on displays, such as a Raspberry Pi driving an LCD or e-ink
+//! panel around the clock: the SoC temperature and the firmware's throttling flags are
+//! sampled so the header can show them and the app can slow down further while throttled.
+
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::sleep;
+
+use crate::config::Power;
+use crate::types::{AlertLevel, DataState};
+
+/// First thermal zone, the SoC sensor on a Pi and on most single-board computers.
+const THERMAL_ZONE: &str = "/sys/class/thermal/thermal_zone0/temp";
+/// What `vcgencmd get_throttled` reads, exposed by Raspberry Pi kernels.
+const GET_THROTTLED: &str = "/sys/devices/platform/soc/soc:firmware/get_throttled";
+
+const SAMPLE_EVERY: Duration = Duration::from_secs(10);
+
+/// Raspberry Pi firmware throttling flags (bits 0-3 now, 16-19 since boot).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Throttling(pub u32);
+
+impl Throttling {
+    /// Any limit in effect right now.
+    pub fn active(self) -> bool {
+        self.0 & 0xf != 0
+    }
+
+    /// Conditions in effect now, e.g. "under-voltage, throttled".
+    pub fn describe(self) -> String {
+        let flags = [(0x1, "under-voltage"), (0x2, "frequency capped"), (0x4, "throttled"), (0x8, "soft temperature limit")];
+        flags.iter().filter(|(bit, _)| self.0 & bit != 0).map(|(_, name)| *name).collect::<Vec<_>>().join(", ")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Thermal {
+    pub temp_c: Option<f64>,
+    /// Only on Raspberry Pi kernels.
+    pub throttling: Option<Throttling>,
+}
+
+impl Thermal {
+    pub fn throttled(&self) -> bool {
+        self.throttling.is_some_and(Throttling::active)
+    }
+}
+
+/// Current readings, or `None` on systems exposing neither.
+pub fn sample() -> Option<Thermal> {
+    let read = |path: &str| std::fs::read_to_string(Path::new(path)).ok();
+    let temp_c = read(THERMAL_ZONE).and_then(|s| s.trim().parse::<f64>().ok()).map(|m| m / 1000.0);
+    let throttling = read(GET_THROTTLED)
+        .and_then(|s| u32::from_str_radix(s.trim().trim_start_matches("0x"), 16).ok())
+        .map(Throttling);
+    (temp_c.is_some() || throttling.is_some()).then_some(Thermal { temp_c, throttling })
+}
+
+/// Keeps `DataState::thermal` current while the low-power profile is active, alerting when
+/// the firmware starts throttling.
+pub async fn monitor(state: Arc<Mutex<DataState>>) {
+    loop {
+        let low_power = state.lock().unwrap().config.power == Power::Low;
+        let thermal = if low_power { sample() } else { None };
+        {
+            let mut s = state.lock().unwrap();
+            let was_throttled = s.thermal.is_some_and(|t| t.throttled());
+            if let Some(t) = thermal.filter(|t| t.throttled() && !was_throttled) {
+                let temp = t.temp_c.map(|c| format!(" at {:.1} °C", c)).unwrap_or_default();
+                let flags = t.throttling.map(Throttling::describe).unwrap_or_default();
+                s.alert(AlertLevel::Warning, format!("CPU {}{}", flags, temp));
+            }
+            s.thermal = thermal;
+        }
+        sleep(SAMPLE_EVERY).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttling_flags() {
+        assert!(!Throttling(0x50000).active()); // happened since boot, not now
+        assert_eq!(Throttling(0x50005).describe(), "under-voltage, throttled");
+        assert!(Throttling(0x8).active());
+    }
+}
@@ -0,0 +1,111 @@
+//! Publishes each refresh's state vectors to Redis for telemetry pipelines: a JSON message per
+//! refresh on a pub/sub channel, and/or an entry per body on a stream. Runs beside any mode
+//! (TUI, `daemon`, `serve`) once a `[redis]` section or `--redis <url>` is given.
+
+use redis::aio::MultiplexedConnection;
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use solar_rs::config::Redis;
+use solar_rs::types::{AlertLevel, BodyState, DataState};
+
+/// How often the shared state is checked for a new refresh.
+const POLL: Duration = Duration::from_secs(1);
+
+pub fn start(state: Arc<Mutex<DataState>>, url: Option<String>) {
+    tokio::spawn(run(state, url));
+}
+
+/// The `[redis]` section, with `--redis` replacing its URL (or standing in for it).
+fn settings(s: &DataState, url: &Option<String>) -> Option<Redis> {
+    match (s.config.redis.clone(), url) {
+        (Some(r), Some(url)) => Some(Redis { url: url.clone(), ..r }),
+        (None, Some(url)) => Some(Redis { url: url.clone(), ..Redis::default() }),
+        (r, None) => r,
+    }
+}
+
+async fn run(state: Arc<Mutex<DataState>>, url: Option<String>) {
+    let mut sent: Option<String> = None;
+    let mut conn: Option<(String, MultiplexedConnection)> = None;
+    let mut failing = false;
+    loop {
+        tokio::time::sleep(POLL).await;
+        let (settings, utc, status, bodies) = {
+            let s = state.lock().unwrap();
+            let Some(utc) = s.last_update_utc.clone().filter(|u| sent.as_ref() != Some(u)) else { continue };
+            (settings(&s, &url), utc, s.status.clone(), s.bodies.clone())
+        };
+        let Some(settings) = settings else { continue };
+        sent = Some(utc.clone());
+
+        // Reconnects after a failure, and when the config names another server
+        if conn.as_ref().is_none_or(|(u, _)| *u != settings.url) {
+            conn = match connect(&settings.url).await {
+                Ok(c) => Some((settings.url.clone(), c)),
+                Err(e) => {
+                    report(&state, &mut failing, Some(format!("Redis: {}", e)));
+                    continue;
+                }
+            };
+        }
+        let Some((_, c)) = conn.as_mut() else { continue };
+        match publish(c, &settings, &utc, &status, &bodies).await {
+            Ok(()) => report(&state, &mut failing, None),
+            Err(e) => {
+                conn = None;
+                report(&state, &mut failing, Some(format!("Redis: {}", e)));
+            }
+        }
+    }
+}
+
+async fn connect(url: &str) -> redis::RedisResult<MultiplexedConnection> {
+    redis::Client::open(url)?.get_multiplexed_async_connection().await
+}
+
+/// Alerts when publishing starts failing and when it recovers, not on every refresh.
+fn report(state: &Arc<Mutex<DataState>>, failing: &mut bool, error: Option<String>) {
+    let was_failing = std::mem::replace(failing, error.is_some());
+    match error {
+        Some(e) if !was_failing => state.lock().unwrap().notify(AlertLevel::Warning, e),
+        None if was_failing => state.lock().unwrap().alert(AlertLevel::Info, "Redis publishing resumed"),
+        _ => {}
+    }
+}
+
+async fn publish(conn: &mut MultiplexedConnection, settings: &Redis, utc: &str, status: &str, bodies: &[BodyState]) -> redis::RedisResult<()> {
+    let bodies: Vec<_> = bodies.iter().filter_map(|b| Some((b, b.pos_au?, b.vel_au_d?))).collect();
+    let source = |b: &BodyState| b.source.map_or("", |p| p.source.label());
+
+    if let Some(channel) = settings.channel() {
+        let message = json!({
+            "schema_version": 1,
+            "last_update_utc": utc,
+            "status": status,
+            "bodies": bodies
+                .iter()
+                .map(|&(b, p, v)| json!({ "name": b.name, "id": b.id.to_string(), "pos_au": p, "vel_au_d": v, "source": source(b) }))
+                .collect::<Vec<_>>(),
+        });
+        redis::cmd("PUBLISH").arg(channel).arg(message.to_string()).query_async::<()>(conn).await?;
+    }
+
+    if let Some(stream) = &settings.stream {
+        let mut pipe = redis::pipe();
+        for &(b, p, v) in &bodies {
+            pipe.cmd("XADD")
+                .arg(stream)
+                .arg("MAXLEN")
+                .arg("~")
+                .arg(settings.stream_maxlen)
+                .arg("*")
+                .arg(&[("utc", utc.to_string()), ("body", b.name.to_string()), ("id", b.id.to_string()), ("source", source(b).to_string())])
+                .arg(&[("x", p.x), ("y", p.y), ("z", p.z), ("vx", v.x), ("vy", v.y), ("vz", v.z)])
+                .ignore();
+        }
+        pipe.query_async::<()>(conn).await?;
+    }
+    Ok(())
+}
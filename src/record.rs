@@ -0,0 +1,109 @@
+//! `--record <file.cast>`: everything drawn to the terminal, with its timing, written as an
+//! asciicast v2 recording (asciinema's format) as it happens, so tours and time-lapses can be
+//! shared and replayed exactly without running anything beside the app.
+
+use serde_json::json;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// The terminal's output, copied into a recording when there is one. Each flush (one per drawn
+/// frame) becomes one output event.
+pub struct Recorder<W: Write> {
+    out: W,
+    cast: Option<Cast>,
+}
+
+struct Cast {
+    file: Box<dyn Write + Send>,
+    started: Instant,
+    pending: Vec<u8>,
+    size: (u16, u16),
+}
+
+impl<W: Write> Recorder<W> {
+    pub fn new(out: W) -> Self {
+        Recorder { out, cast: None }
+    }
+
+    /// Also records into `path`, replacing it.
+    pub fn to_file(out: W, path: &Path) -> io::Result<Self> {
+        let size = crossterm::terminal::size()?;
+        Self::to_writer(out, Box::new(BufWriter::new(File::create(path)?)), size)
+    }
+
+    fn to_writer(out: W, mut file: Box<dyn Write + Send>, (width, height): (u16, u16)) -> io::Result<Self> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        let term = std::env::var("TERM").unwrap_or_default();
+        let header = json!({ "version": 2, "width": width, "height": height, "timestamp": timestamp, "title": "solar-rs", "env": { "TERM": term } });
+        writeln!(file, "{}", header)?;
+        Ok(Recorder { out, cast: Some(Cast { file, started: Instant::now(), pending: Vec::new(), size: (width, height) }) })
+    }
+}
+
+impl<W: Write> Write for Recorder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.out.write(buf)?;
+        if let Some(cast) = &mut self.cast {
+            cast.pending.extend_from_slice(&buf[..n]);
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()?;
+        let Some(cast) = &mut self.cast else { return Ok(()) };
+        let at = cast.started.elapsed().as_secs_f64();
+        // A resize goes in ahead of the frame drawn for it
+        if let Some(size) = crossterm::terminal::size().ok().filter(|&s| s != cast.size) {
+            cast.size = size;
+            writeln!(cast.file, "{}", json!([at, "r", format!("{}x{}", size.0, size.1)]))?;
+        }
+        if !cast.pending.is_empty() {
+            let data = String::from_utf8_lossy(&cast.pending).into_owned();
+            writeln!(cast.file, "{}", json!([at, "o", data]))?;
+            cast.pending.clear();
+        }
+        cast.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// A file stand-in the test can read back.
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn writes_a_header_then_one_event_per_flush() {
+        let cast = Shared::default();
+        let mut r = Recorder::to_writer(Vec::new(), Box::new(cast.clone()), (120, 40)).unwrap();
+        write!(r, "\x1b[2J").unwrap();
+        write!(r, "☉ Sun").unwrap();
+        r.flush().unwrap();
+        r.flush().unwrap();
+        assert_eq!(r.out, "\x1b[2J☉ Sun".as_bytes());
+
+        let text = String::from_utf8(cast.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<serde_json::Value> = text.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!((lines[0]["version"].as_u64(), lines[0]["width"].as_u64(), lines[0]["height"].as_u64()), (Some(2), Some(120), Some(40)));
+        // Under a real terminal its size may differ, and go in as a resize
+        let output: Vec<_> = lines[1..].iter().filter(|l| l[1] == "o").collect();
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0][2].as_str(), Some("\x1b[2J☉ Sun"));
+        assert!(output[0][0].as_f64().is_some_and(|t| t >= 0.0));
+    }
+}
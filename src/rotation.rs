@@ -0,0 +1,70 @@
+//! Spin axes and rotation phase from the IAU WGCCRE 2015 rotation models (Archinal et al.
+//! 2018), keeping only the secular terms: the dropped periodic terms move the poles by under
+//! a degree, which is invisible in a terminal-sized gizmo.
+
+use crate::frames::equatorial_to_ecliptic;
+use crate::types::Vec3;
+
+/// Pole α₀ = ra0 + ra_rate·T, δ₀ = dec0 + dec_rate·T (T in Julian centuries, ICRF degrees) and
+/// prime meridian W = w0 + w_rate·d (d in days), all from J2000 TDB.
+pub struct RotationModel {
+    pub name: &'static str,
+    ra0: f64,
+    ra_rate: f64,
+    dec0: f64,
+    dec_rate: f64,
+    w0: f64,
+    w_rate: f64,
+}
+
+#[rustfmt::skip]
+const MODELS: &[RotationModel] = &[
+    RotationModel { name: "Sun",     ra0: 286.13,     ra_rate: 0.0,       dec0: 63.87,     dec_rate: 0.0,      w0: 84.176,  w_rate: 14.184_4 },
+    RotationModel { name: "Mercury", ra0: 281.010_3,  ra_rate: -0.032_8,  dec0: 61.415_5,  dec_rate: -0.004_9, w0: 329.598_8, w_rate: 6.138_510_8 },
+    RotationModel { name: "Venus",   ra0: 272.76,     ra_rate: 0.0,       dec0: 67.16,     dec_rate: 0.0,      w0: 160.20,  w_rate: -1.481_368_8 },
+    RotationModel { name: "Earth",   ra0: 0.0,        ra_rate: -0.641,    dec0: 90.0,      dec_rate: -0.557,   w0: 190.147, w_rate: 360.985_623_5 },
+    RotationModel { name: "Mars",    ra0: 317.681_43, ra_rate: -0.106_1,  dec0: 52.886_5,  dec_rate: -0.060_9, w0: 176.630, w_rate: 350.891_982_26 },
+    RotationModel { name: "Jupiter", ra0: 268.056_595, ra_rate: -0.006_499, dec0: 64.495_303, dec_rate: 0.002_413, w0: 284.95, w_rate: 870.536 },
+    RotationModel { name: "Saturn",  ra0: 40.589,     ra_rate: -0.036,    dec0: 83.537,    dec_rate: -0.004,   w0: 38.90,   w_rate: 810.793_902_4 },
+    RotationModel { name: "Uranus",  ra0: 257.311,    ra_rate: 0.0,       dec0: -15.175,   dec_rate: 0.0,      w0: 203.81,  w_rate: -501.160_092_8 },
+    RotationModel { name: "Neptune", ra0: 299.36,     ra_rate: 0.0,       dec0: 43.46,     dec_rate: 0.0,      w0: 249.978, w_rate: 541.139_775_7 },
+    RotationModel { name: "Ceres",   ra0: 291.418,    ra_rate: 0.0,       dec0: 66.764,    dec_rate: 0.0,      w0: 170.650, w_rate: 952.153_2 },
+    RotationModel { name: "Pluto",   ra0: 132.993,    ra_rate: 0.0,       dec0: -6.163,    dec_rate: 0.0,      w0: 302.695, w_rate: 56.362_522_5 },
+];
+
+pub fn rotation_model(name: &str) -> Option<&'static RotationModel> {
+    MODELS.iter().find(|m| m.name == name)
+}
+
+impl RotationModel {
+    /// North pole (the IAU pole, on the north side of the invariable plane) as a unit J2000
+    /// ecliptic vector, `days` after J2000.
+    pub fn pole(&self, days: f64) -> Vec3 {
+        let t = days / 36_525.0;
+        let ra = (self.ra0 + self.ra_rate * t).to_radians();
+        let dec = (self.dec0 + self.dec_rate * t).to_radians();
+        equatorial_to_ecliptic(Vec3 { x: dec.cos() * ra.cos(), y: dec.cos() * ra.sin(), z: dec.sin() })
+    }
+
+    /// Spin angular momentum direction: the IAU north pole, flipped for retrograde rotators.
+    pub fn spin_axis(&self, days: f64) -> Vec3 {
+        self.pole(days) * self.w_rate.signum()
+    }
+
+    /// Prime meridian angle W, degrees in 0..360.
+    pub fn prime_meridian_deg(&self, days: f64) -> f64 {
+        (self.w0 + self.w_rate * days).rem_euclid(360.0)
+    }
+
+    /// Sidereal rotation period in hours; negative for retrograde spin.
+    pub fn period_hours(&self) -> f64 {
+        360.0 / self.w_rate * 24.0
+    }
+}
+
+/// Obliquity: angle between the spin axis and the orbit normal, degrees. Above 90° the body
+/// spins backwards relative to its orbit: Venus (177°) nearly upside down, Uranus (98°) on
+/// its side.
+pub fn obliquity_deg(spin_axis: Vec3, orbit_normal: Vec3) -> f64 {
+    spin_axis.cross(orbit_normal).norm().atan2(spin_axis.dot(orbit_normal)).to_degrees()
+}
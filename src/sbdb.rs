@@ -0,0 +1,154 @@
+//! Name lookup in JPL's Small-Body Database: turns what is typed after `/` ("Apophis", "67P",
+//! "2024 YR4") into an extra body with a Horizons COMMAND, so any asteroid or comet can join
+//! the live view for the rest of the session.
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+use url::Url;
+
+use crate::bandwidth;
+use crate::config::{apply_config, ExtraBody};
+use crate::horizons::{get_api_text, http_client, Retry};
+use crate::types::{meta_by_id, meta_by_name, register_extra_body, AlertLevel, BodyId, DataState};
+
+/// Matches listed when a search term is ambiguous.
+const MAX_CHOICES: usize = 5;
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    object: Option<Object>,
+    #[serde(default)]
+    phys_par: Vec<PhysPar>,
+    /// Set instead of `object` when more than one body matches.
+    #[serde(default)]
+    list: Vec<Choice>,
+    message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Object {
+    shortname: String,
+    spkid: String,
+    /// `an`/`au` for asteroids, `cn`/`cu` for comets (numbered or not).
+    kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PhysPar {
+    name: String,
+    value: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    name: String,
+}
+
+fn lookup_url(query: &str) -> Result<Url> {
+    let mut url = Url::parse("https://ssd-api.jpl.nasa.gov/sbdb.api")?;
+    url.query_pairs_mut().append_pair("sstr", query.trim()).append_pair("phys-par", "1");
+    Ok(url)
+}
+
+/// The body an SBDB response describes, fetched from Horizons by SPK id in the small-body
+/// form (`DES=20099942;`). Ambiguous and unknown names come back as errors for the status line.
+fn parse_lookup(json: &str) -> Result<ExtraBody> {
+    let response: Response = serde_json::from_str(json)?;
+    let Some(object) = response.object else {
+        if !response.list.is_empty() {
+            let names: Vec<&str> = response.list.iter().take(MAX_CHOICES).map(|c| c.name.as_str()).collect();
+            return Err(anyhow!("{} matches, e.g. {}", response.list.len(), names.join(", ")));
+        }
+        return Err(anyhow!("{}", response.message.as_deref().unwrap_or("no such object")));
+    };
+    let spkid: i32 = object.spkid.parse().map_err(|_| anyhow!("bad SPK id {:?}", object.spkid))?;
+    // "99942 Apophis" reads as Apophis; unnumbered ones come in parentheses, "(2024 YR4)"
+    let short = object.shortname.trim();
+    let name = match short.split_once(' ') {
+        Some((number, rest)) if number.chars().all(|c| c.is_ascii_digit()) => rest,
+        _ => short,
+    }
+    .trim_matches(['(', ')']);
+    let abs_mag = response.phys_par.iter().find(|p| p.name == "H").and_then(|p| p.value.as_deref()?.parse().ok());
+    Ok(ExtraBody {
+        name: name.to_string(),
+        command: format!("DES={};", spkid),
+        id: Some(spkid),
+        icon: object.kind.starts_with('c').then_some('☄'),
+        color: None,
+        abs_mag,
+    })
+}
+
+/// Looks `query` up and adds the body to the live view; the next refresh fetches its vectors.
+/// Bodies already known, by SPK id or name, are pointed to instead of added twice.
+pub async fn add_body(state: Arc<Mutex<DataState>>, query: String) {
+    let (config, offline) = {
+        let s = state.lock().unwrap();
+        (s.config.clone(), s.offline)
+    };
+    if offline {
+        state.lock().unwrap().status = format!("Offline; \"{}\" can't be looked up in the SBDB", query.trim());
+        return;
+    }
+    if bandwidth::current().quota_reached(&config) {
+        state.lock().unwrap().status = "Daily download quota reached; SBDB lookups are off until midnight".into();
+        return;
+    }
+    let client = http_client(&config);
+    let on_retry = |r: &Retry| state.lock().unwrap().status = format!("SBDB lookup for \"{}\": {}", query.trim(), r);
+    let result = match lookup_url(&query) {
+        Ok(url) => get_api_text(&client, url, "SBDB", &on_retry).await.map_err(anyhow::Error::from).and_then(|json| parse_lookup(&json)),
+        Err(e) => Err(e),
+    };
+
+    let mut s = state.lock().unwrap();
+    s.bandwidth = bandwidth::current();
+    let body = match result {
+        Ok(body) => body,
+        Err(e) => {
+            s.status = format!("SBDB lookup for \"{}\": {:#}", query.trim(), e);
+            return;
+        }
+    };
+    if let Some(known) = body.id.and_then(|id| meta_by_id(BodyId(id))).or_else(|| meta_by_name(&body.name)) {
+        s.status = if s.bodies.iter().any(|b| b.id == known.id) {
+            format!("{} is already shown", known.name)
+        } else {
+            format!("{} is built in but hidden by the config (see `bodies`, D and s)", known.name)
+        };
+        return;
+    }
+    match register_extra_body(&body) {
+        Some(meta) => {
+            let config = (*s.config).clone();
+            apply_config(&mut s, config);
+            s.alert(AlertLevel::Info, format!("Added {} (SPK id {}) from the SBDB for this session", meta.name, meta.id));
+            s.status = format!("Added {}; its position arrives with the next refresh", meta.name);
+        }
+        None => s.status = format!("{} was added meanwhile", body.name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_responses() {
+        let apophis = r#"{"object":{"shortname":"99942 Apophis","spkid":"20099942","kind":"an","des":"99942"},
+            "phys_par":[{"name":"H","value":"19.09"}],"signature":{"version":"1.3"}}"#;
+        let body = parse_lookup(apophis).unwrap();
+        assert_eq!((body.name.as_str(), body.command.as_str(), body.id), ("Apophis", "DES=20099942;", Some(20099942)));
+        assert_eq!((body.icon, body.abs_mag), (None, Some(19.09)));
+
+        let comet = r#"{"object":{"shortname":"67P/Churyumov-Gerasimenko","spkid":"1000012","kind":"cn"}}"#;
+        assert_eq!(parse_lookup(comet).unwrap().icon, Some('☄'));
+        let unnumbered = r#"{"object":{"shortname":"(2024 YR4)","spkid":"54509621","kind":"au"}}"#;
+        assert_eq!(parse_lookup(unnumbered).unwrap().name, "2024 YR4");
+
+        let ambiguous = r#"{"code":"300","list":[{"pdes":"2000 AA","name":"2000 AA"},{"pdes":"2000 AB","name":"2000 AB"}]}"#;
+        assert_eq!(parse_lookup(ambiguous).unwrap_err().to_string(), "2 matches, e.g. 2000 AA, 2000 AB");
+    }
+}
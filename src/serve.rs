@@ -0,0 +1,204 @@
+//! `solar-rs serve`: a small JSON web service for dashboards, with container-style health
+//! probes. It follows a running primary instance if there is one, otherwise it fetches itself.
+//!
+//! API responses are versioned: each lives under `/api/v<N>/` and carries a matching
+//! `schema_version`. Incompatible changes get a new version alongside the old one; the
+//! OpenAPI document at `/openapi.json` describes all of them.
+//!
+//! The TUI can also listen with `--control <addr>`, for the few routes that drive its screen
+//! (camera tours) rather than read the shared state.
+
+use anyhow::Result;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use tokio::net::TcpListener;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::daemon::shutdown_signal;
+use solar_rs::events::{EventKind, OrbitEvent};
+use crate::share;
+use solar_rs::config::ConfigPaths;
+use solar_rs::tours::TourRequest;
+use solar_rs::types::{AlertLevel, DataState, Vec3};
+
+type Shared = Arc<Mutex<DataState>>;
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "solar-rs",
+        description = "Heliocentric state vectors from JPL Horizons.",
+        license(name = "MIT OR Apache-2.0")
+    ),
+    paths(healthz, readyz, positions_v1, events_v1, tours_v1, play_tour_v1, stop_tour_v1),
+    components(schemas(PositionsV1, BodyPositionV1, EventsV1, OrbitEvent, EventKind, Vec3, ToursV1))
+)]
+struct ApiDoc;
+
+/// Current state vectors, heliocentric ecliptic J2000.
+#[derive(Serialize, ToSchema)]
+struct PositionsV1 {
+    /// Always 1 for this response type.
+    schema_version: u32,
+    /// UTC time of the last successful refresh.
+    last_update_utc: Option<String>,
+    status: String,
+    bodies: Vec<BodyPositionV1>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct BodyPositionV1 {
+    name: &'static str,
+    /// Horizons COMMAND id.
+    id: String,
+    /// Position in AU; null until first fetched.
+    pos_au: Option<Vec3>,
+    /// Velocity in AU/day; null until first fetched.
+    vel_au_d: Option<Vec3>,
+}
+
+/// Upcoming events predicted from the current vectors, soonest first.
+#[derive(Serialize, ToSchema)]
+struct EventsV1 {
+    /// Always 1 for this response type.
+    schema_version: u32,
+    events: Vec<OrbitEvent>,
+}
+
+/// Camera tours on a TUI started with `--control`.
+#[derive(Serialize, ToSchema)]
+struct ToursV1 {
+    /// Always 1 for this response type.
+    schema_version: u32,
+    /// Tour names, from `<config dir>/tours/<name>.toml`.
+    tours: Vec<String>,
+    /// The tour on screen, if any.
+    playing: Option<String>,
+}
+
+#[derive(Clone)]
+struct Control {
+    state: Shared,
+    paths: Option<ConfigPaths>,
+}
+
+pub async fn run(state: Shared, listen: &str) -> Result<()> {
+    share::start(state.clone()).await;
+
+    let app = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .route("/api/v1/positions", get(positions_v1))
+        .route("/api/v1/events", get(events_v1))
+        .route("/openapi.json", get(|| async { Json(ApiDoc::openapi()) }))
+        .with_state(state);
+
+    let listener = TcpListener::bind(listen).await?;
+    eprintln!("solar-rs serve: listening on {}", listener.local_addr()?);
+    axum::serve(listener, app).with_graceful_shutdown(shutdown_signal()).await?;
+    eprintln!("solar-rs serve: shut down");
+    Ok(())
+}
+
+/// Liveness: the process is up and serving.
+#[utoipa::path(get, path = "/healthz", responses((status = 200, description = "Process is up", body = String)))]
+async fn healthz() -> &'static str {
+    "ok"
+}
+
+/// Readiness: at least one refresh has produced positions.
+#[utoipa::path(
+    get,
+    path = "/readyz",
+    responses(
+        (status = 200, description = "Positions available", body = String),
+        (status = 503, description = "Waiting for the first refresh", body = String)
+    )
+)]
+async fn readyz(State(state): State<Shared>) -> (StatusCode, &'static str) {
+    let s = state.lock().unwrap();
+    let has_data = s.last_update_utc.is_some() && s.bodies.iter().any(|b| b.pos_au.is_some());
+    if has_data {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "waiting for first refresh")
+    }
+}
+
+#[utoipa::path(get, path = "/api/v1/positions", responses((status = 200, description = "Current state vectors", body = PositionsV1)))]
+async fn positions_v1(State(state): State<Shared>) -> Json<PositionsV1> {
+    let s = state.lock().unwrap();
+    Json(PositionsV1 {
+        schema_version: 1,
+        last_update_utc: s.last_update_utc.clone(),
+        status: s.status.clone(),
+        bodies: s
+            .bodies
+            .iter()
+            .map(|b| BodyPositionV1 { name: b.name, id: b.id.to_string(), pos_au: b.pos_au, vel_au_d: b.vel_au_d })
+            .collect(),
+    })
+}
+
+#[utoipa::path(get, path = "/api/v1/events", responses((status = 200, description = "Upcoming events", body = EventsV1)))]
+async fn events_v1(State(state): State<Shared>) -> Json<EventsV1> {
+    let s = state.lock().unwrap();
+    Json(EventsV1 { schema_version: 1, events: s.events.clone() })
+}
+
+/// `--control <addr>`: tour routes served from the TUI itself, for kiosks driven from elsewhere.
+/// Failing to listen is an alert rather than fatal.
+pub fn start_control(state: Shared, paths: Option<ConfigPaths>, listen: String) {
+    tokio::spawn(async move {
+        if let Err(e) = control(state.clone(), paths, &listen).await {
+            state.lock().unwrap().notify(AlertLevel::Warning, format!("Control API on {}: {:#}", listen, e));
+        }
+    });
+}
+
+async fn control(state: Shared, paths: Option<ConfigPaths>, listen: &str) -> Result<()> {
+    let app = Router::new()
+        .route("/api/v1/tours", get(tours_v1))
+        .route("/api/v1/tours/stop", post(stop_tour_v1))
+        .route("/api/v1/tours/{name}/play", post(play_tour_v1))
+        .with_state(Control { state, paths });
+    let listener = TcpListener::bind(listen).await?;
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+#[utoipa::path(get, path = "/api/v1/tours", responses((status = 200, description = "Available tours and the one playing (TUI `--control` only)", body = ToursV1)))]
+async fn tours_v1(State(c): State<Control>) -> Json<ToursV1> {
+    let tours = c.paths.as_ref().map(|p| p.list_tours()).unwrap_or_default();
+    Json(ToursV1 { schema_version: 1, tours, playing: c.state.lock().unwrap().tour_playing.clone() })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/v1/tours/{name}/play",
+    params(("name" = String, Path, description = "Tour name")),
+    responses(
+        (status = 202, description = "Starts on the next frame (TUI `--control` only)", body = String),
+        (status = 404, description = "No such tour", body = String)
+    )
+)]
+async fn play_tour_v1(State(c): State<Control>, Path(name): Path<String>) -> (StatusCode, &'static str) {
+    // Only listed names, so the path can't reach outside the tours directory
+    if !c.paths.as_ref().is_some_and(|p| p.list_tours().contains(&name)) {
+        return (StatusCode::NOT_FOUND, "no such tour");
+    }
+    c.state.lock().unwrap().tour_request = Some(TourRequest::Play(name));
+    (StatusCode::ACCEPTED, "playing")
+}
+
+#[utoipa::path(post, path = "/api/v1/tours/stop", responses((status = 202, description = "Stops on the next frame (TUI `--control` only)", body = String)))]
+async fn stop_tour_v1(State(c): State<Control>) -> (StatusCode, &'static str) {
+    c.state.lock().unwrap().tour_request = Some(TourRequest::Stop);
+    (StatusCode::ACCEPTED, "stopping")
+}
@@ -0,0 +1,170 @@
+//! Read-only data sharing between instances on one machine.
+//!
+//! The first instance becomes the primary: it runs the updater and publishes every refresh as
+//! a JSON line on a per-user Unix socket. Later instances find the socket and follow it instead
+//! of hitting the Horizons API themselves; if the primary exits, a follower takes over.
+//!
+//! The primary also persists each refresh to a snapshot store in the cache directory, so new
+//! instances have positions to show before the first refresh arrives.
+
+use anyhow::Result;
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::{UnixListener, UnixStream},
+    time::sleep,
+};
+
+use solar_rs::horizons::updater;
+use solar_rs::snapshot::Snapshot;
+use solar_rs::types::{AlertLevel, DataState};
+
+pub fn socket_path() -> PathBuf {
+    dirs::runtime_dir()
+        .or_else(dirs::cache_dir)
+        .unwrap_or_else(std::env::temp_dir)
+        .join("solar-rs.sock")
+}
+
+pub fn store_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join("solar-rs").join("latest.json"))
+}
+
+fn save_store(line: &str) -> Result<()> {
+    let Some(path) = store_path() else { return Ok(()) };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    // Write-then-rename so readers never see a half-written file.
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, line)?;
+    std::fs::rename(tmp, path)?;
+    Ok(())
+}
+
+/// Seeds the state from the last persisted snapshot, if any.
+pub fn load_store(state: &mut DataState) {
+    let Some(path) = store_path() else { return };
+    let Ok(text) = std::fs::read_to_string(path) else { return };
+    let Ok(snap) = serde_json::from_str::<Snapshot>(&text) else { return };
+    let stamp = snap.last_update_utc.clone().unwrap_or_default();
+    snap.apply(state);
+    state.status = format!("Cached snapshot from {}", stamp);
+}
+
+/// Follows a running primary if there is one, otherwise becomes the primary.
+pub async fn start(state: Arc<Mutex<DataState>>) {
+    load_store(&mut state.lock().unwrap());
+    let path = socket_path();
+    match UnixStream::connect(&path).await {
+        Ok(stream) => {
+            tokio::spawn(follow(state, stream, path));
+        }
+        Err(_) => become_primary(state, path),
+    }
+}
+
+pub fn become_primary(state: Arc<Mutex<DataState>>, path: PathBuf) {
+    tokio::spawn(updater(state.clone()));
+
+    // A leftover socket from a crashed primary refuses connections; replace it.
+    let _ = std::fs::remove_file(&path);
+    match UnixListener::bind(&path) {
+        Ok(listener) => {
+            tokio::spawn(publish(state, listener));
+        }
+        Err(e) => state.lock().unwrap().notify(AlertLevel::Warning, format!("Sharing disabled: {}", e)),
+    }
+}
+
+/// Accepts followers and sends them a snapshot whenever the updater completes a refresh.
+async fn publish(state: Arc<Mutex<DataState>>, listener: UnixListener) {
+    let clients: Arc<tokio::sync::Mutex<Vec<UnixStream>>> = Arc::default();
+
+    let accepting = clients.clone();
+    let initial = state.clone();
+    tokio::spawn(async move {
+        while let Ok((mut stream, _)) = listener.accept().await {
+            // Bring new followers up to date immediately rather than at the next refresh.
+            let line = snapshot_line(&initial.lock().unwrap());
+            if stream.write_all(line.as_bytes()).await.is_ok() {
+                accepting.lock().await.push(stream);
+            }
+        }
+    });
+
+    let mut last_sent: Option<String> = None;
+    loop {
+        sleep(Duration::from_millis(250)).await;
+        let (stamp, line) = {
+            let s = state.lock().unwrap();
+            (s.last_update_utc.clone(), snapshot_line(&s))
+        };
+        if stamp.is_none() || stamp == last_sent {
+            continue;
+        }
+        last_sent = stamp;
+
+        if let Err(e) = save_store(&line) {
+            state.lock().unwrap().notify(AlertLevel::Warning, format!("Snapshot store write failed: {}", e));
+        }
+
+        let mut clients = clients.lock().await;
+        let mut alive = Vec::with_capacity(clients.len());
+        for mut c in clients.drain(..) {
+            if c.write_all(line.as_bytes()).await.is_ok() {
+                alive.push(c);
+            }
+        }
+        *clients = alive;
+    }
+}
+
+fn snapshot_line(s: &DataState) -> String {
+    let mut line = serde_json::to_string(&Snapshot::of(s)).unwrap_or_default();
+    line.push('\n');
+    line
+}
+
+fn apply(state: &mut DataState, snap: Snapshot) {
+    snap.apply(state);
+    state.status = format!("{} (shared)", snap.status);
+}
+
+async fn follow(state: Arc<Mutex<DataState>>, stream: UnixStream, path: PathBuf) {
+    state.lock().unwrap().status = "Following primary instance…".into();
+
+    if let Err(e) = read_snapshots(&state, stream).await {
+        state.lock().unwrap().notify(AlertLevel::Warning, format!("Primary connection lost: {}", e));
+    }
+
+    // Primary went away: whoever reconnects first keeps following, otherwise take over.
+    sleep(Duration::from_millis(takeover_delay_ms())).await;
+    match UnixStream::connect(&path).await {
+        Ok(stream) => {
+            Box::pin(follow(state, stream, path)).await;
+        }
+        Err(_) => {
+            state.lock().unwrap().status = "Primary gone, fetching directly".into();
+            become_primary(state, path);
+        }
+    }
+}
+
+async fn read_snapshots(state: &Arc<Mutex<DataState>>, stream: UnixStream) -> Result<()> {
+    let mut lines = BufReader::new(stream).lines();
+    while let Some(line) = lines.next_line().await? {
+        let snap: Snapshot = serde_json::from_str(&line)?;
+        apply(&mut state.lock().unwrap(), snap);
+    }
+    Ok(())
+}
+
+/// Spreads out takeover attempts so several followers don't all race to bind at once.
+fn takeover_delay_ms() -> u64 {
+    100 + u64::from(std::process::id() % 400)
+}
@@ -0,0 +1,66 @@
+//! A refresh as JSON: each body's state vectors and where they came from. Instances on one
+//! machine share refreshes in this form and keep the latest in the cache directory, and
+//! `solar-rs render-frame --state` reads it as a fixture.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Instant;
+
+use crate::sources::Provenance;
+use crate::types::{DataState, Vec3};
+use crate::{events, trails};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub last_update_utc: Option<String>,
+    pub status: String,
+    pub bodies: Vec<SnapshotBody>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotBody {
+    /// Horizons COMMAND id, as in `BodyId`'s display.
+    pub id: String,
+    pub pos_au: Option<Vec3>,
+    pub vel_au_d: Option<Vec3>,
+    #[serde(default)]
+    pub source: Option<Provenance>,
+}
+
+impl Snapshot {
+    pub fn of(s: &DataState) -> Self {
+        Snapshot {
+            last_update_utc: s.last_update_utc.clone(),
+            status: s.status.clone(),
+            bodies: s
+                .bodies
+                .iter()
+                .map(|b| SnapshotBody { id: b.id.to_string(), pos_au: b.pos_au, vel_au_d: b.vel_au_d, source: b.source })
+                .collect(),
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("parse {}", path.display()))
+    }
+
+    /// Puts the vectors into `state`, where bodies the snapshot lacks keep theirs, and brings
+    /// the events and trails up to date. The status line is left to the caller.
+    pub fn apply(&self, state: &mut DataState) {
+        for b in &mut state.bodies {
+            if let Some(sb) = self.bodies.iter().find(|sb| sb.id == b.id.to_string()) {
+                b.pos_au = sb.pos_au.or(b.pos_au);
+                b.vel_au_d = sb.vel_au_d.or(b.vel_au_d);
+                b.source = sb.source.or(b.source);
+                if sb.source.is_some_and(|p| !p.source.is_fallback()) {
+                    b.updated = Some(Instant::now());
+                }
+            }
+        }
+        state.last_update_utc = self.last_update_utc.clone();
+        events::refresh(state);
+        trails::record(state);
+    }
+}
@@ -0,0 +1,152 @@
+//! Where each body's state vectors come from. Every refresh the updater walks the configured
+//! chain per body and keeps the first source that answers, so one failed Horizons query
+//! degrades a single body instead of leaving it blank.
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::orbital_mechanics::propagate;
+use crate::physics::{AU_KM, GM_SUN_AU3_D2, SECONDS_PER_DAY};
+use crate::spk::Spk;
+use crate::types::{BodyId, Vec3};
+use crate::{ephemeris, vsop87};
+
+/// Cached vectors older than this are dropped rather than propagated; two-body drift for the
+/// inner planets reaches about a degree within a few months.
+const MAX_CACHE_AGE_DAYS: f64 = 30.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Source {
+    /// Live vectors from JPL Horizons.
+    Horizons,
+    /// The body's last good vectors, propagated two-body to the refresh time.
+    Cache,
+    /// Built-in mean orbital elements (planets and Pluto), see [`crate::ephemeris`].
+    Keplerian,
+    /// The VSOP87 planetary theory evaluated locally (planets only), see [`crate::vsop87`].
+    Vsop87,
+    /// A local JPL SPK kernel given with `--spk`, see [`crate::spk`].
+    Spk,
+}
+
+impl Source {
+    /// Parses a source by its config name, e.g. `vsop87`.
+    pub fn parse(name: &str) -> Result<Source> {
+        Source::deserialize(serde::de::value::StrDeserializer::<serde::de::value::Error>::new(name))
+            .map_err(|_| anyhow!("unknown source {:?} (horizons, cache, keplerian, vsop87 or spk)", name))
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Source::Horizons => "Horizons",
+            Source::Cache => "cache",
+            Source::Keplerian => "mean elements",
+            Source::Vsop87 => "VSOP87",
+            Source::Spk => "SPK",
+        }
+    }
+
+    /// Answers from local data alone, needing neither the network nor earlier vectors.
+    pub fn is_local(self) -> bool {
+        matches!(self, Source::Keplerian | Source::Vsop87 | Source::Spk)
+    }
+
+    /// Stand-in data that doesn't count as a fresh update.
+    pub fn is_fallback(self) -> bool {
+        matches!(self, Source::Cache | Source::Keplerian)
+    }
+}
+
+/// Horizons, then a kernel given with `--spk` (passed over without one), then VSOP87 for the
+/// planets, then the cache, with the mean elements last.
+pub fn default_chain() -> Vec<Source> {
+    if cfg!(feature = "net") {
+        vec![Source::Horizons, Source::Spk, Source::Vsop87, Source::Cache, Source::Keplerian]
+    } else {
+        vec![Source::Spk, Source::Vsop87, Source::Keplerian]
+    }
+}
+
+/// Which source satisfied a body's latest refresh, and the epoch of the data it started from.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    pub source: Source,
+    pub epoch: DateTime<Utc>,
+}
+
+/// Last good vectors of a body as of the previous refresh.
+#[derive(Debug, Clone, Copy)]
+pub struct Cached {
+    pub pos: Vec3,
+    pub vel: Vec3,
+    /// Time the vectors refer to.
+    pub at: DateTime<Utc>,
+    /// Where they originally came from.
+    pub provenance: Provenance,
+}
+
+/// A local source's heliocentric state of a body at `utc` (see [`Source::is_local`]).
+pub fn local_state(source: Source, name: &str, id: BodyId, spk: Option<&Spk>, utc: DateTime<Utc>) -> Result<(Vec3, Vec3)> {
+    match source {
+        Source::Keplerian => ephemeris::state_at(name, utc).ok_or_else(|| anyhow!("no mean elements")),
+        Source::Vsop87 => vsop87::state_at(name, utc).ok_or_else(|| anyhow!("not a VSOP87 planet")),
+        Source::Spk => match spk {
+            Some(kernel) => kernel.state_at(id, utc),
+            None => Err(anyhow!("no SPK kernel loaded (--spk)")),
+        },
+        Source::Horizons | Source::Cache => Err(anyhow!("{} is not a local source", source.label())),
+    }
+}
+
+/// How far one source's state of a body lies from another's.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Divergence {
+    pub pos_km: f64,
+    /// Difference in distance from the Sun.
+    pub radial_km: f64,
+    /// Angle between the two positions as seen from the Sun.
+    pub angle_arcsec: f64,
+    pub vel_m_s: f64,
+}
+
+pub fn divergence((p, v): (Vec3, Vec3), (q, w): (Vec3, Vec3)) -> Divergence {
+    let cos = (p.dot(q) / (p.norm() * q.norm())).clamp(-1.0, 1.0);
+    Divergence {
+        pos_km: (p - q).norm() * AU_KM,
+        radial_km: (p.norm() - q.norm()) * AU_KM,
+        angle_arcsec: cos.acos().to_degrees() * 3600.0,
+        vel_m_s: (v - w).norm() * AU_KM * 1000.0 / SECONDS_PER_DAY,
+    }
+}
+
+/// The cached vectors propagated to `now`, keeping the epoch of the original data.
+pub fn from_cache(cached: Option<Cached>, now: DateTime<Utc>) -> Result<(Vec3, Vec3, DateTime<Utc>)> {
+    let c = cached.ok_or_else(|| anyhow!("nothing cached yet"))?;
+    let age_days = (now - c.provenance.epoch).num_seconds() as f64 / 86_400.0;
+    // Either way: after a time-travel jump the cache is decades away from the epoch asked for
+    if age_days.abs() > MAX_CACHE_AGE_DAYS {
+        return Err(anyhow!("cached vectors are {:.0} days old", age_days));
+    }
+    let dt = (now - c.at).num_seconds() as f64 / 86_400.0;
+    let (p, v) = propagate(c.pos, c.vel, dt, GM_SUN_AU3_D2).ok_or_else(|| anyhow!("propagation failed"))?;
+    Ok((p, v, c.provenance.epoch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn divergence_of_shifted_state() {
+        let p = Vec3 { x: 1.0, y: 0.0, z: 0.0 };
+        let v = Vec3 { x: 0.0, y: 0.0172, z: 0.0 };
+        let q = Vec3 { x: 1.0, y: 1e-6, z: 0.0 };
+        let d = divergence((p, v), (q, v));
+        assert!((d.pos_km - 1e-6 * AU_KM).abs() < 1e-3);
+        assert!(d.radial_km.abs() < 1e-3 && d.vel_m_s == 0.0);
+        // 1e-6 rad
+        assert!((d.angle_arcsec - 0.206_265).abs() < 1e-4);
+    }
+}
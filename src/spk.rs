@@ -0,0 +1,309 @@
+//! Reader for JPL SPICE SPK kernels (e.g. DE440's `de440.bsp`), so a local planetary ephemeris
+//! can stand in for Horizons with the same precision. Only what the JPL planetary kernels use
+//! is supported: DAF files with type 2 (Chebyshev position) and type 3 (Chebyshev position
+//! and velocity) segments in the J2000/ICRF frame. Records are read from disk on demand, so
+//! a 100 MB kernel costs only its segment table in memory.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Utc};
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+    sync::Mutex,
+};
+
+use crate::frames::equatorial_to_ecliptic;
+use crate::physics::{days_since_j2000, AU_KM, SECONDS_PER_DAY};
+use crate::types::{BodyId, Vec3};
+
+const RECORD_BYTES: u64 = 1024;
+/// NAIF ids of the solar-system barycentre and the Sun.
+const SSB: i32 = 0;
+const SUN: i32 = 10;
+/// SPK frame code for J2000 (ICRF).
+const J2000_FRAME: i32 = 1;
+/// Segment summaries that fit in one 128-word summary record with ND=2, NI=6.
+const MAX_SUMMARIES: f64 = 25.0;
+
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    target: i32,
+    center: i32,
+    kind: i32,
+    start_et: f64,
+    end_et: f64,
+    /// First and last double of the segment, 1-based word addresses.
+    start: u64,
+    end: u64,
+}
+
+#[derive(Debug)]
+pub struct Spk {
+    file: Mutex<File>,
+    big_endian: bool,
+    segments: Vec<Segment>,
+}
+
+impl Spk {
+    pub fn open(path: &Path) -> Result<Spk> {
+        let mut file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+        let mut record = [0u8; RECORD_BYTES as usize];
+        file.read_exact(&mut record).with_context(|| format!("read {}", path.display()))?;
+        if &record[..7] != b"DAF/SPK" {
+            return Err(anyhow!("{} is not an SPK file", path.display()));
+        }
+        let big_endian = match &record[88..96] {
+            b"BIG-IEEE" => true,
+            b"LTL-IEEE" => false,
+            _ => cfg!(target_endian = "big"), // pre-1990s files have no format marker
+        };
+        let mut spk = Spk { file: Mutex::new(file), big_endian, segments: Vec::new() };
+
+        let int = |b: &[u8]| {
+            let b: [u8; 4] = b.try_into().unwrap();
+            if big_endian { i32::from_be_bytes(b) } else { i32::from_le_bytes(b) }
+        };
+        let (nd, ni, mut next) = (int(&record[8..12]), int(&record[12..16]), int(&record[76..80]));
+        if (nd, ni) != (2, 6) {
+            return Err(anyhow!("{}: unexpected DAF summary layout ND={} NI={}", path.display(), nd, ni));
+        }
+
+        // Linked list of summary records: next, previous, count, then 5 words per segment
+        let mut visited = HashSet::new();
+        while next > 0 {
+            if !visited.insert(next) {
+                return Err(anyhow!("{}: summary records form a loop at record {}", path.display(), next));
+            }
+            let words = spk.read_words((next as u64 - 1) * 128 + 1, 128)?;
+            let count = words[2];
+            if !(0.0..=MAX_SUMMARIES).contains(&count) {
+                return Err(anyhow!("{}: bad summary count {} in record {}", path.display(), count, next));
+            }
+            for i in 0..count as usize {
+                let s = &words[3 + i * 5..8 + i * 5];
+                let ints: Vec<i32> = s[2..5]
+                    .iter()
+                    .flat_map(|w| {
+                        let b = if big_endian { w.to_be_bytes() } else { w.to_le_bytes() };
+                        [int(&b[..4]), int(&b[4..])]
+                    })
+                    .collect();
+                spk.segments.push(Segment {
+                    target: ints[0],
+                    center: ints[1],
+                    kind: ints[3],
+                    start_et: s[0],
+                    end_et: s[1],
+                    start: ints[4] as u64,
+                    end: ints[5] as u64,
+                });
+                if ints[2] != J2000_FRAME {
+                    return Err(anyhow!("{}: segment for {} is not in the J2000 frame", path.display(), ints[0]));
+                }
+                // Room for at least the 4-word directory at the end
+                if ints[4] < 1 || ints[5] < ints[4] + 3 {
+                    return Err(anyhow!("{}: segment for {} has bad addresses {}..{}", path.display(), ints[0], ints[4], ints[5]));
+                }
+            }
+            next = words[0] as i32;
+        }
+        if spk.segments.is_empty() {
+            return Err(anyhow!("{} has no segments", path.display()));
+        }
+        Ok(spk)
+    }
+
+    /// `count` doubles starting at 1-based word address `addr`.
+    fn read_words(&self, addr: u64, count: usize) -> Result<Vec<f64>> {
+        let mut bytes = vec![0u8; count * 8];
+        let mut file = self.file.lock().unwrap();
+        file.seek(SeekFrom::Start((addr - 1) * 8))?;
+        file.read_exact(&mut bytes)?;
+        Ok(bytes
+            .chunks_exact(8)
+            .map(|c| {
+                let b: [u8; 8] = c.try_into().unwrap();
+                if self.big_endian { f64::from_be_bytes(b) } else { f64::from_le_bytes(b) }
+            })
+            .collect())
+    }
+
+    /// Position (km) and velocity (km/s) of `target` relative to its segment's centre at `et`
+    /// (TDB seconds past J2000), and that centre.
+    fn segment_state(&self, target: i32, et: f64) -> Result<(i32, [f64; 6])> {
+        let seg = self
+            .segments
+            .iter()
+            .rev() // later segments take precedence
+            .find(|s| s.target == target && (s.start_et..=s.end_et).contains(&et))
+            .ok_or_else(|| anyhow!("no SPK segment for body {} at this epoch", target))?;
+        if !matches!(seg.kind, 2 | 3) {
+            return Err(anyhow!("SPK segment type {} for body {} is not supported", seg.kind, target));
+        }
+
+        let dir = self.read_words(seg.end - 3, 4)?; // init, interval length, record size, count
+        let (init, intlen, rsize, n) = (dir[0], dir[1], dir[2] as usize, dir[3] as u64);
+        let sets = if seg.kind == 2 { 3 } else { 6 };
+        // Records of a midpoint, a radius and at least one coefficient per set, all within
+        // the segment ahead of its directory
+        let room = seg.end - 3 - seg.start;
+        if n < 1 || rsize < 2 + sets || (rsize as u64).checked_mul(n).is_none_or(|words| words > room) {
+            return Err(anyhow!("SPK segment for body {} has a bad directory: {} records of {} words", target, n, rsize));
+        }
+        let index = (((et - init) / intlen).floor().max(0.0) as u64).min(n - 1);
+        let rec = self.read_words(seg.start + index * rsize as u64, rsize)?;
+        let (mid, radius) = (rec[0], rec[1]);
+        let ncoef = (rsize - 2) / sets;
+        let t = (et - mid) / radius;
+
+        let mut state = [0.0; 6];
+        for axis in 0..3 {
+            let coef = &rec[2 + axis * ncoef..2 + (axis + 1) * ncoef];
+            let (p, dp) = chebyshev(coef, t);
+            state[axis] = p;
+            state[axis + 3] = dp / radius;
+        }
+        if seg.kind == 3 {
+            for axis in 0..3 {
+                let coef = &rec[2 + (axis + 3) * ncoef..2 + (axis + 4) * ncoef];
+                state[axis + 3] = chebyshev(coef, t).0;
+            }
+        }
+        Ok((seg.center, state))
+    }
+
+    /// State of `target` relative to the solar-system barycentre, following centres down.
+    fn barycentric(&self, mut target: i32, et: f64) -> Result<[f64; 6]> {
+        let mut sum = [0.0; 6];
+        while target != SSB {
+            let (center, s) = self.segment_state(target, et)?;
+            sum.iter_mut().zip(s).for_each(|(a, b)| *a += b);
+            target = center;
+        }
+        Ok(sum)
+    }
+
+    /// Heliocentric J2000 ecliptic position (AU) and velocity (AU/day) of the body with the
+    /// given id. Planet centres missing from the kernel, such as Mars in DE440, fall back to
+    /// their system barycentre, at most a few hundred km away.
+    pub fn state_at(&self, id: BodyId, utc: DateTime<Utc>) -> Result<(Vec3, Vec3)> {
+        let naif = id.0;
+        let et = days_since_j2000(utc) * SECONDS_PER_DAY;
+        let has = |t: i32| self.segments.iter().any(|s| s.target == t);
+        let target = if !has(naif) && naif % 100 == 99 && has(naif / 100) { naif / 100 } else { naif };
+
+        let mut d = self.barycentric(target, et)?;
+        let sun = self.barycentric(SUN, et)?;
+        d.iter_mut().zip(sun).for_each(|(b, s)| *b -= s);
+        Ok(ecliptic_au(d))
+    }
+
+    /// The Sun's J2000 ecliptic position (AU) and velocity (AU/day) about the solar-system
+    /// barycentre.
+    pub fn sun_barycentric(&self, utc: DateTime<Utc>) -> Result<(Vec3, Vec3)> {
+        let et = days_since_j2000(utc) * SECONDS_PER_DAY;
+        Ok(ecliptic_au(self.barycentric(SUN, et)?))
+    }
+}
+
+/// An equatorial state in km and km/s as ecliptic AU and AU/day.
+fn ecliptic_au(d: [f64; 6]) -> (Vec3, Vec3) {
+    let p = Vec3 { x: d[0], y: d[1], z: d[2] } * (1.0 / AU_KM);
+    let v = Vec3 { x: d[3], y: d[4], z: d[5] } * (SECONDS_PER_DAY / AU_KM);
+    (equatorial_to_ecliptic(p), equatorial_to_ecliptic(v))
+}
+
+/// Chebyshev series Σ cₖ Tₖ(t) and its derivative with respect to `t`.
+fn chebyshev(coef: &[f64], t: f64) -> (f64, f64) {
+    let (mut t0, mut t1) = (1.0, t);
+    let (mut d0, mut d1) = (0.0, 1.0);
+    let mut p = coef[0] + coef.get(1).map_or(0.0, |c| c * t);
+    let mut dp = coef.get(1).copied().unwrap_or(0.0);
+    for c in coef.iter().skip(2) {
+        let t2 = 2.0 * t * t1 - t0;
+        let d2 = 2.0 * t1 + 2.0 * t * d1 - d0;
+        p += c * t2;
+        dp += c * d2;
+        (t0, t1, d0, d1) = (t1, t2, d1, d2);
+    }
+    (p, dp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// A one-segment kernel: body 399 about the Sun on a straight line x = 1 AU + v·t.
+    fn kernel() -> Vec<u8> {
+        let le = |v: f64| v.to_le_bytes();
+        let mut file = vec![0u8; 3 * RECORD_BYTES as usize];
+        file[..8].copy_from_slice(b"DAF/SPK ");
+        file[8..12].copy_from_slice(&2i32.to_le_bytes());
+        file[12..16].copy_from_slice(&6i32.to_le_bytes());
+        file[76..80].copy_from_slice(&2i32.to_le_bytes()); // first summary record
+        file[88..96].copy_from_slice(b"LTL-IEEE");
+
+        // Segment data from word 385 (record 4): one type 2 record of degree 1 per axis
+        let (mid, radius, v_km_s) = (0.0, 86_400.0, 30.0);
+        let data = [mid, radius, AU_KM, v_km_s * radius, 0.0, 0.0, 0.0, 0.0, -radius, 86_400.0 * 2.0, 8.0, 1.0];
+        let (start, end) = (385i32, 385 + data.len() as i32 - 1);
+
+        let summary = &mut file[RECORD_BYTES as usize..];
+        summary[..8].copy_from_slice(&le(0.0));
+        summary[16..24].copy_from_slice(&le(1.0));
+        summary[24..32].copy_from_slice(&le(-radius));
+        summary[32..40].copy_from_slice(&le(radius));
+        for (i, v) in [399, 10, 1, 2, start, end].iter().enumerate() {
+            summary[40 + i * 4..44 + i * 4].copy_from_slice(&v.to_le_bytes());
+        }
+        for v in data {
+            file.extend_from_slice(&le(v));
+        }
+        file
+    }
+
+    fn open(bytes: &[u8], name: &str) -> Result<Spk> {
+        let path = std::env::temp_dir().join(format!("solar-rs-test-{}-{}.bsp", name, std::process::id()));
+        std::fs::File::create(&path).unwrap().write_all(bytes).unwrap();
+        let spk = Spk::open(&path);
+        std::fs::remove_file(path).ok();
+        spk
+    }
+
+    #[test]
+    fn reads_type_2_segment() {
+        let spk = open(&kernel(), "good").unwrap();
+        let (center, s) = spk.segment_state(399, 43_200.0).unwrap();
+        assert_eq!(center, 10);
+        assert!((s[0] - (AU_KM + 30.0 * 43_200.0)).abs() < 1e-6, "x = {}", s[0]);
+        assert!((s[3] - 30.0).abs() < 1e-9, "vx = {}", s[3]);
+        assert!(spk.segment_state(499, 0.0).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_kernels() {
+        let summary = RECORD_BYTES as usize;
+        let data = 3 * RECORD_BYTES as usize;
+        let with = |at: usize, bytes: &[u8]| {
+            let mut k = kernel();
+            k[at..at + bytes.len()].copy_from_slice(bytes);
+            k
+        };
+        // A summary count past what fits in a record, a record linking back to itself, and
+        // segment addresses that don't leave room for the directory
+        assert!(open(&with(summary + 16, &26.0f64.to_le_bytes()), "count").is_err());
+        assert!(open(&with(summary, &2.0f64.to_le_bytes()), "loop").is_err());
+        assert!(open(&with(summary + 56, &0i32.to_le_bytes()), "start").is_err());
+        assert!(open(&with(summary + 60, &386i32.to_le_bytes()), "end").is_err());
+
+        // Directories with no records, records too short for a coefficient per axis, and
+        // more records than the segment holds
+        for (word, value, name) in [(11, 0.0, "n"), (10, 4.0, "rsize"), (11, 2.0, "overrun"), (10, f64::NAN, "nan")] {
+            let spk = open(&with(data + word * 8, &value.to_le_bytes()), name).unwrap();
+            assert!(spk.segment_state(399, 43_200.0).is_err(), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,37 @@
+//! Keeps a running Stellarium in step with the TUI through its Remote Control HTTP API:
+//! the selected body becomes Stellarium's centred object, at the epoch of the shown vectors.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+use solar_rs::config::Stellarium;
+
+/// Julian Date (UT) of `utc`, as Stellarium's `/api/main/time` expects.
+fn julian_date(utc: DateTime<Utc>) -> f64 {
+    utc.timestamp_millis() as f64 / 86_400_000.0 + 2_440_587.5
+}
+
+pub async fn sync(stellarium: &Stellarium, body: &str, utc: DateTime<Utc>) -> Result<()> {
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(3)).build()?;
+    let base = format!("http://{}/api/main", stellarium.address);
+    let post = |path: &str, form: Vec<(&str, String)>| {
+        let req = client.post(format!("{}/{}", base, path)).form(&form);
+        match &stellarium.password {
+            Some(pw) => req.basic_auth("", Some(pw)),
+            None => req,
+        }
+    };
+
+    // Stop the clock at our epoch, then centre the body.
+    post("time", vec![("time", format!("{:.8}", julian_date(utc))), ("timerate", "0".into())])
+        .send()
+        .await
+        .context("reach Stellarium")?
+        .error_for_status()?;
+    post("focus", vec![("target", body.to_string()), ("mode", "center".into())])
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
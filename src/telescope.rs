@@ -0,0 +1,98 @@
+//! GoTo commands for a telescope mount over ASCOM Alpaca or INDI.
+//!
+//! Both expect coordinates referred to the equinox of date (INDI's `EQUATORIAL_EOD_COORD`,
+//! and Alpaca mounts reporting `EquatorialSystem` = topocentric), so callers precess from J2000.
+
+use anyhow::{anyhow, Context, Result};
+use std::time::Duration;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    time::timeout,
+};
+
+use solar_rs::config::Telescope;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Starts a slew to `ra_deg` / `dec_deg` and returns once the mount has accepted it.
+pub async fn goto(telescope: &Telescope, ra_deg: f64, dec_deg: f64) -> Result<()> {
+    match telescope {
+        Telescope::Alpaca { address, device } => alpaca_goto(address, *device, ra_deg, dec_deg).await,
+        Telescope::Indi { address, device } => indi_goto(address, device, ra_deg, dec_deg).await,
+    }
+}
+
+async fn alpaca_goto(address: &str, device: u32, ra_deg: f64, dec_deg: f64) -> Result<()> {
+    let client = reqwest::Client::builder().timeout(TIMEOUT).build()?;
+    let base = format!("http://{}/api/v1/telescope/{}", address, device);
+    let client_id = std::process::id().to_string();
+
+    let put = |action: &str, form: Vec<(&'static str, String)>| {
+        let mut form = form;
+        form.push(("ClientID", client_id.clone()));
+        client.put(format!("{}/{}", base, action)).form(&form).send()
+    };
+
+    // Alpaca reports device errors in the body with HTTP 200.
+    let check = |body: serde_json::Value, action: &str| -> Result<()> {
+        match body.get("ErrorNumber").and_then(|n| n.as_i64()) {
+            Some(0) | None => Ok(()),
+            Some(n) => Err(anyhow!(
+                "{} failed ({}): {}",
+                action,
+                n,
+                body.get("ErrorMessage").and_then(|m| m.as_str()).unwrap_or("")
+            )),
+        }
+    };
+
+    let resp = put("connected", vec![("Connected", "true".into())]).await.context("connect to Alpaca")?;
+    check(resp.error_for_status()?.json().await?, "connect")?;
+
+    let resp = put(
+        "slewtocoordinatesasync",
+        vec![("RightAscension", format!("{:.6}", ra_deg / 15.0)), ("Declination", format!("{:.6}", dec_deg))],
+    )
+    .await
+    .context("send Alpaca slew")?;
+    check(resp.error_for_status()?.json().await?, "slew")
+}
+
+async fn indi_goto(address: &str, device: &str, ra_deg: f64, dec_deg: f64) -> Result<()> {
+    let mut stream = timeout(TIMEOUT, TcpStream::connect(address))
+        .await
+        .map_err(|_| anyhow!("INDI connect timed out"))?
+        .with_context(|| format!("connect to INDI server {}", address))?;
+
+    // Ask the driver to track (not just sync) on the new coordinates, then set them.
+    let device = xml_escape(device);
+    let msg = format!(
+        concat!(
+            "<newSwitchVector device=\"{dev}\" name=\"ON_COORD_SET\">",
+            "<oneSwitch name=\"TRACK\">On</oneSwitch></newSwitchVector>\n",
+            "<newNumberVector device=\"{dev}\" name=\"EQUATORIAL_EOD_COORD\">",
+            "<oneNumber name=\"RA\">{ra:.6}</oneNumber><oneNumber name=\"DEC\">{dec:.6}</oneNumber>",
+            "</newNumberVector>\n"
+        ),
+        dev = device,
+        ra = ra_deg / 15.0,
+        dec = dec_deg
+    );
+    stream.write_all(msg.as_bytes()).await?;
+
+    // INDI has no request/response; wait briefly for the driver to echo the vector state so
+    // an alert (e.g. below the horizon limit) can be reported.
+    let mut buf = vec![0u8; 4096];
+    if let Ok(Ok(n)) = timeout(Duration::from_millis(800), stream.read(&mut buf)).await {
+        let reply = String::from_utf8_lossy(&buf[..n]);
+        if reply.contains("state=\"Alert\"") {
+            return Err(anyhow!("mount rejected the slew"));
+        }
+    }
+    Ok(())
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('"', "&quot;").replace('<', "&lt;").replace('>', "&gt;")
+}
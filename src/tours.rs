@@ -0,0 +1,312 @@
+//! Camera tours: scripted fly-throughs of the orbit map for recorded demos and kiosk loops. A
+//! tour is a list of waypoints, each a view (focus level and zoom, centre, epoch) moved to over
+//! a duration with an easing and then held. Tours are TOML files in `<config dir>/tours/`,
+//! played with `y` or `--tour <name>`, or started and stopped over the control API.
+
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use serde::Deserialize;
+use std::path::Path;
+use std::time::Instant;
+
+use crate::events::parse_epoch;
+use crate::names;
+use crate::types::{BodyState, Vec3, DEFAULT_FOCUS, FOCUS_LEVELS};
+
+/// How a move between waypoints speeds up and slows down.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    /// Eased progress through a move, for `t` from 0 to 1.
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t,
+            Easing::EaseOut => t * (2.0 - t),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Waypoint {
+    /// Seconds to move here from the previous waypoint. The first waypoint is cut to, and only
+    /// moved to when a looping tour comes round again.
+    #[serde(default = "default_duration")]
+    pub duration_secs: f64,
+    /// Seconds to stay once here.
+    #[serde(default)]
+    pub hold_secs: f64,
+    #[serde(default)]
+    pub easing: Easing,
+    /// A focus level by name ("Mars", "Neptune"…); the default level if unset.
+    pub focus: Option<String>,
+    #[serde(default = "default_zoom")]
+    pub zoom: f64,
+    /// Body the map is centred on; the Sun if unset.
+    pub centre: Option<String>,
+    /// Moves the centre off that body, AU along the ecliptic x and y.
+    #[serde(default)]
+    pub offset_au: [f64; 2],
+    /// UTC epoch to show, as typed for time travel; live time if unset.
+    pub epoch: Option<String>,
+}
+
+fn default_duration() -> f64 {
+    3.0
+}
+
+fn default_zoom() -> f64 {
+    1.0
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tour {
+    /// Start over after the last waypoint, moving back to the first.
+    #[serde(default, rename = "loop")]
+    pub repeat: bool,
+    #[serde(rename = "waypoint")]
+    pub waypoints: Vec<Waypoint>,
+}
+
+/// Reads and checks a tour file.
+pub fn load(path: &Path) -> Result<Tour> {
+    let text = std::fs::read_to_string(path).with_context(|| format!("read tour {}", path.display()))?;
+    parse(&text).with_context(|| format!("tour {}", path.display()))
+}
+
+pub fn parse(text: &str) -> Result<Tour> {
+    let tour: Tour = toml::from_str(text)?;
+    if tour.waypoints.is_empty() {
+        return Err(anyhow!("no waypoints"));
+    }
+    for (i, w) in tour.waypoints.iter().enumerate() {
+        let n = i + 1;
+        if !(w.duration_secs >= 0.0 && w.hold_secs >= 0.0 && w.duration_secs.is_finite() && w.hold_secs.is_finite()) {
+            return Err(anyhow!("waypoint {}: durations must be zero or more seconds", n));
+        }
+        if !(w.zoom > 0.0 && w.zoom.is_finite()) {
+            return Err(anyhow!("waypoint {}: zoom must be above zero", n));
+        }
+        if let Some(f) = w.focus.as_deref().filter(|f| focus_level(f).is_none()) {
+            return Err(anyhow!("waypoint {}: no focus level {:?}", n, f));
+        }
+        if let Some(e) = w.epoch.as_deref().filter(|e| parse_epoch(e).is_none()) {
+            return Err(anyhow!("waypoint {}: not a UTC time: {:?}", n, e));
+        }
+    }
+    if tour.repeat && tour.waypoints.iter().all(|w| w.duration_secs + w.hold_secs == 0.0) {
+        return Err(anyhow!("a looping tour needs some duration"));
+    }
+    Ok(tour)
+}
+
+fn focus_level(name: &str) -> Option<usize> {
+    FOCUS_LEVELS.iter().position(|(n, _)| n.eq_ignore_ascii_case(name))
+}
+
+/// The focus level nearest a map span (AU from centre to edge) and the zoom that shows it.
+pub fn focus_and_zoom(span_au: f64) -> (usize, f64) {
+    let distance = |&(_, au): &(&str, f64)| (au / span_au).ln().abs();
+    let i = (0..FOCUS_LEVELS.len()).min_by(|&a, &b| distance(&FOCUS_LEVELS[a]).total_cmp(&distance(&FOCUS_LEVELS[b]))).unwrap_or(DEFAULT_FOCUS);
+    (i, FOCUS_LEVELS[i].1 / span_au)
+}
+
+/// A waypoint with its names resolved against the bodies being shown.
+#[derive(Debug, Clone)]
+struct Stop {
+    move_secs: f64,
+    hold_secs: f64,
+    easing: Easing,
+    span_au: f64,
+    centre: Option<usize>, // index into `DataState::bodies`
+    offset: Vec3,
+    epoch: Option<DateTime<Utc>>,
+}
+
+impl Stop {
+    fn centre(&self, bodies: &[BodyState]) -> Vec3 {
+        self.centre.and_then(|i| bodies.get(i)).and_then(|b| b.pos_au).unwrap_or(Vec3::ZERO) + self.offset
+    }
+}
+
+/// A tour being played, timed from when it started.
+#[derive(Debug, Clone)]
+pub struct TourPlayer {
+    pub name: String,
+    stops: Vec<Stop>,
+    repeat: bool,
+    started: Instant,
+}
+
+/// Where a tour is at one moment: part way from one waypoint to the next.
+pub struct TourFrame<'a> {
+    from: &'a Stop,
+    to: &'a Stop,
+    t: f64, // eased
+}
+
+impl TourPlayer {
+    /// Starts `tour` at `now`; every body it centres on must be among `bodies`.
+    pub fn new(name: &str, tour: &Tour, bodies: &[BodyState], now: Instant) -> Result<Self> {
+        if tour.waypoints.is_empty() {
+            return Err(anyhow!("tour {}: no waypoints", name));
+        }
+        let stops = tour
+            .waypoints
+            .iter()
+            .map(|w| {
+                let centre = match w.centre.as_deref() {
+                    Some(q) => Some(names::find_body(bodies, q).ok_or_else(|| anyhow!("tour {}: no body named {:?} shown", name, q))?),
+                    None => None,
+                };
+                let focus = w.focus.as_deref().and_then(focus_level).unwrap_or(DEFAULT_FOCUS);
+                Ok(Stop {
+                    move_secs: w.duration_secs,
+                    hold_secs: w.hold_secs,
+                    easing: w.easing,
+                    span_au: FOCUS_LEVELS[focus].1 / w.zoom,
+                    centre,
+                    offset: Vec3 { x: w.offset_au[0], y: w.offset_au[1], z: 0.0 },
+                    epoch: w.epoch.as_deref().and_then(parse_epoch),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(TourPlayer { name: name.to_string(), stops, repeat: tour.repeat, started: now })
+    }
+
+    /// Where the tour is at `now`, or `None` once a tour that doesn't loop has ended.
+    pub fn frame(&self, now: Instant) -> Option<TourFrame<'_>> {
+        let n = self.stops.len();
+        let first = &self.stops[0];
+        let mut e = now.saturating_duration_since(self.started).as_secs_f64();
+        if e < first.hold_secs {
+            return Some(TourFrame { from: first, to: first, t: 1.0 });
+        }
+        e -= first.hold_secs;
+        let legs = |from: usize| (from..n).map(|i| (&self.stops[(i + n - 1) % n], &self.stops[i]));
+        let first_pass: f64 = self.stops[1..].iter().map(|s| s.move_secs + s.hold_secs).sum();
+        if e >= first_pass {
+            if !self.repeat {
+                return None;
+            }
+            let cycle: f64 = self.stops.iter().map(|s| s.move_secs + s.hold_secs).sum();
+            return walk(legs(0), (e - first_pass) % cycle);
+        }
+        walk(legs(1), e)
+    }
+}
+
+/// The frame `e` seconds into a run of (from, to) legs, each a move and then a hold.
+fn walk<'a>(legs: impl Iterator<Item = (&'a Stop, &'a Stop)>, mut e: f64) -> Option<TourFrame<'a>> {
+    let mut last = None;
+    for (from, to) in legs {
+        if e < to.move_secs {
+            return Some(TourFrame { from, to, t: to.easing.apply(e / to.move_secs) });
+        }
+        e -= to.move_secs;
+        if e < to.hold_secs {
+            return Some(TourFrame { from: to, to, t: 1.0 });
+        }
+        e -= to.hold_secs;
+        last = Some(to);
+    }
+    last.map(|to| TourFrame { from: to, to, t: 1.0 })
+}
+
+impl TourFrame<'_> {
+    /// The epoch to show, or `None` to stay live; a waypoint without one stands for `live`.
+    pub fn epoch(&self, live: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.from.epoch.is_none() && self.to.epoch.is_none() {
+            return None;
+        }
+        let (a, b) = (self.from.epoch.unwrap_or(live), self.to.epoch.unwrap_or(live));
+        Some(a + ChronoDuration::milliseconds(((b - a).num_milliseconds() as f64 * self.t) as i64))
+    }
+
+    /// Map span (AU from centre to edge) and centre, with `bodies` at the epoch shown. The span
+    /// changes geometrically, so a zoom across many orbits doesn't rush its start.
+    pub fn camera(&self, bodies: &[BodyState]) -> (f64, Vec3) {
+        let span = (self.from.span_au.ln() * (1.0 - self.t) + self.to.span_au.ln() * self.t).exp();
+        let (a, b) = (self.from.centre(bodies), self.to.centre(bodies));
+        (span, a + (b - a) * self.t)
+    }
+}
+
+/// A tour command from outside the input loop (the control API), picked up on the next frame.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TourRequest {
+    Play(String),
+    Stop,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const TOUR: &str = r#"
+        loop = true
+
+        [[waypoint]]
+        focus = "Jupiter"
+        hold_secs = 1
+
+        [[waypoint]]
+        duration_secs = 2
+        easing = "linear"
+        focus = "Earth"
+        zoom = 2
+        epoch = "2030-01-01"
+    "#;
+
+    #[test]
+    fn easings_run_from_zero_to_one() {
+        for e in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+            assert_eq!((e.apply(-1.0), e.apply(0.0), e.apply(1.0), e.apply(2.0)), (0.0, 0.0, 1.0, 1.0));
+        }
+        assert_eq!(Easing::EaseInOut.apply(0.5), 0.5);
+        assert!(Easing::EaseIn.apply(0.5) < 0.5 && Easing::EaseOut.apply(0.5) > 0.5);
+    }
+
+    #[test]
+    fn plays_legs_in_order_and_loops_back() {
+        let tour = parse(TOUR).unwrap();
+        let start = Instant::now();
+        let player = TourPlayer::new("demo", &tour, &[], start).unwrap();
+        let at = |secs: f64| player.frame(start + Duration::from_secs_f64(secs)).map(|f| f.camera(&[]).0);
+        let near = |secs: f64, span: f64| at(secs).is_some_and(|s| (s - span).abs() < 1e-9);
+        let jupiter = FOCUS_LEVELS[2].1;
+        assert!(near(0.5, jupiter));
+        // Half way (geometrically) from Jupiter's orbit to half of Earth's
+        assert!(near(2.0, (jupiter * 0.5).sqrt()));
+        let epoch = |secs: f64| player.frame(start + Duration::from_secs_f64(secs)).and_then(|f| f.epoch(Utc::now()));
+        assert_eq!((epoch(0.5), epoch(3.0)), (None, parse_epoch("2030-01-01")));
+        // Back round: the first waypoint's default 3 s move, then its hold
+        assert!(near(3.0 + 1.5, (jupiter * 0.5).sqrt()));
+        assert!(near(3.0 + 3.5, jupiter));
+
+        let once = TourPlayer::new("demo", &Tour { repeat: false, ..tour }, &[], start).unwrap();
+        assert!(once.frame(start + Duration::from_secs(2)).is_some());
+        assert!(once.frame(start + Duration::from_secs(4)).is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_focus_and_bodies() {
+        assert!(parse("[[waypoint]]\nfocus = \"Vulcan\"").is_err());
+        assert!(parse("loop = true\n[[waypoint]]\nduration_secs = 0").is_err());
+        let tour = parse("[[waypoint]]\ncentre = \"Mars\"").unwrap();
+        assert!(TourPlayer::new("t", &tour, &[], Instant::now()).is_err());
+    }
+}
@@ -0,0 +1,55 @@
+//! Breadcrumb trails behind the bodies on the orbit map: each body's recent positions, sampled
+//! once per `trail_step_hours` of epoch into a ring buffer on its `BodyState`.
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+
+use crate::events;
+use crate::types::{BodyState, DataState};
+
+/// Adds the positions of a refresh to the trails, when the epoch has moved on a step since a
+/// body's last sample. Call after each refresh.
+pub fn record(state: &mut DataState) {
+    let Some(epoch) = events::epoch(state) else { return };
+    let step = ChronoDuration::seconds((state.config.trail_step_hours * 3600.0) as i64);
+    record_at(&mut state.bodies, epoch, state.config.trail_length, step);
+}
+
+/// A jump back in time, or further ahead than the whole trail spans (time travel), starts a
+/// trail over.
+fn record_at(bodies: &mut [BodyState], epoch: DateTime<Utc>, len: usize, step: ChronoDuration) {
+    for b in bodies {
+        if b.trail.back().is_some_and(|&(t, _)| epoch < t || epoch - t > step * len as i32) {
+            b.trail.clear();
+        }
+        let Some(p) = b.pos_au.filter(|_| len > 0) else { continue };
+        if b.trail.back().is_none_or(|&(t, _)| epoch - t >= step) {
+            b.trail.push_back((epoch, p));
+        }
+        while b.trail.len() > len {
+            b.trail.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BodyId, Vec3};
+    use chrono::TimeZone;
+
+    #[test]
+    fn samples_once_a_step_and_restarts_on_time_travel() {
+        let mut bodies = vec![BodyState { name: "Mars", id: BodyId(499), pos_au: Some(Vec3::ZERO), vel_au_d: None, source: None, updated: None, trail: Default::default() }];
+        let mut at = |h: i64| {
+            let epoch = Utc.with_ymd_and_hms(2030, 1, 1, 0, 0, 0).unwrap() + ChronoDuration::hours(h);
+            record_at(&mut bodies, epoch, 3, ChronoDuration::hours(6));
+            bodies[0].trail.len()
+        };
+        assert_eq!(at(0), 1);
+        assert_eq!(at(1), 1);
+        assert_eq!(at(6), 2);
+        assert_eq!(at(12), 3);
+        assert_eq!(at(18), 3);
+        assert_eq!(at(-100), 1);
+    }
+}
@@ -1,60 +1,485 @@
-use ratatui::style::Color;
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt,
+    ops::{Add, Mul, Sub},
+    str::FromStr,
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
 
-#[derive(Debug, Clone, Copy)]
+use crate::cneos::CloseApproaches;
+use crate::config::{ColorBy, Config, ExtraBody, MapView};
+use crate::events::{GoTo, OrbitEvent, Playback};
+use crate::gravity::GravityField;
+use crate::horizons::{ElementHistory, ElementsRow, RetryQueue, SkyRow, TrajectoryBuffer};
+use crate::journal::{JournalEntry, NoteDraft};
+use crate::markers::Marker;
+use crate::pins::Pin;
+use crate::tours::{TourPlayer, TourRequest};
+use crate::picker::DatePicker;
+use crate::milankovitch::OrbitalForcing;
+use crate::names;
+use crate::sources::{Provenance, Source};
+use crate::bandwidth::Usage;
+use crate::power::Thermal;
+use crate::spk::Spk;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "net", derive(utoipa::ToSchema))]
 pub struct Vec3 {
     pub x: f64,
     pub y: f64,
     pub z: f64,
 }
 
+impl Vec3 {
+    pub const ZERO: Vec3 = Vec3 { x: 0.0, y: 0.0, z: 0.0 };
+
+    pub fn dot(self, o: Vec3) -> f64 {
+        self.x * o.x + self.y * o.y + self.z * o.z
+    }
+
+    pub fn cross(self, o: Vec3) -> Vec3 {
+        Vec3 {
+            x: self.y * o.z - self.z * o.y,
+            y: self.z * o.x - self.x * o.z,
+            z: self.x * o.y - self.y * o.x,
+        }
+    }
+
+    pub fn norm(self) -> f64 {
+        self.dot(self).sqrt()
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+    fn add(self, o: Vec3) -> Vec3 {
+        Vec3 { x: self.x + o.x, y: self.y + o.y, z: self.z + o.z }
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+    fn sub(self, o: Vec3) -> Vec3 {
+        Vec3 { x: self.x - o.x, y: self.y - o.y, z: self.z - o.z }
+    }
+}
+
+impl Mul<f64> for Vec3 {
+    type Output = Vec3;
+    fn mul(self, k: f64) -> Vec3 {
+        Vec3 { x: self.x * k, y: self.y * k, z: self.z * k }
+    }
+}
+
+/// A body's NAIF id, which is also its Horizons `COMMAND` (10 for the Sun, 399 for Earth).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BodyId(pub i32);
+
+impl BodyId {
+    pub const SUN: BodyId = BodyId(10);
+    pub const EARTH: BodyId = BodyId(399);
+}
+
+impl fmt::Display for BodyId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for BodyId {
+    type Err = std::num::ParseIntError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim().parse().map(BodyId)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct BodyState {
     pub name: &'static str,
-    pub id: &'static str,
+    pub id: BodyId,
     pub pos_au: Option<Vec3>,
+    pub vel_au_d: Option<Vec3>,
+    pub source: Option<Provenance>, // which source answered the last refresh
+    pub updated: Option<Instant>,   // when fresh data last arrived, for the update pulse
+    pub trail: VecDeque<(DateTime<Utc>, Vec3)>, // past positions, oldest first (see `trails`)
 }
 
+/// What the background tasks produce and share behind a mutex: bodies, fetch metadata,
+/// events, alerts and config. Nothing here is about how it is shown.
 #[derive(Debug, Clone)]
-pub struct AppState {
+pub struct DataState {
     pub bodies: Vec<BodyState>,
     pub last_update_utc: Option<String>,
     pub status: String,
+
+    /// Upcoming events from the latest vectors, soonest first (see `events::refresh`).
+    pub events: Vec<OrbitEvent>,
+    pub search_progress: Option<f64>, // rare-event search running, fraction done
+
+    pub journal: Vec<JournalEntry>, // observation journal, oldest first
+    pub markers: Vec<Marker>,       // named timeline markers, earliest first
+    pub pins: Vec<Pin>,             // named points pinned on the orbit map
+    pub tour_request: Option<TourRequest>, // from the control API, taken by the input loop
+    pub tour_playing: Option<String>, // name of the tour on screen, for the control API
+
+    // Osculating-element history of one body (fetched on demand), and the newest elements
+    // fetched per body, which the diagnostics prefer over the J2000 mean orbit
+    pub history: Option<ElementHistory>,
+    pub fresh_elements: BTreeMap<&'static str, ElementsRow>,
+
+    pub milankovitch: Option<Arc<Vec<OrbitalForcing>>>, // computed on first open
+
+    // Horizons observer tables from the configured observer, fetched when the sky panel opens
+    pub sky: BTreeMap<BodyId, Arc<Vec<SkyRow>>>,
+
+    pub range: Option<Arc<TrajectoryBuffer>>, // vector tables over a chosen date range, fetched with `F`
+    pub close_approaches: Option<Arc<CloseApproaches>>, // CNEOS NEO passes, refreshed daily once opened
+
+    pub alerts: Vec<Alert>, // session alert history, oldest first, capped at `MAX_ALERTS`
+    pub retry_queue: RetryQueue, // bodies whose Horizons queries are failing, as of the last round
+
+    pub config: Arc<Config>,
+    pub source: Option<Source>,  // `--source`: the only source used, overriding `config.sources`
+    pub spk: Option<Arc<Spk>>,   // kernel loaded with `--spk`
+    pub offline: bool,           // `--offline`: no requests at all, cached and local data only
+    pub sim_epoch: Option<DateTime<Utc>>, // time travel: vectors are fetched for this epoch, not now
+    pub frozen_clock: Option<DateTime<Utc>>, // stands in for now, so a rendered frame is reproducible
+    pub profile: Option<String>, // active named profile layered over the main config
+    pub popup: Option<String>, // modal message (e.g. config errors), dismissed with Esc
+    pub thermal: Option<Thermal>, // CPU readings, sampled in the low-power profile only
+    pub bandwidth: Usage,         // Horizons downloads this session and today
+    // The Sun's state about the solar-system barycentre, kept while `origin = barycentric`;
+    // body states themselves stay heliocentric, as the physics expects
+    pub sun_barycentric: Option<(Vec3, Vec3)>,
+    pub gravity_field: Option<Arc<GravityField>>, // sampled for the map overlay, kept until its window moves
+}
+
+/// How the screen is drawn, from `--display-profile`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DisplayProfile {
+    #[default]
+    Standard,
+    /// For e-ink panels: no color or dimmed text, no update pulse, and the screen is redrawn
+    /// in full every `EINK_REDRAW` rather than continuously (keys still redraw at once).
+    Eink,
+}
+
+/// Full-redraw interval of the e-ink profile; each one flashes the panel to clear ghosting.
+pub const EINK_REDRAW: Duration = Duration::from_secs(60);
+
+impl DisplayProfile {
+    pub fn parse(name: &str) -> anyhow::Result<DisplayProfile> {
+        DisplayProfile::deserialize(serde::de::value::StrDeserializer::<serde::de::value::Error>::new(name))
+            .map_err(|_| anyhow::anyhow!("unknown display profile {:?} (standard or eink)", name))
+    }
+}
+
+/// How the data is shown. Owned by the input loop alone, so view changes take no lock and
+/// the updater never touches them.
+#[derive(Debug, Clone)]
+pub struct ViewState {
     pub use_unicode_icons: bool,
+    pub display: DisplayProfile,
 
     // Zoom controls
     pub zoom: f64,          // multiplicative zoom factor (1.0 default)
     pub focus_index: usize, // which max-orbit target we fit to
+
+    // Table selection
+    pub selected: usize,            // index into `DataState::bodies`
+    pub relative_to: Option<BodyId>, // table and map centred on this body instead of the Sun
+    pub show_diagnostics: bool,     // orbit diagnostics panel in place of the vector table
+    pub show_events: bool,          // upcoming events list in place of the vector table
+    pub event_selected: usize,      // index into `events::timeline`, moved with ↑/↓ in the panel
+    pub goto: Option<GoTo>,         // epoch jumped to with Enter, shown until Esc
+    pub playback: Option<Playback>, // time-lapse clock, shown through `goto` each frame
+    pub show_history: bool,         // element history of the selected body instead
+    pub show_alerts: bool,          // alert history instead
+    pub show_sky: bool,             // RA/Dec and alt/az from the observer instead
+    pub show_neo: bool,             // upcoming NEO close approaches instead
+    pub show_elements: bool,        // osculating elements of every body instead
+    pub show_divergence: bool,      // positions against `config.reference_source`, also on the map
+    pub alert_scroll: usize,        // alert rows scrolled back from the newest
+    pub show_milankovitch: bool,    // Milankovitch screen in place of everything below the header
+    pub map_view: MapView,          // what the map panel draws
+    pub color_by: ColorBy,          // map and table coloring
+    pub velocity_arrows: bool,      // direction-of-motion arrows on the map, scaled by speed
+    pub predicted_path: bool,       // dotted path of the selected body over the next `path_days`
+    pub braille: bool,              // orbit map rings and circles in Braille sub-cell dots
+    pub mouse: bool,                // mouse captured for the orbit map coordinate readout
+    pub pointer: Option<(u16, u16)>, // terminal cell under the mouse, while captured
+    pub drag_from: Option<(u16, u16)>, // where the left button went down, until it comes up
+    pub measure: Vec<Vec3>,         // measure tool points on the map (heliocentric AU), up to two
+    pub pan: Vec3,                  // map centre moved off the Sun or reference body (AU, z = 0)
+    pub follow: Option<BodyId>,     // body the map stays centred on, without re-referencing vectors
+    pub tour: Option<TourPlayer>,   // camera tour playing; moves zoom, centre and epoch each frame
+    pub camera: Option<Vec3>,       // map centre set by the tour, over follow and pan
+    pub gravity_field: bool,        // net gravity direction glyphs across the map
+    pub show_limits: bool,          // sphere of influence and Roche limit of the selected planet
+    pub topocentric: bool,          // RA/Dec and distances from the observer, not Earth's centre
+    pub apparent: bool,             // light-time and aberration corrected rather than geometric
+    pub of_date: bool,              // detail-pane RA/Dec on the true equator of date, not J2000
+    pub language: String,           // body names in the table and detail pane (see `names`)
+    pub note: Option<NoteDraft>,    // observation note being typed; takes all keys while open
+    pub find: Option<String>,       // find-by-name query being typed; takes all keys while open
+    pub marker: Option<String>,     // timeline marker name being typed; takes all keys while open
+    pub pin: Option<(Vec3, String)>, // map pin being named and where it goes; takes all keys while open
+    pub picker: Option<DatePicker>, // date picker for time travel or a range; takes all keys while open
+    pub count: Option<usize>,       // vim-style count prefix typed so far (see `keys`)
+
+    /// Config the view-related settings were last taken from.
+    pub config: Arc<Config>,
+}
+
+impl ViewState {
+    pub fn new(config: Arc<Config>, use_unicode_icons: bool) -> Self {
+        ViewState {
+            use_unicode_icons,
+            display: DisplayProfile::default(),
+            zoom: 1.0,
+            focus_index: DEFAULT_FOCUS,
+            selected: 0,
+            relative_to: None,
+            show_diagnostics: false,
+            show_events: false,
+            event_selected: 0,
+            goto: None,
+            playback: None,
+            show_history: false,
+            show_alerts: false,
+            show_sky: false,
+            show_neo: false,
+            show_elements: false,
+            show_divergence: false,
+            alert_scroll: 0,
+            show_milankovitch: false,
+            map_view: config.view,
+            color_by: config.color_by,
+            velocity_arrows: false,
+            predicted_path: false,
+            braille: false,
+            mouse: false,
+            pointer: None,
+            drag_from: None,
+            measure: Vec::new(),
+            pan: Vec3::ZERO,
+            follow: None,
+            tour: None,
+            camera: None,
+            gravity_field: false,
+            show_limits: false,
+            topocentric: false,
+            apparent: false,
+            of_date: false,
+            language: names::resolve_language(config.language.as_deref()),
+            note: None,
+            find: None,
+            marker: None,
+            pin: None,
+            picker: None,
+            count: None,
+            config,
+        }
+    }
+
+    /// Catches up with the shared data once per frame: takes the view settings from a newly
+    /// loaded config, keeps indices valid when the body list shrinks, and lets go of a
+    /// reference or followed body no longer listed.
+    pub fn sync(&mut self, data: &DataState) {
+        if !Arc::ptr_eq(&self.config, &data.config) {
+            self.map_view = data.config.view;
+            self.color_by = data.config.color_by;
+            self.language = names::resolve_language(data.config.language.as_deref());
+            self.config = data.config.clone();
+        }
+        self.selected = self.selected.min(data.bodies.len().saturating_sub(1));
+        self.event_selected = self.event_selected.min((data.events.len() + data.markers.len()).saturating_sub(1));
+        if self.relative_to.is_some_and(|id| data.body(id).is_none()) {
+            self.relative_to = None;
+        }
+        if self.follow.is_some_and(|id| data.body(id).is_none()) {
+            self.follow = None;
+        }
+    }
+}
+
+pub const MAX_ALERTS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+/// Something that happened during the session: a fetch failure, a finished search, a failed
+/// slew. Kept after the status line has moved on.
+#[derive(Debug, Clone)]
+pub struct Alert {
+    pub utc: DateTime<Utc>,
+    pub level: AlertLevel,
+    pub text: String,
+    pub repeats: u32, // times it fired again since first recorded
+}
+
+impl DataState {
+    /// Nothing fetched or loaded yet: no journal, markers or pins, every source in the chain.
+    pub fn new(config: Arc<Config>, bodies: Vec<BodyState>) -> Self {
+        DataState {
+            bodies,
+            last_update_utc: None,
+            status: "Starting…".into(),
+            events: Vec::new(),
+            search_progress: None,
+            journal: Vec::new(),
+            markers: Vec::new(),
+            pins: Vec::new(),
+            tour_request: None,
+            tour_playing: None,
+            retry_queue: Default::default(),
+            history: None,
+            fresh_elements: Default::default(),
+            sky: Default::default(),
+            close_approaches: None,
+            range: None,
+            alerts: Vec::new(),
+            milankovitch: None,
+            config,
+            source: None,
+            spk: None,
+            offline: false,
+            sim_epoch: None,
+            frozen_clock: None,
+            profile: None,
+            popup: None,
+            thermal: None,
+            bandwidth: Default::default(),
+            sun_barycentric: None,
+            gravity_field: None,
+        }
+    }
+
+    /// The listed body with `id`, wherever the last rebuild of the list put it.
+    pub fn body(&self, id: BodyId) -> Option<&BodyState> {
+        self.bodies.iter().find(|b| b.id == id)
+    }
+
+    /// The time the data is meant for: the time-travel epoch while one is set, else now.
+    pub fn clock(&self) -> DateTime<Utc> {
+        self.sim_epoch.or(self.frozen_clock).unwrap_or_else(Utc::now)
+    }
+
+    /// Records an alert in the history without touching the status line. A repeat of one of
+    /// the recent alerts (the same fetch failing every refresh) moves it to the end with a
+    /// count instead of flooding the history.
+    pub fn alert(&mut self, level: AlertLevel, text: impl Into<String>) {
+        push_alert(&mut self.alerts, Alert { utc: Utc::now(), level, text: text.into(), repeats: 0 });
+    }
+
+    /// Shows `text` on the status line and records it as an alert.
+    pub fn notify(&mut self, level: AlertLevel, text: impl Into<String>) {
+        let text = text.into();
+        self.alert(level, text.clone());
+        self.status = text;
+    }
+}
+
+fn push_alert(alerts: &mut Vec<Alert>, mut alert: Alert) {
+    let recent = alerts.len().saturating_sub(16);
+    if let Some(i) = alerts[recent..].iter().position(|a| a.level == alert.level && a.text == alert.text) {
+        alert.repeats = alerts.remove(recent + i).repeats + 1;
+    }
+    if alerts.len() == MAX_ALERTS {
+        alerts.remove(0);
+    }
+    alerts.push(alert);
 }
 
 #[derive(Debug, Deserialize)]
 pub struct HorizonsJson {
+    #[serde(default)]
+    pub signature: Option<ApiSignature>,
     #[serde(default)]
     pub error: Option<String>,
     #[serde(default)]
     pub result: String,
 }
 
+/// The `signature` block of a JPL SSD API response.
+#[derive(Debug, Deserialize)]
+pub struct ApiSignature {
+    #[serde(default)]
+    pub source: String,
+    pub version: String,
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct BodyMeta {
     pub name: &'static str,
-    pub id: &'static str,
+    pub id: BodyId,
     pub nf_icon: char,
     pub uni_icon: char,
-    pub color: Color,
     pub orbit_au: Option<f64>,
+    pub mass_kg: f64,
+    pub radius_km: f64,
+    pub bond_albedo: f64,
+    pub class: BodyClass,
+    /// Absolute magnitude H, V(1,0) for planets (the Sun's apparent magnitude at 1 AU).
+    pub abs_mag: f64,
+    /// The planet a moon orbits; `None` for bodies orbiting the Sun.
+    pub parent: Option<BodyId>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyClass {
+    Star,
+    Terrestrial,
+    GasGiant,
+    IceGiant,
+    /// Pluto, Ceres and the other IAU dwarf planets, hidden unless `dwarf_planets` is set.
+    Dwarf,
+    /// Natural satellites, drawn on the map only once they separate from their planet.
+    Moon,
+    /// Asteroids, comets and anything else added with `extra_bodies`.
+    SmallBody,
+    /// Probes and telescopes with Horizons ids (negative, as in NAIF), hidden unless
+    /// `spacecraft` is set.
+    Spacecraft,
 }
 
 pub const BODIES: &[BodyMeta] = &[
-    BodyMeta { name: "Sun",     id: "10",  nf_icon: '\u{F185}', uni_icon: '', color: Color::Yellow,orbit_au: None },
-    BodyMeta { name: "Mercury", id: "199", nf_icon: '', uni_icon: '', color: Color::LightMagenta, orbit_au: Some(0.387098) },
-    BodyMeta { name: "Venus",   id: "299", nf_icon: '', uni_icon: '', color: Color::LightYellow,  orbit_au: Some(0.723332) },
-    BodyMeta { name: "Earth",   id: "399", nf_icon: '', uni_icon: '', color: Color::LightBlue,    orbit_au: Some(1.000000) },
-    BodyMeta { name: "Mars",    id: "499", nf_icon: '', uni_icon: '', color: Color::Red,          orbit_au: Some(1.523679) },
-    BodyMeta { name: "Jupiter", id: "599", nf_icon: '', uni_icon: '', color: Color::LightRed,     orbit_au: Some(5.203800) },
-    BodyMeta { name: "Saturn",  id: "699", nf_icon: '', uni_icon: '', color: Color::LightYellow,  orbit_au: Some(9.537070) },
-    BodyMeta { name: "Uranus",  id: "799", nf_icon: '', uni_icon: '', color: Color::Cyan,         orbit_au: Some(19.19126) },
-    BodyMeta { name: "Neptune", id: "899", nf_icon: '', uni_icon: '', color: Color::Blue,         orbit_au: Some(30.06896) },
+    BodyMeta { name: "Sun",      id: BodyId(10),      nf_icon: '\u{F185}', uni_icon: '', orbit_au: None,           mass_kg: 1.98847e30, radius_km: 695700.0, bond_albedo: 0.0,   class: BodyClass::Star,        abs_mag: -26.74, parent: None },
+    BodyMeta { name: "Mercury",  id: BodyId(199),     nf_icon: '', uni_icon: '', orbit_au: Some(0.387098), mass_kg: 3.3011e23,  radius_km: 2439.7,   bond_albedo: 0.088, class: BodyClass::Terrestrial, abs_mag: -0.42,  parent: None },
+    BodyMeta { name: "Venus",    id: BodyId(299),     nf_icon: '', uni_icon: '', orbit_au: Some(0.723332), mass_kg: 4.8675e24,  radius_km: 6051.8,   bond_albedo: 0.76,  class: BodyClass::Terrestrial, abs_mag: -4.40,  parent: None },
+    BodyMeta { name: "Earth",    id: BodyId(399),     nf_icon: '', uni_icon: '', orbit_au: Some(1.000000), mass_kg: 5.9722e24,  radius_km: 6371.0,   bond_albedo: 0.306, class: BodyClass::Terrestrial, abs_mag: -3.86,  parent: None },
+    BodyMeta { name: "Moon",     id: BodyId(301),     nf_icon: '', uni_icon: '', orbit_au: None,           mass_kg: 7.342e22,   radius_km: 1737.4,   bond_albedo: 0.11,  class: BodyClass::Moon,        abs_mag: 0.21,   parent: Some(BodyId::EARTH) },
+    BodyMeta { name: "Mars",     id: BodyId(499),     nf_icon: '', uni_icon: '', orbit_au: Some(1.523679), mass_kg: 6.4171e23,  radius_km: 3389.5,   bond_albedo: 0.25,  class: BodyClass::Terrestrial, abs_mag: -1.52,  parent: None },
+    BodyMeta { name: "Ceres",    id: BodyId(2000001), nf_icon: '', uni_icon: '', orbit_au: Some(2.767500), mass_kg: 9.3839e20,  radius_km: 469.7,    bond_albedo: 0.03,  class: BodyClass::Dwarf,       abs_mag: 3.34,   parent: None },
+    BodyMeta { name: "Jupiter",  id: BodyId(599),     nf_icon: '', uni_icon: '', orbit_au: Some(5.203800), mass_kg: 1.8982e27,  radius_km: 69911.0,  bond_albedo: 0.343, class: BodyClass::GasGiant,    abs_mag: -9.40,  parent: None },
+    BodyMeta { name: "Io",       id: BodyId(501),     nf_icon: '', uni_icon: '', orbit_au: None,           mass_kg: 8.9319e22,  radius_km: 1821.6,   bond_albedo: 0.63,  class: BodyClass::Moon,        abs_mag: -1.68,  parent: Some(BodyId(599)) },
+    BodyMeta { name: "Europa",   id: BodyId(502),     nf_icon: '', uni_icon: '', orbit_au: None,           mass_kg: 4.7998e22,  radius_km: 1560.8,   bond_albedo: 0.62,  class: BodyClass::Moon,        abs_mag: -1.41,  parent: Some(BodyId(599)) },
+    BodyMeta { name: "Ganymede", id: BodyId(503),     nf_icon: '', uni_icon: '', orbit_au: None,           mass_kg: 1.4819e23,  radius_km: 2634.1,   bond_albedo: 0.44,  class: BodyClass::Moon,        abs_mag: -2.09,  parent: Some(BodyId(599)) },
+    BodyMeta { name: "Callisto", id: BodyId(504),     nf_icon: '', uni_icon: '', orbit_au: None,           mass_kg: 1.0759e23,  radius_km: 2410.3,   bond_albedo: 0.13,  class: BodyClass::Moon,        abs_mag: -1.05,  parent: Some(BodyId(599)) },
+    BodyMeta { name: "Saturn",   id: BodyId(699),     nf_icon: '', uni_icon: '', orbit_au: Some(9.537070), mass_kg: 5.6834e26,  radius_km: 58232.0,  bond_albedo: 0.342, class: BodyClass::GasGiant,    abs_mag: -8.88,  parent: None },
+    BodyMeta { name: "Titan",    id: BodyId(606),     nf_icon: '', uni_icon: '', orbit_au: None,           mass_kg: 1.3452e23,  radius_km: 2574.7,   bond_albedo: 0.27,  class: BodyClass::Moon,        abs_mag: -1.28,  parent: Some(BodyId(699)) },
+    BodyMeta { name: "Uranus",   id: BodyId(799),     nf_icon: '', uni_icon: '', orbit_au: Some(19.19126), mass_kg: 8.6810e25,  radius_km: 25362.0,  bond_albedo: 0.300, class: BodyClass::IceGiant,    abs_mag: -7.19,  parent: None },
+    BodyMeta { name: "Neptune",  id: BodyId(899),     nf_icon: '', uni_icon: '', orbit_au: Some(30.06896), mass_kg: 1.02413e26, radius_km: 24622.0,  bond_albedo: 0.290, class: BodyClass::IceGiant,    abs_mag: -6.87,  parent: None },
+    BodyMeta { name: "Triton",   id: BodyId(801),     nf_icon: '', uni_icon: '', orbit_au: None,           mass_kg: 2.139e22,   radius_km: 1353.4,   bond_albedo: 0.76,  class: BodyClass::Moon,        abs_mag: -1.24,  parent: Some(BodyId(899)) },
+    BodyMeta { name: "Pluto",    id: BodyId(999),     nf_icon: '', uni_icon: '', orbit_au: Some(39.48212), mass_kg: 1.3030e22,  radius_km: 1188.3,   bond_albedo: 0.72,  class: BodyClass::Dwarf,       abs_mag: -0.76,  parent: None },
+    BodyMeta { name: "Haumea",   id: BodyId(2136108), nf_icon: '', uni_icon: '', orbit_au: Some(43.11600), mass_kg: 4.006e21,   radius_km: 798.0,    bond_albedo: 0.51,  class: BodyClass::Dwarf,       abs_mag: 0.23,   parent: None },
+    BodyMeta { name: "Makemake", id: BodyId(2136472), nf_icon: '', uni_icon: '', orbit_au: Some(45.43000), mass_kg: 3.1e21,     radius_km: 715.0,    bond_albedo: 0.74,  class: BodyClass::Dwarf,       abs_mag: -0.20,  parent: None },
+    BodyMeta { name: "Eris",     id: BodyId(2136199), nf_icon: '', uni_icon: '', orbit_au: Some(67.86400), mass_kg: 1.6466e22,  radius_km: 1163.0,   bond_albedo: 0.96,  class: BodyClass::Dwarf,       abs_mag: -1.21,  parent: None },
+    BodyMeta { name: "Voyager 1", id: BodyId(-31),     nf_icon: '', uni_icon: '', orbit_au: None,           mass_kg: 721.9,      radius_km: 0.0019,   bond_albedo: 0.0,   class: BodyClass::Spacecraft,  abs_mag: f64::NAN, parent: None },
+    BodyMeta { name: "Voyager 2", id: BodyId(-32),     nf_icon: '', uni_icon: '', orbit_au: None,           mass_kg: 721.9,      radius_km: 0.0019,   bond_albedo: 0.0,   class: BodyClass::Spacecraft,  abs_mag: f64::NAN, parent: None },
+    BodyMeta { name: "New Horizons", id: BodyId(-98),     nf_icon: '', uni_icon: '', orbit_au: None,           mass_kg: 478.0,      radius_km: 0.0014,   bond_albedo: 0.0,   class: BodyClass::Spacecraft,  abs_mag: f64::NAN, parent: None },
+    BodyMeta { name: "JWST",     id: BodyId(-170),    nf_icon: '', uni_icon: '', orbit_au: None,           mass_kg: 6161.4,     radius_km: 0.0105,   bond_albedo: 0.0,   class: BodyClass::Spacecraft,  abs_mag: f64::NAN, parent: None },
+    BodyMeta { name: "Parker",   id: BodyId(-96),     nf_icon: '', uni_icon: '', orbit_au: None,           mass_kg: 685.0,      radius_km: 0.0012,   bond_albedo: 0.0,   class: BodyClass::Spacecraft,  abs_mag: f64::NAN, parent: None },
 ];
 
 pub const FOCUS_LEVELS: &[(&str, f64)] = &[
@@ -64,12 +489,139 @@ pub const FOCUS_LEVELS: &[(&str, f64)] = &[
     ("Saturn",  9.537070),
     ("Uranus",  19.19126),
     ("Neptune", 30.06896),
+    ("Pluto",   39.48212),
+    ("Eris",    67.86400),
 ];
 
-pub fn meta_by_name(name: &str) -> Option<BodyMeta> {
-    BODIES.iter().copied().find(|m| m.name == name)
+/// Focus level of the default view and `0`: Neptune's orbit, with the dwarf planets beyond.
+pub const DEFAULT_FOCUS: usize = 5;
+
+/// A body from the config's `extra_bodies`, registered at startup.
+#[derive(Debug)]
+pub struct ExtraMeta {
+    pub meta: BodyMeta,
+    /// Horizons COMMAND, used verbatim.
+    pub command: &'static str,
+    /// As given in the config; the UI parses it.
+    pub color: Option<&'static str>,
+}
+
+/// Made-up ids of extra bodies without an SPK id start here, above every NAIF range.
+pub const EXTRA_ID_BASE: i32 = 900_000_000;
+
+static EXTRA_BODIES: RwLock<Vec<&'static ExtraMeta>> = RwLock::new(Vec::new());
+
+/// Adds a body after the built-in and earlier extra ones for the rest of the process, since
+/// body names and metadata are `'static`: the config's at startup, SBDB lookups as they come.
+/// `None` if its name or id is taken.
+pub fn register_extra_body(b: &ExtraBody) -> Option<&'static BodyMeta> {
+    let leak = |s: &str| -> &'static str { Box::leak(s.to_owned().into_boxed_str()) };
+    let mut extra = EXTRA_BODIES.write().unwrap();
+    let id = BodyId(b.id.unwrap_or(EXTRA_ID_BASE + extra.len() as i32));
+    if BODIES.iter().chain(extra.iter().map(|e| &e.meta)).any(|m| m.id == id || m.name == b.name) {
+        return None;
+    }
+    let icon = b.icon.unwrap_or('*');
+    let meta = BodyMeta {
+        name: leak(&b.name),
+        id,
+        nf_icon: icon,
+        uni_icon: icon,
+        orbit_au: None,
+        mass_kg: 0.0,
+        radius_km: 0.0,
+        bond_albedo: 0.0,
+        class: BodyClass::SmallBody,
+        abs_mag: b.abs_mag.unwrap_or(f64::NAN),
+        parent: None,
+    };
+    let entry: &'static ExtraMeta =
+        Box::leak(Box::new(ExtraMeta { meta, command: leak(&b.command), color: b.color.as_deref().map(leak) }));
+    extra.push(entry);
+    Some(&entry.meta)
+}
+
+pub fn extra_bodies() -> Vec<&'static ExtraMeta> {
+    EXTRA_BODIES.read().unwrap().clone()
+}
+
+/// Built-in bodies, then the registered extra ones.
+pub fn all_bodies() -> impl Iterator<Item = &'static BodyMeta> {
+    BODIES.iter().chain(extra_bodies().into_iter().map(|e| &e.meta))
+}
+
+/// The built-in or extra body with this id.
+pub fn meta_by_id(id: BodyId) -> Option<&'static BodyMeta> {
+    all_bodies().find(|m| m.id == id)
+}
+
+/// The body with this name, for names given by the user (config, `--body`): IAU names for
+/// the built-in bodies, the configured one for extra bodies.
+pub fn meta_by_name(name: &str) -> Option<&'static BodyMeta> {
+    all_bodies().find(|m| m.name == name)
 }
 
-pub fn icon_for(meta: BodyMeta, use_unicode: bool) -> char {
+/// Bodies of one class, in table order.
+pub fn bodies_of_class(class: BodyClass) -> impl Iterator<Item = &'static BodyMeta> {
+    all_bodies().filter(move |m| m.class == class)
+}
+
+pub fn icon_for(meta: &BodyMeta, use_unicode: bool) -> char {
     if use_unicode { meta.uni_icon } else { meta.nf_icon }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push(alerts: &mut Vec<Alert>, level: AlertLevel, text: &str) {
+        push_alert(alerts, Alert { utc: Utc::now(), level, text: text.into(), repeats: 0 });
+    }
+
+    #[test]
+    fn alerts_count_repeats_and_stay_capped() {
+        let mut alerts = Vec::new();
+        push(&mut alerts, AlertLevel::Warning, "Fetch error (Mars)");
+        push(&mut alerts, AlertLevel::Info, "Search done");
+        push(&mut alerts, AlertLevel::Warning, "Fetch error (Mars)");
+        push(&mut alerts, AlertLevel::Warning, "Fetch error (Mars)");
+        // The repeat moves to the end with a count; another level is another alert
+        let seen: Vec<_> = alerts.iter().map(|a| (a.text.as_str(), a.repeats)).collect();
+        assert_eq!(seen, [("Search done", 0), ("Fetch error (Mars)", 2)]);
+        push(&mut alerts, AlertLevel::Error, "Fetch error (Mars)");
+        assert_eq!(alerts.len(), 3);
+
+        for n in 0..MAX_ALERTS + 10 {
+            push(&mut alerts, AlertLevel::Info, &format!("alert {}", n));
+        }
+        assert_eq!(alerts.len(), MAX_ALERTS);
+        assert_eq!(alerts[0].text, "alert 10");
+        // Only recent alerts are matched: one pushed out of the last 16 starts a new count
+        push(&mut alerts, AlertLevel::Info, "alert 10");
+        assert_eq!((alerts.len(), alerts[0].text.as_str()), (MAX_ALERTS, "alert 11"));
+        assert_eq!(alerts.last().map(|a| a.repeats), Some(0));
+        push(&mut alerts, AlertLevel::Info, &format!("alert {}", MAX_ALERTS + 9));
+        assert_eq!(alerts.last().map(|a| a.repeats), Some(1));
+        assert_eq!(alerts.len(), MAX_ALERTS);
+    }
+
+    #[test]
+    fn followed_body_survives_a_rebuilt_list() {
+        let config = Arc::new(Config::default());
+        let mut data = DataState::new(config.clone(), config.body_states(&[]));
+        let mut view = ViewState::new(config.clone(), false);
+        let jupiter = meta_by_name("Jupiter").unwrap().id;
+        (view.follow, view.relative_to) = (Some(jupiter), Some(jupiter));
+
+        // Dwarf planets go in ahead of Jupiter in registry order
+        let with_dwarfs = Config { dwarf_planets: true, ..(*config).clone() };
+        data.bodies = with_dwarfs.body_states(&data.bodies);
+        view.sync(&data);
+        assert_eq!(view.follow.and_then(|id| data.body(id)).map(|b| b.name), Some("Jupiter"));
+        assert_eq!(view.relative_to, Some(jupiter));
+
+        data.bodies.retain(|b| b.id != jupiter);
+        view.sync(&data);
+        assert_eq!((view.follow, view.relative_to), (None, None));
+    }
+}
@@ -1,90 +1,1406 @@
+use chrono::{DateTime, Datelike, Local, Timelike, Utc};
+use std::time::Duration;
 use ratatui::{
+    buffer::Buffer,
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
+    symbols::Marker,
     text::{Line, Span},
-    widgets::{Block, Borders, Cell, Paragraph, Row, Table},
+    widgets::{
+        canvas::{Canvas, Line as Segment, Painter, Shape},
+        Axis, Block, Borders, Cell, Chart, Clear, Dataset, GraphType, Paragraph, Row, Table, TableState, Wrap,
+    },
     Frame,
 };
 
-use crate::types::{icon_for, meta_by_name, AppState, BODIES, FOCUS_LEVELS};
+use solar_rs::physics::{
+    apparent_magnitude, au_per_day_to_km_s, circular_speed_km_s, equilibrium_temp_k, hill_radius_au, irradiance_w_m2, roche_limit_au, soi_radius_au,
+    days_since_j2000, orbit_diagnostics, reference_orbit, solar_escape_speed_km_s, solar_tidal_accel_m_s2,
+    surface_escape_speed_km_s, light_time_s, AU_KM, GM_SUN_AU3_D2, SUN_MASS_KG,
+};
+use solar_rs::config::{ColorBy, MapView, Origin, PanelLayout, Power, SkyMarker, Theme};
+use solar_rs::frames::{
+    apparent_offset, ecliptic_to_equatorial, galactic_center, lon_lat_deg, ra_dec_deg, solar_apex, true_of_date,
+};
+use solar_rs::journal::{JournalEntry, NoteDraft, NOTE_FIELDS};
+use solar_rs::picker::{DatePicker, FIELDS};
+use solar_rs::milankovitch::OrbitalForcing;
+use solar_rs::bandwidth::format_bytes;
+use solar_rs::cneos;
+use solar_rs::events::{self, TimelineEntry};
+use solar_rs::gravity::FieldWindow;
+use solar_rs::horizons::sky_at;
+use solar_rs::observer;
+use solar_rs::names::{self, display_name};
+use solar_rs::orbital_mechanics::state_to_elements;
+use solar_rs::rotation::{obliquity_deg, rotation_model};
+use solar_rs::sources::{self, Source};
+use solar_rs::types::{bodies_of_class, extra_bodies, icon_for, meta_by_id, meta_by_name, AlertLevel, BodyClass, BodyId, BodyMeta, BodyState, DataState, DisplayProfile, Vec3, ViewState, FOCUS_LEVELS};
+
+/// Draws a frame; returns where the orbit map went, if it is on screen.
+pub fn draw_ui(f: &mut Frame, data: &DataState, view: &ViewState) -> Option<MapProjection> {
+    let map = draw_screen(f, data, view);
+    if view.display == DisplayProfile::Eink {
+        strip_for_eink(f.buffer_mut());
+    }
+    map
+}
+
+/// Drops colors and dimmed or blinking text, which e-ink panels render as low-contrast grays
+/// or smear; bold and reversed (the selection) stay.
+fn strip_for_eink(buf: &mut Buffer) {
+    for cell in buf.content.iter_mut() {
+        cell.set_fg(Color::Reset).set_bg(Color::Reset);
+        cell.modifier.remove(Modifier::DIM | Modifier::SLOW_BLINK | Modifier::RAPID_BLINK);
+    }
+}
 
-pub fn draw_ui(f: &mut Frame, state: &AppState) {
-    let (focus_name, focus_au) = FOCUS_LEVELS[state.focus_index];
+fn draw_screen(f: &mut Frame, data: &DataState, view: &ViewState) -> Option<MapProjection> {
+    let (focus_name, focus_au) = FOCUS_LEVELS[view.focus_index];
 
     let root = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Length(3), Constraint::Min(0)])
         .split(f.area());
 
+    let observer = match data.config.observer {
+        Some(o) => format!(
+            " | obs {:.2}°{} {:.2}°{}",
+            o.lat_deg.abs(),
+            if o.lat_deg < 0.0 { 'S' } else { 'N' },
+            o.lon_deg.abs(),
+            if o.lon_deg < 0.0 { 'W' } else { 'E' }
+        ),
+        None => String::new(),
+    };
+
+    let thermal = match data.thermal {
+        Some(t) => {
+            let temp = t.temp_c.map(|c| format!(" {:.0}°C", c)).unwrap_or_default();
+            let limits = t.throttling.filter(|f| f.active()).map(|f| format!(" ({})", f.describe())).unwrap_or_default();
+            format!(" | CPU{}{}", temp, limits)
+        }
+        None => String::new(),
+    };
+
+    let usage = &data.bandwidth;
+    let today = usage.bytes_on(Local::now().date_naive());
+    let bandwidth = match data.config.daily_quota_mb {
+        Some(mb) => format!(" | ↓ {} ({} of {} MB today)", format_bytes(usage.session_bytes), format_bytes(today), mb),
+        None if today > 0 => format!(" | ↓ {} ({} today)", format_bytes(usage.session_bytes), format_bytes(today)),
+        None => String::new(),
+    };
+
+    // Which sources answered the last refresh, e.g. "Horizons×7 cache×1"
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for src in data.bodies.iter().filter(|b| b.id != BodyId::SUN).filter_map(|b| b.source) {
+        match counts.iter_mut().find(|(l, _)| *l == src.source.label()) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((src.source.label(), 1)),
+        }
+    }
+    let sources = match counts.as_slice() {
+        [] => String::new(),
+        [(label, _)] => format!(" [{}]", label),
+        _ => format!(" [{}]", counts.iter().map(|(l, n)| format!("{}×{}", l, n)).collect::<Vec<_>>().join(" ")),
+    };
+
+    let now = data.clock();
+    let stale = match data.bodies.iter().filter(|b| data.config.is_stale(b, now)).count() {
+        0 => String::new(),
+        n => format!(" | {} stale (>{} h)", n, data.config.max_data_age_hours),
+    };
+    let failing = match data.retry_queue.len() {
+        0 => String::new(),
+        n => format!(" | {} failing", n),
+    };
+    let offline = if data.offline { " | offline" } else { "" };
+    let goto = match &view.goto {
+        Some(g) => format!(" | at {} (Esc: now)", g.label),
+        None => String::new(),
+    };
+
+    let scale = data.config.time_scale;
+    let julian = format!(" | JD {:.5} {}", scale.julian_date(now), scale.label());
+
+    let simulated = match data.sim_epoch {
+        Some(t) => format!("SIMULATED {} UTC (T: change) | ", t.format("%Y-%m-%d %H:%M")),
+        None => String::new(),
+    };
+
     let header = Paragraph::new(Line::from(format!(
-        "Last update: {} | Status: {} | zoom: {:.2}x | focus: {} ({:.2} AU) | +/- zoom, 0 reset, [ ] focus, q quit",
-        state.last_update_utc.as_deref().unwrap_or("—"),
-        state.status,
-        state.zoom,
+        "{}Last update: {}{}{} | Status: {}{}{}{}{} | profile: {} | zoom: {:.2}x | focus: {} ({:.2} AU){}{}{} | +/- zoom, 0 reset, [ ] focus, ↑/↓ select, 5+ count, . repeat, u/^R undo/redo, / find, v view, c color, C centre, V velocity, w path, B braille, P pointer, X measure, Z zoom to box, f follow, i pin, Shift+arrows pan, Home re-centre, G gravity, R SOI/Roche, D dwarfs, s spacecraft, b barycentre, d diagnostics, e events, M mark, T time travel, U UT/TDB, Space play, < > speed, y tour, F range, ←/→ scrub, h elements, l alerts, a apparent, A sky, N NEOs, E elements, K divergence, j J2000/date, t topocentric, o log, m Milankovitch, p profile, q quit",
+        simulated,
+        data.last_update_utc.as_deref().unwrap_or("—"),
+        julian,
+        goto,
+        data.status,
+        sources,
+        offline,
+        stale,
+        failing,
+        data.profile.as_deref().unwrap_or("default"),
+        view.zoom,
         focus_name,
-        focus_au
+        focus_au,
+        observer,
+        thermal,
+        bandwidth
     )))
-    .block(Block::default().borders(Borders::ALL).title("Solar System"));
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(if data.sim_epoch.is_some() { Style::default().fg(Color::Yellow) } else { Style::default() })
+            .title(if data.sim_epoch.is_some() { "Solar System — simulated time" } else { "Solar System" })
+            .title_bottom(Line::from(view.count.map(|n| n.to_string()).unwrap_or_default()).right_aligned()),
+    );
 
     f.render_widget(header, root[0]);
 
+    if view.show_milankovitch {
+        render_milankovitch(f, root[1], data);
+        render_overlays(f, data, view, None);
+        return None;
+    }
+
+    let (table_pct, map_pct) = match data.config.layout {
+        PanelLayout::Split => (40, 60),
+        PanelLayout::Map => (0, 100),
+        PanelLayout::Table | PanelLayout::Compare => (100, 0),
+    };
     let main = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .constraints([Constraint::Percentage(table_pct), Constraint::Percentage(map_pct)])
         .split(root[1]);
 
-    // Table
-    let rows = state.bodies.iter().map(|b| {
-        let icon_cell = if let Some(m) = meta_by_name(b.name) {
+    if table_pct > 0 {
+        let left = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(0), Constraint::Length(16)])
+            .split(main[0]);
+
+        if view.show_diagnostics {
+            render_diagnostics(f, left[0], data, view);
+        } else if view.show_events {
+            render_events(f, left[0], data, view);
+        } else if view.show_history {
+            render_history(f, left[0], data);
+        } else if view.show_alerts {
+            render_alerts(f, left[0], data, view);
+        } else if view.show_sky {
+            render_sky(f, left[0], data, view);
+        } else if view.show_neo {
+            render_close_approaches(f, left[0], data);
+        } else if view.show_elements {
+            render_elements(f, left[0], data, view);
+        } else if view.show_divergence {
+            render_divergence(f, left[0], data, view);
+        } else if data.config.layout == PanelLayout::Compare {
+            render_compare_table(f, left[0], data, view);
+        } else {
+            render_table(f, left[0], data, view);
+        }
+        render_detail(f, left[1], data, view);
+    }
+
+    // Map
+    let mut map = None;
+    if map_pct > 0 {
+        match view.map_view {
+            MapView::Map => map = Some(render_map(f, main[1], data, view)),
+            MapView::Strip => f.render_widget(render_strip_block(main[1], data, view), main[1]),
+            MapView::Orrery => f.render_widget(render_orrery_block(main[1], data, view), main[1]),
+        }
+        if view.map_view == MapView::Map && view.zoom >= GIZMO_MIN_ZOOM {
+            render_spin_gizmo(f, main[1], data, view);
+        }
+    }
+
+    render_overlays(f, data, view, (map_pct > 0).then_some(main[1]));
+    map
+}
+
+/// Map zoom from which the selected planet's spin gizmo is shown.
+const GIZMO_MIN_ZOOM: f64 = 4.0;
+
+/// Side view of the selected planet's spin axis against its orbit plane (drawn level), with
+/// the prime meridian as a marker on the equator, inset at the map's lower left.
+fn render_spin_gizmo(f: &mut Frame, area: Rect, data: &DataState, view: &ViewState) {
+    let Some(b) = data.bodies.get(view.selected) else { return };
+    let Some(model) = rotation_model(b.name) else { return };
+    let Some((p, v)) = b.pos_au.zip(b.vel_au_d).filter(|(p, _)| p.norm() > 0.0) else { return };
+    let days = events::epoch(data).map_or_else(|| days_since_j2000(Utc::now()), days_since_j2000);
+    let normal = p.cross(v);
+    let tilt = obliquity_deg(model.spin_axis(days), normal * (1.0 / normal.norm()));
+    let w_deg = model.prime_meridian_deg(days);
+
+    const W: usize = 22;
+    const H: usize = 7;
+    if area.width < W as u16 + 4 || area.height < H as u16 + 6 {
+        return;
+    }
+    let mut grid = vec![vec![' '; W]; H];
+    let (cx, cy) = (W as f64 / 2.0, H as f64 / 2.0 - 0.5);
+    let mut put = |x: f64, y: f64, ch: char| {
+        let (col, row) = ((cx + x * 2.0).round(), (cy - y).round()); // cells are about twice as tall as wide
+        if (0.0..W as f64).contains(&col) && (0.0..H as f64).contains(&row) {
+            grid[row as usize][col as usize] = ch;
+        }
+    };
+
+    // Orbit plane, then the disc, the spin axis and the prime meridian on the equator
+    for k in 0..W {
+        put((k as f64 - cx) / 2.0, 0.0, '┄');
+    }
+    let radius = 1.6;
+    for row in 0..H {
+        for col in 0..W {
+            let (x, y) = ((col as f64 - cx) / 2.0, cy - row as f64);
+            if x.hypot(y) <= radius {
+                put(x, y, '░');
+            }
+        }
+    }
+    let (s, c) = tilt.to_radians().sin_cos();
+    for k in -13..=13 {
+        let t = k as f64 * 0.25;
+        if t.abs() > radius - 0.3 {
+            put(t * s, t * c, '·');
+        }
+    }
+    put(3.4 * s, 3.4 * c, 'N');
+    let (sw, cw) = w_deg.to_radians().sin_cos();
+    put(radius * cw * c, -radius * cw * s, if sw >= 0.0 { '●' } else { '○' });
+
+    let color = meta_by_id(b.id).map_or(Color::Reset, |m| body_color(data, view, m));
+    let mut lines: Vec<Line> = grid
+        .into_iter()
+        .map(|row| Line::styled(row.into_iter().collect::<String>(), Style::default().fg(color)))
+        .collect();
+    lines.push(Line::from(format!("tilt {:.1}°  W {:.0}°", tilt, w_deg)));
+    lines.push(Line::from(format!("day {:+.2} h", model.period_hours())));
+
+    let rect = Rect {
+        x: area.x + 1,
+        y: area.y + area.height - (H as u16 + 4) - 1,
+        width: W as u16 + 2,
+        height: H as u16 + 4,
+    };
+    f.render_widget(Clear, rect);
+    f.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(format!("{} spin", b.name))),
+        rect,
+    );
+}
+
+/// Modal overlays drawn over whichever screen is showing; `map` is where the map is, if shown.
+fn render_overlays(f: &mut Frame, data: &DataState, view: &ViewState, map: Option<Rect>) {
+    if let Some(draft) = &view.note {
+        render_note_form(f, f.area(), draft);
+    }
+    if let Some(query) = &view.find {
+        render_find(f, f.area(), data, view, query);
+    }
+    if let Some(name) = &view.marker {
+        render_marker_prompt(f, f.area(), data, name);
+    }
+    if let Some((p, name)) = &view.pin {
+        render_pin_prompt(f, f.area(), *p, name);
+    }
+    if let Some(picker) = &view.picker {
+        render_date_picker(f, map.unwrap_or(f.area()), picker);
+    }
+    if let Some(msg) = &data.popup {
+        render_popup(f, f.area(), msg);
+    }
+}
+
+/// One-line find-by-name prompt under the header, showing the body Enter would select.
+fn render_find(f: &mut Frame, area: Rect, data: &DataState, view: &ViewState, query: &str) {
+    let w = 48.min(area.width);
+    let rect = Rect { x: area.x + (area.width - w) / 2, y: area.y + 3.min(area.height), width: w, height: 3.min(area.height) };
+    let found = match names::find_body(&data.bodies, query).map(|i| data.bodies[i].name) {
+        Some(name) => Span::styled(format!("  → {}", display_name(name, &view.language)), Style::default().fg(Color::Green)),
+        None if query.is_empty() => Span::raw(""),
+        None => Span::styled("  no match", Style::default().fg(Color::DarkGray)),
+    };
+    let prompt = Paragraph::new(Line::from(vec![Span::raw(format!("{}▏", query)), found])).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title("Find body (Enter select, Esc cancel)"),
+    );
+    f.render_widget(Clear, rect);
+    f.render_widget(prompt, rect);
+}
+
+/// One-line prompt naming a timeline marker, at the epoch on screen.
+fn render_marker_prompt(f: &mut Frame, area: Rect, data: &DataState, name: &str) {
+    let w = 48.min(area.width);
+    let rect = Rect { x: area.x + (area.width - w) / 2, y: area.y + 3.min(area.height), width: w, height: 3.min(area.height) };
+    let at = events::epoch(data).unwrap_or_else(Utc::now);
+    let prompt = Paragraph::new(format!("{}▏", name)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!("Marker at {} (Enter drop, Esc cancel)", at.format("%Y-%m-%d %H:%M"))),
+    );
+    f.render_widget(Clear, rect);
+    f.render_widget(prompt, rect);
+}
+
+/// One-line prompt naming a map pin, at the point it will mark.
+fn render_pin_prompt(f: &mut Frame, area: Rect, p: Vec3, name: &str) {
+    let w = 48.min(area.width);
+    let rect = Rect { x: area.x + (area.width - w) / 2, y: area.y + 3.min(area.height), width: w, height: 3.min(area.height) };
+    let prompt = Paragraph::new(format!("{}▏", name)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!("Pin at {:+.3} {:+.3} AU (Enter drop, Esc cancel)", p.x, p.y)),
+    );
+    f.render_widget(Clear, rect);
+    f.render_widget(prompt, rect);
+}
+
+/// The date picker as a popup centred over `area` (the map, when there is one): the fields with
+/// the selected one highlighted, and the picked month as a calendar.
+fn render_date_picker(f: &mut Frame, area: Rect, picker: &DatePicker) {
+    let weeks = picker.month_grid();
+    let (w, h) = (36.min(area.width), (weeks.len() as u16 + 8).min(area.height));
+    let rect = Rect { x: area.x + (area.width - w) / 2, y: area.y + (area.height - h) / 2, width: w, height: h };
+
+    let selected = Style::default().add_modifier(Modifier::REVERSED);
+    let values = [
+        format!("{:04}", picker.utc.year()),
+        format!("{:02}", picker.utc.month()),
+        format!("{:02}", picker.utc.day()),
+        format!("{:02}", picker.utc.hour()),
+        format!("{:02}", picker.utc.minute()),
+    ];
+    let separators = ["-", "-", "  ", ":", ""];
+    let mut fields = vec![Span::raw("  ")];
+    for (i, (value, sep)) in values.into_iter().zip(separators).enumerate() {
+        fields.push(Span::styled(value, if i == picker.field { selected } else { Style::default() }));
+        fields.push(Span::raw(sep));
+    }
+    fields.push(Span::styled(format!("  {}", FIELDS[picker.field]), Style::default().fg(Color::DarkGray)));
+
+    let mut lines = vec![Line::from(fields), Line::raw(""), Line::raw(format!("  {}", picker.utc.format("%B %Y"))), Line::raw("  Mo Tu We Th Fr Sa Su")];
+    for week in weeks {
+        let mut spans = vec![Span::raw("  ")];
+        for day in week {
+            let style = if day == Some(picker.utc.day()) { selected } else { Style::default() };
+            spans.push(Span::styled(day.map_or("  ".into(), |d| format!("{:>2}", d)), style));
+            spans.push(Span::raw(" "));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::raw(""));
+    let hint = Style::default().fg(Color::DarkGray);
+    lines.push(Line::styled("←/→ field  ↑/↓ ±1  PgUp/PgDn ±10", hint));
+    lines.push(Line::styled("Enter ok  n now  Esc cancel", hint));
+
+    let popup = Paragraph::new(lines).block(
+        Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Cyan)).title(picker.purpose.title()),
+    );
+    f.render_widget(Clear, rect);
+    f.render_widget(popup, rect);
+}
+
+fn render_note_form(f: &mut Frame, area: Rect, draft: &NoteDraft) {
+    let w = (area.width * 3 / 5).max(30).min(area.width);
+    let h = (NOTE_FIELDS.len() as u16 + 3).min(area.height);
+    let rect = Rect { x: area.x + (area.width - w) / 2, y: area.y + (area.height - h) / 2, width: w, height: h };
+
+    let mut lines: Vec<Line> = NOTE_FIELDS
+        .iter()
+        .zip(&draft.values)
+        .enumerate()
+        .map(|(i, (label, value))| {
+            let style = if i == draft.field { Style::default().add_modifier(Modifier::REVERSED) } else { Style::default() };
+            let cursor = if i == draft.field { "▏" } else { "" };
+            Line::from(vec![Span::raw(format!("{:<11}", label)), Span::styled(format!("{}{}", value, cursor), style)])
+        })
+        .collect();
+    lines.push(Line::from(Span::styled("Tab next field, Enter save, Esc cancel", Style::default().fg(Color::DarkGray))));
+
+    let form = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan))
+            .title(format!("Observation note: {}", draft.body)),
+    );
+    f.render_widget(Clear, rect);
+    f.render_widget(form, rect);
+}
+
+/// Eccentricity, obliquity and climatic precession over ±100 kyr, one chart each.
+fn render_milankovitch(f: &mut Frame, area: Rect, data: &DataState) {
+    let Some(samples) = &data.milankovitch else {
+        let p = Paragraph::new("Computing orbital forcing series…")
+            .block(Block::default().borders(Borders::ALL).title("Milankovitch cycles"));
+        f.render_widget(p, area);
+        return;
+    };
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Ratio(1, 3), Constraint::Ratio(1, 3), Constraint::Ratio(1, 3)])
+        .split(area);
+
+    let now = samples.iter().min_by(|a, b| a.kyr.abs().total_cmp(&b.kyr.abs()));
+    type Series = fn(&OrbitalForcing) -> f64;
+    let charts: [(&str, Series, Color); 3] = [
+        ("Eccentricity", |s| s.eccentricity, Color::Yellow),
+        ("Obliquity (°)", |s| s.obliquity_deg, Color::Cyan),
+        ("Climatic precession e·sin ϖ", |s| s.precession_index, Color::Magenta),
+    ];
+
+    for (i, (title, value, color)) in charts.iter().enumerate() {
+        let points: Vec<(f64, f64)> = samples.iter().map(|s| (s.kyr, value(s))).collect();
+        let (lo, hi) = points.iter().fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &(_, y)| (lo.min(y), hi.max(y)));
+        let pad = (hi - lo).max(1e-9) * 0.05;
+        let (lo, hi) = (lo - pad, hi + pad);
+        let marker = [(0.0, lo), (0.0, hi)];
+
+        let current = match now {
+            Some(s) if i == 2 => format!(" — now {:+.4} (ϖ {:.0}°)", value(s), s.perihelion_deg),
+            Some(s) => format!(" — now {:.4}", value(s)),
+            None => String::new(),
+        };
+        let chart = Chart::new(vec![
+            Dataset::default().marker(Marker::Braille).graph_type(GraphType::Line).style(Style::default().fg(*color)).data(&points),
+            Dataset::default().marker(Marker::Dot).graph_type(GraphType::Line).style(Style::default().fg(Color::DarkGray)).data(&marker),
+        ])
+        .block(Block::default().borders(Borders::ALL).title(format!("{}{}", title, current)))
+        .x_axis(
+            Axis::default()
+                .bounds([-100.0, 100.0])
+                .labels(["-100 kyr", "now", "+100 kyr"])
+                .style(Style::default().fg(Color::DarkGray)),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([lo, hi])
+                .labels([format!("{:.3}", lo), format!("{:.3}", hi)])
+                .style(Style::default().fg(Color::DarkGray)),
+        );
+        f.render_widget(chart, rows[i]);
+    }
+}
+
+fn render_popup(f: &mut Frame, area: Rect, msg: &str) {
+    let w = (area.width * 3 / 5).max(20).min(area.width);
+    let h = (area.height / 3).max(5).min(area.height);
+    let rect = Rect {
+        x: area.x + (area.width - w) / 2,
+        y: area.y + (area.height - h) / 2,
+        width: w,
+        height: h,
+    };
+    let popup = Paragraph::new(msg.to_string())
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Red))
+                .title("Config error (Esc to dismiss)"),
+        );
+    f.render_widget(Clear, rect);
+    f.render_widget(popup, rect);
+}
+
+/// How long a body stays highlighted after fresh data for it arrives.
+const PULSE: Duration = Duration::from_millis(1500);
+
+/// Update pulse: `Some(true)` in its bright first third, `Some(false)` while fading, `None`
+/// once over, and always on e-ink, which can't animate.
+fn pulse(b: &BodyState, view: &ViewState) -> Option<bool> {
+    if view.display == DisplayProfile::Eink {
+        return None;
+    }
+    let age = b.updated?.elapsed();
+    (age < PULSE).then_some(age < PULSE / 3)
+}
+
+/// Map panel title, noting the coloring mode unless it is the default.
+fn map_title(title: &str, view: &ViewState) -> String {
+    let p = view.pan;
+    let title = if p == Vec3::ZERO { title.to_string() } else { format!("{}, panned {:+.2} {:+.2} AU (Home re-centres)", title, p.x, p.y) };
+    match view.color_by {
+        ColorBy::Body => title,
+        c => format!("{} — colored by {} (c cycles)", title, c.label()),
+    }
+}
+
+/// Each built-in body's own color, used when coloring by body.
+const BODY_COLORS: &[(BodyId, Color)] = &[
+    (BodyId::SUN, Color::Yellow),
+    (BodyId(199), Color::LightMagenta),
+    (BodyId(299), Color::LightYellow),
+    (BodyId::EARTH, Color::LightBlue),
+    (BodyId(499), Color::Red),
+    (BodyId(599), Color::LightRed),
+    (BodyId(699), Color::LightYellow),
+    (BodyId(799), Color::Cyan),
+    (BodyId(899), Color::Blue),
+    (BodyId(2000001), Color::Gray),
+    (BodyId(999), Color::LightRed),
+    (BodyId(2136108), Color::White),
+    (BodyId(2136472), Color::Magenta),
+    (BodyId(2136199), Color::Gray),
+    (BodyId(301), Color::White),
+    (BodyId(501), Color::Yellow),
+    (BodyId(502), Color::White),
+    (BodyId(503), Color::Gray),
+    (BodyId(504), Color::Gray),
+    (BodyId(606), Color::Yellow),
+    (BodyId(801), Color::LightCyan),
+    (BodyId(-31), Color::LightGreen),
+    (BodyId(-32), Color::LightGreen),
+    (BodyId(-98), Color::LightGreen),
+    (BodyId(-170), Color::LightGreen),
+    (BodyId(-96), Color::LightGreen),
+];
+
+fn body_color(data: &DataState, view: &ViewState, m: &BodyMeta) -> Color {
+    if data.config.theme == Theme::Mono {
+        return Color::Reset;
+    }
+    let position = |id: BodyId| data.bodies.iter().find(|b| b.id == id).and_then(|b| b.pos_au);
+    match view.color_by {
+        ColorBy::Body => BODY_COLORS
+            .iter()
+            .find(|(id, _)| *id == m.id)
+            .map(|&(_, c)| c)
+            .or_else(|| extra_bodies().into_iter().find(|e| e.meta.id == m.id)?.color?.parse().ok())
+            .unwrap_or(Color::Reset),
+        ColorBy::Class => match m.class {
+            BodyClass::Star => Color::Yellow,
+            BodyClass::Terrestrial => Color::LightRed,
+            BodyClass::GasGiant => Color::LightYellow,
+            BodyClass::IceGiant => Color::LightCyan,
+            BodyClass::Dwarf => Color::Gray,
+            BodyClass::Moon => Color::White,
+            BodyClass::SmallBody => Color::Magenta,
+            BodyClass::Spacecraft => Color::LightGreen,
+        },
+        ColorBy::Distance => match position(m.id).map(|p| p.norm()) {
+            Some(r) if r < 1.2 => Color::LightRed,
+            Some(r) if r < 4.0 => Color::Yellow,
+            Some(r) if r < 15.0 => Color::Green,
+            Some(_) => Color::LightBlue,
+            None => Color::DarkGray,
+        },
+        ColorBy::Magnitude => {
+            let mag = position(m.id)
+                .zip(position(BodyId::EARTH))
+                .map(|(p, earth)| (p.norm(), (p - earth).norm()))
+                .filter(|&(_, delta)| delta > 0.0)
+                .map(|(r, delta)| apparent_magnitude(m, r, delta))
+                .filter(|v| v.is_finite()); // spacecraft, extra bodies without `abs_mag`
+            match mag {
+                Some(v) if v < -2.0 => Color::White,
+                Some(v) if v < 2.0 => Color::Yellow,
+                Some(v) if v < 6.0 => Color::Green, // naked eye
+                Some(_) => Color::Blue,
+                None => Color::DarkGray,
+            }
+        }
+    }
+}
+
+/// The Sun's position and velocity in the shown frame, once the updater has them for the
+/// barycentric origin; body states are heliocentric, so this is what positions shift by.
+fn sun_barycentric(data: &DataState) -> Option<(Vec3, Vec3)> {
+    data.sun_barycentric.filter(|_| data.config.origin == Origin::Barycentric)
+}
+
+/// Column widths of the vectors table. Body, R and the distance from Earth always
+/// show; a pane narrower than all of them leaves out km/s, and then X, Y and Z.
+const TABLE_ICON_W: u16 = 2;
+const TABLE_BODY_W: u16 = 10;
+const TABLE_XYZ_W: u16 = 9;
+const TABLE_R_W: u16 = 8;
+const TABLE_SPEED_W: u16 = 5;
+const TABLE_EARTH_W: u16 = 17;
+
+/// Which optional columns fit a table `inner` cells wide, as (X/Y/Z, km/s), with one
+/// cell between columns.
+fn table_columns(inner: u16) -> (bool, bool) {
+    let base = TABLE_ICON_W + TABLE_BODY_W + TABLE_R_W + TABLE_EARTH_W + 3;
+    let speed = inner > base + TABLE_SPEED_W;
+    let xyz = speed && inner > base + TABLE_SPEED_W + 3 * (TABLE_XYZ_W + 1);
+    (xyz, speed)
+}
+
+fn render_table(f: &mut Frame, area: Rect, data: &DataState, view: &ViewState) {
+    let (show_xyz, show_speed) = table_columns(area.width.saturating_sub(2));
+    let origin = view.relative_to.and_then(|id| data.body(id));
+    let origin_pos = origin.map(|o| o.pos_au);
+    let (sun_pos, sun_vel) = sun_barycentric(data).unwrap_or((Vec3::ZERO, Vec3::ZERO));
+    let earth = data.bodies.iter().find(|b| b.id == BodyId::EARTH).and_then(|e| e.pos_au);
+
+    let rows = data.bodies.iter().map(|b| {
+        let icon_cell = if let Some(m) = meta_by_id(b.id) {
             Cell::from(Span::styled(
-                icon_for(m, state.use_unicode_icons).to_string(),
-                Style::default().fg(m.color),
+                icon_for(m, view.use_unicode_icons).to_string(),
+                Style::default().fg(body_color(data, view, m)),
             ))
         } else {
             Cell::from("?")
         };
 
-        let (x, y, z, r) = if let Some(v) = b.pos_au {
+        let pos = match origin_pos {
+            Some(Some(o)) => b.pos_au.map(|p| p - o),
+            Some(None) => None, // origin not fetched yet
+            None => b.pos_au.map(|p| p + sun_pos),
+        };
+        // Apparent: as the origin would see it (the Sun or barycentre is taken as at rest)
+        let origin_vel = origin.map_or(Some(Vec3::ZERO - sun_vel), |o| o.vel_au_d);
+        let pos = match (pos, b.vel_au_d, origin_vel) {
+            (Some(d), Some(v), Some(w)) if view.apparent => Some(apparent_offset(d, v, w)),
+            (pos, ..) => pos,
+        };
+
+        let speed = match (b.vel_au_d, origin_vel) {
+            (Some(v), Some(w)) => format!("{:.2}", au_per_day_to_km_s((v - w).norm())),
+            _ => "—".into(),
+        };
+
+        let (x, y, z, r) = if let Some(v) = pos {
             let r = (v.x * v.x + v.y * v.y).sqrt();
             (
-                format!("{:+.6}", v.x),
-                format!("{:+.6}", v.y),
-                format!("{:+.6}", v.z),
-                format!("{:.6}", r),
+                format!("{:+.4}", v.x),
+                format!("{:+.4}", v.y),
+                format!("{:+.4}", v.z),
+                format!("{:.4}", r),
             )
         } else {
             ("—".into(), "—".into(), "—".into(), "—".into())
         };
 
+        // Geometric distance from Earth's centre, whatever the table is relative to
+        let from_earth = match (b.pos_au, earth) {
+            (Some(p), Some(e)) if b.id != BodyId::EARTH => {
+                let d = (p - e).norm();
+                format!("{:.3} / {}", d, format_light_time(light_time_s(d)))
+            }
+            _ => "—".into(),
+        };
+
+        let mut cells = vec![icon_cell, Cell::from(display_name(b.name, &view.language))];
+        if show_xyz {
+            cells.extend([Cell::from(x), Cell::from(y), Cell::from(z)]);
+        }
+        cells.push(Cell::from(r));
+        if show_speed {
+            cells.push(Cell::from(speed));
+        }
+        cells.push(Cell::from(from_earth));
+        Row::new(cells).style(row_style(data, b, view))
+    });
+
+    let (mode, other) = if view.apparent { ("apparent", "geometric") } else { ("geometric", "apparent") };
+    let title = match origin {
+        Some(o) => format!("AU from {}, {} (a: {})", o.name, mode, other),
+        None if sun_barycentric(data).is_some() => format!("Barycentric AU, {} (a: {})", mode, other),
+        None => format!("Heliocentric AU, {} (a: {})", mode, other),
+    };
+
+    let mut header = vec!["", "Body"];
+    let mut widths = vec![Constraint::Length(TABLE_ICON_W), Constraint::Length(TABLE_BODY_W)];
+    if show_xyz {
+        header.extend(["X", "Y", "Z"]);
+        widths.extend([Constraint::Length(TABLE_XYZ_W); 3]);
+    }
+    header.push("R");
+    widths.push(Constraint::Length(TABLE_R_W));
+    if show_speed {
+        header.push("km/s");
+        widths.push(Constraint::Length(TABLE_SPEED_W));
+    }
+    header.push("From Earth (AU)");
+    widths.push(Constraint::Length(TABLE_EARTH_W));
+
+    let table = Table::new(rows, widths)
+        .header(Row::new(header).style(Style::default()))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(Block::default().borders(Borders::ALL).title(title));
+
+    let mut table_state = TableState::default().with_selected(Some(view.selected));
+    f.render_stateful_widget(table, area, &mut table_state);
+}
+
+/// Whether the body takes part in the event jumped to from the events panel.
+fn in_goto(b: &BodyState, view: &ViewState) -> bool {
+    view.goto.as_ref().is_some_and(|g| g.bodies.contains(&b.name))
+}
+
+/// One-way light time as "15m 08s", "4h 11m" or, for the Moon, "1.3s".
+fn format_light_time(secs: f64) -> String {
+    let whole = secs.round() as u64;
+    match whole {
+        _ if secs < 60.0 => format!("{:.1}s", secs),
+        0..3600 => format!("{}m {:02}s", whole / 60, whole % 60),
+        _ => format!("{}h {:02}m", whole / 3600, whole % 3600 / 60),
+    }
+}
+
+/// Table rows flash while fresh data arrives and are dimmed once it is older than
+/// `max_data_age_hours`; the bodies of an event jumped to are underlined.
+fn row_style(data: &DataState, b: &BodyState, view: &ViewState) -> Style {
+    if in_goto(b, view) {
+        return Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+    }
+    match pulse(b, view) {
+        Some(true) => Style::default().fg(Color::White).add_modifier(Modifier::BOLD),
+        Some(false) => Style::default().add_modifier(Modifier::BOLD),
+        None if data.config.is_stale(b, data.clock()) => Style::default().fg(Color::DarkGray),
+        None => Style::default(),
+    }
+}
+
+/// Heliocentric and geocentric vectors of every body in one row (`layout = "compare"`). The
+/// geocentric ones are taken from Earth's vectors of the same refresh, which is what a
+/// Horizons query centred on Earth returns for geometric states.
+fn render_compare_table(f: &mut Frame, area: Rect, data: &DataState, view: &ViewState) {
+    let earth = data.bodies.iter().find(|b| b.id == BodyId::EARTH);
+    // As seen from the origin when apparent (the Sun is taken as at rest)
+    let relative = |b: &BodyState, origin: Option<&BodyState>| -> Option<Vec3> {
+        let (o, w) = origin.map_or((Some(Vec3::ZERO), Some(Vec3::ZERO)), |o| (o.pos_au, o.vel_au_d));
+        let d = b.pos_au? - o?;
+        Some(match (b.vel_au_d, w) {
+            (Some(v), Some(w)) if view.apparent => apparent_offset(d, v, w),
+            _ => d,
+        })
+    };
+    let cells = |d: Option<Vec3>| -> Vec<Cell<'static>> {
+        match d {
+            Some(d) => vec![
+                Cell::from(format!("{:+.6}", d.x)),
+                Cell::from(format!("{:+.6}", d.y)),
+                Cell::from(format!("{:+.6}", d.z)),
+                Cell::from(format!("{:.6}", d.norm())),
+            ],
+            None => vec![Cell::from("—"), Cell::from("—"), Cell::from("—"), Cell::from("—")],
+        }
+    };
+
+    let rows = data.bodies.iter().map(|b| {
+        let icon = match meta_by_id(b.id) {
+            Some(m) => Cell::from(Span::styled(
+                icon_for(m, view.use_unicode_icons).to_string(),
+                Style::default().fg(body_color(data, view, m)),
+            )),
+            None => Cell::from("?"),
+        };
+        let mut row = vec![icon, Cell::from(display_name(b.name, &view.language))];
+        row.extend(cells(relative(b, None)));
+        row.extend(cells(earth.and_then(|e| relative(b, Some(e)))));
+        Row::new(row).style(row_style(data, b, view))
+    });
+
+    let mode = if view.apparent { "apparent" } else { "geometric" };
+    let title = format!("Heliocentric ☉ and geocentric ⊕ vectors (AU, {})", mode);
+    let mut widths = vec![Constraint::Length(3), Constraint::Length(10)];
+    for _ in 0..2 {
+        widths.extend([Constraint::Length(11), Constraint::Length(11), Constraint::Length(11), Constraint::Length(10)]);
+    }
+    let table = Table::new(rows, widths)
+        .header(Row::new(vec!["", "Body", "X☉", "Y☉", "Z☉", "r", "X⊕", "Y⊕", "Z⊕", "Δ"]))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+        .block(Block::default().borders(Borders::ALL).title(title));
+
+    let mut table_state = TableState::default().with_selected(Some(view.selected));
+    f.render_stateful_widget(table, area, &mut table_state);
+}
+
+/// Upcoming events, soonest first, with the time remaining from the last refresh.
+fn render_events(f: &mut Frame, area: Rect, data: &DataState, view: &ViewState) {
+    let now = data
+        .last_update_utc
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&Utc));
+
+    let title = match data.search_progress {
+        Some(f) => format!("Upcoming events — searching {:.0}%", f * 100.0),
+        None => "Upcoming events (↑/↓ select, Enter go to, M mark, Del unmark, S search, x export)".to_string(),
+    };
+
+    let timeline = events::timeline(data);
+    let rows = timeline.iter().map(|entry| {
+        let utc = entry.utc();
+        let until = now.map_or(String::new(), |n| format!("{:.1} d", (utc - n).num_minutes() as f64 / 1440.0));
+        let (symbol, color, name, label) = match entry {
+            TimelineEntry::Event(e) => {
+                let color = meta_by_name(e.body).map_or(Color::Reset, |m| body_color(data, view, m));
+                let bodies = match e.other {
+                    Some(other) => format!("{:.3}/{:.3}", e.body, other),
+                    None => e.body.to_string(),
+                };
+                (e.kind.symbol(), color, bodies, e.kind.label())
+            }
+            TimelineEntry::Marker(m) => ('▼', Color::Yellow, m.name.clone(), "marker"),
+        };
         Row::new(vec![
-            icon_cell,
-            Cell::from(b.name),
-            Cell::from(x),
-            Cell::from(y),
-            Cell::from(z),
-            Cell::from(r),
+            Cell::from(Span::styled(symbol.to_string(), Style::default().fg(color))),
+            Cell::from(name),
+            Cell::from(label),
+            Cell::from(utc.format("%Y-%m-%d").to_string()),
+            Cell::from(until),
         ])
     });
 
     let table = Table::new(
         rows,
         [
-            Constraint::Length(3),
+            Constraint::Length(2),
+            Constraint::Length(9),
+            Constraint::Length(10),
+            Constraint::Length(10),
+            Constraint::Min(8),
+        ],
+    )
+    .header(Row::new(vec!["", "Body", "Event", "UTC", "In"]))
+    .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
+    .block(Block::default().borders(Borders::ALL).title(title));
+
+    let mut table_state = TableState::default().with_selected((!timeline.is_empty()).then_some(view.event_selected));
+    f.render_stateful_widget(table, area, &mut table_state);
+}
+
+/// Each body's RA/Dec and altitude/azimuth from the configured observer, from Horizons
+/// observer tables where fetched (`H`) and computed locally otherwise; bodies below the
+/// horizon are dimmed.
+fn render_sky(f: &mut Frame, area: Rect, data: &DataState, view: &ViewState) {
+    let (Some(o), Some(epoch)) = (data.config.observer, events::epoch(data)) else {
+        let text = "Set [observer] lat_deg and lon_deg in the config to see where to point.";
+        f.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }).block(Block::default().borders(Borders::ALL).title("Sky")), area);
+        return;
+    };
+
+    let rows = data.bodies.iter().filter(|b| b.id != BodyId::EARTH).filter_map(|b| {
+        let (pos, src) = match data.sky.get(&b.id).and_then(|rows| sky_at(rows, epoch)) {
+            Some(p) => (p, "H"),
+            None => (observer::sky_position(data, b, epoch)?, ""),
+        };
+        let color = meta_by_id(b.id).map_or(Color::Reset, |m| body_color(data, view, m));
+        let style = if pos.alt_deg < 0.0 { Style::default().fg(Color::DarkGray) } else { Style::default() };
+        Some(
+            Row::new(vec![
+                Cell::from(Span::styled(display_name(b.name, &view.language), Style::default().fg(color))),
+                Cell::from(format_ra(pos.ra_deg)),
+                Cell::from(format_dec(pos.dec_deg)),
+                Cell::from(format!("{:+.1}°", pos.alt_deg)),
+                Cell::from(format!("{:.1}°{}", pos.az_deg, compass_point(pos.az_deg))),
+                Cell::from(src),
+            ])
+            .style(style),
+        )
+    });
+
+    let title = format!(
+        "Sky from {:.2}°{} {:.2}°{} at {} UTC (apparent, of date)",
+        o.lat_deg.abs(),
+        if o.lat_deg < 0.0 { 'S' } else { 'N' },
+        o.lon_deg.abs(),
+        if o.lon_deg < 0.0 { 'W' } else { 'E' },
+        epoch.format("%H:%M")
+    );
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(8),
+            Constraint::Length(9),
+            Constraint::Length(7),
+            Constraint::Length(6),
+            Constraint::Length(8),
+            Constraint::Length(1),
+        ],
+    )
+    .header(Row::new(vec!["Body", "RA", "Dec", "Alt", "Az", ""]))
+    .block(Block::default().borders(Borders::ALL).title(title));
+
+    f.render_widget(table, area);
+}
+
+/// Upcoming near-Earth object passes from CNEOS, soonest first; those inside the Moon's
+/// distance are highlighted.
+fn render_close_approaches(f: &mut Frame, area: Rect, data: &DataState) {
+    let Some(cad) = &data.close_approaches else {
+        let wait = Paragraph::new("Fetching close approaches from CNEOS…")
+            .block(Block::default().borders(Borders::ALL).title("NEO close approaches"));
+        f.render_widget(wait, area);
+        return;
+    };
+
+    let now = Utc::now();
+    let rows = cad.passes.iter().filter(|p| p.utc >= now).map(|p| {
+        let style = if p.dist_ld < 1.0 { Style::default().fg(Color::Yellow) } else { Style::default() };
+        Row::new(vec![
+            Cell::from(p.designation.clone()),
+            Cell::from(p.utc.format("%Y-%m-%d %H:%M").to_string()),
+            Cell::from(format!("{:.2}", p.dist_ld)),
+            Cell::from(format!("{:.1}", p.v_rel_km_s)),
+            Cell::from(p.abs_mag.map_or(String::new(), |h| format!("{:.1}", h))),
+        ])
+        .style(style)
+    });
+
+    let title = format!(
+        "NEO close approaches ≤ {} LD, next {} days (CNEOS, {} UTC)",
+        cneos::MAX_DIST_LD,
+        cneos::DAYS_AHEAD,
+        cad.fetched.format("%m-%d %H:%M")
+    );
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(12),
+            Constraint::Length(16),
+            Constraint::Length(6),
+            Constraint::Length(6),
+            Constraint::Min(4),
+        ],
+    )
+    .header(Row::new(vec!["Object", "Closest (UTC)", "LD", "km/s", "H"]))
+    .block(Block::default().borders(Borders::ALL).title(title));
+
+    f.render_widget(table, area);
+}
+
+/// The body's state from `config.reference_source` at the epoch of the shown vectors.
+fn reference_state(data: &DataState, b: &BodyState) -> Option<(Vec3, Vec3)> {
+    let epoch = events::epoch(data)?;
+    sources::local_state(data.config.reference_source, b.name, b.id, data.spk.as_deref(), epoch).ok()
+}
+
+/// How far each shown position lies from the reference source's, whose predictions are
+/// marked `×` on the map meanwhile.
+fn render_divergence(f: &mut Frame, area: Rect, data: &DataState, view: &ViewState) {
+    let reference = data.config.reference_source;
+    let rows = data.bodies.iter().filter(|b| b.id != BodyId::SUN).map(|b| {
+        let shown = b.pos_au.zip(b.vel_au_d);
+        let d = shown.zip(reference_state(data, b)).map(|(s, r)| sources::divergence(s, r));
+        let source = b.source.map_or("—", |p| p.source.label());
+        let mut cells = vec![Cell::from(display_name(b.name, &view.language)), Cell::from(source)];
+        match d {
+            Some(d) => cells.extend([
+                Cell::from(format!("{:.0}", d.pos_km)),
+                Cell::from(format!("{:+.0}", d.radial_km)),
+                Cell::from(format!("{:.1}", d.angle_arcsec)),
+                Cell::from(format!("{:.1}", d.vel_m_s)),
+            ]),
+            None => cells.extend(["—", "—", "—", "—"].map(Cell::from)),
+        }
+        Row::new(cells)
+    });
+
+    let table = Table::new(
+        rows,
+        [
             Constraint::Length(10),
             Constraint::Length(14),
-            Constraint::Length(14),
-            Constraint::Length(14),
+            Constraint::Length(11),
+            Constraint::Length(11),
+            Constraint::Length(9),
+            Constraint::Min(7),
+        ],
+    )
+    .header(Row::new(vec!["Body", "Shown from", "Δ km", "Δr km", "Δ″", "Δv m/s"]))
+    .block(Block::default().borders(Borders::ALL).title(format!("Divergence from {} (× on the map)", reference.label())));
+
+    f.render_widget(table, area);
+}
+
+/// Eight-point compass direction of an azimuth from north through east.
+fn compass_point(az_deg: f64) -> &'static str {
+    const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    POINTS[((az_deg / 45.0).round() as usize) % 8]
+}
+
+/// Session alert history, newest first, scrolled back by `alert_scroll` rows.
+fn render_alerts(f: &mut Frame, area: Rect, data: &DataState, view: &ViewState) {
+    let rows = data.alerts.iter().rev().skip(view.alert_scroll).map(|a| {
+        let (mark, color) = match a.level {
+            AlertLevel::Info => ("·", Color::Reset),
+            AlertLevel::Warning => ("⚠", Color::Yellow),
+            AlertLevel::Error => ("✖", Color::Red),
+        };
+        let text = match a.repeats {
+            0 => a.text.clone(),
+            n => format!("{} (×{})", a.text, n + 1),
+        };
+        Row::new(vec![
+            Cell::from(a.utc.format("%H:%M:%S").to_string()),
+            Cell::from(Span::styled(mark, Style::default().fg(color))),
+            Cell::from(text),
+        ])
+    });
+
+    let title = format!(
+        "Alerts ({}{}) — PgUp/PgDn scroll, l: back",
+        data.alerts.len(),
+        if view.alert_scroll > 0 { format!(", {} newer above", view.alert_scroll) } else { String::new() }
+    );
+    let table = Table::new(rows, [Constraint::Length(8), Constraint::Length(1), Constraint::Min(10)])
+        .block(Block::default().borders(Borders::ALL).title(title));
+
+    f.render_widget(table, area);
+}
+
+/// Osculating a, e, i and true anomaly of every body at the epoch of the shown vectors: from
+/// Horizons (`H`) where its elements were fetched, moved on to that epoch, else from the vectors.
+/// Moons' are about their planet.
+fn render_elements(f: &mut Frame, area: Rect, data: &DataState, view: &ViewState) {
+    let days = events::epoch(data).map(days_since_j2000);
+    let rows = data.bodies.iter().filter(|b| b.id != BodyId::SUN).map(|b| {
+        let parent = meta_by_id(b.id).and_then(|m| m.parent).and_then(|p| data.bodies.iter().find(|o| o.id == p));
+        let fetched = days.zip(data.fresh_elements.get(b.name)).filter(|_| parent.is_none());
+        let (el, src) = match fetched.and_then(|(t, row)| row.elements_at(t)) {
+            Some(el) => (Some(el), "H"),
+            None => {
+                let (origin, mu) = match parent {
+                    Some(p) => (p.pos_au.zip(p.vel_au_d), meta_by_id(p.id).map(|m| GM_SUN_AU3_D2 * m.mass_kg / SUN_MASS_KG)),
+                    None => (Some((Vec3::ZERO, Vec3::ZERO)), Some(GM_SUN_AU3_D2)),
+                };
+                let el = b.pos_au.zip(b.vel_au_d).zip(origin).zip(mu).map(|(((p, v), (o, w)), mu)| state_to_elements(p - o, v - w, mu));
+                (el, "")
+            }
+        };
+        let mut cells = vec![Cell::from(display_name(b.name, &view.language))];
+        match el {
+            Some(el) => cells.extend([
+                Cell::from(format!("{:.6}", el.a_au)),
+                Cell::from(format!("{:.5}", el.e)),
+                Cell::from(format!("{:.3}", el.i_deg)),
+                Cell::from(format!("{:.2}", el.true_anomaly_deg.rem_euclid(360.0))),
+                Cell::from(src),
+            ]),
+            None => cells.extend(["—", "—", "—", "—", ""].map(Cell::from)),
+        }
+        Row::new(cells).style(row_style(data, b, view))
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(10),
             Constraint::Length(12),
+            Constraint::Length(9),
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Min(2),
         ],
     )
-    .header(Row::new(vec!["", "Body", "X", "Y", "Z", "R"]).style(Style::default()))
-    .block(Block::default().borders(Borders::ALL).title("Heliocentric vectors (AU)"));
+    .header(Row::new(vec!["Body", "a (AU)", "e", "i (°)", "ν (°)", ""]))
+    .block(Block::default().borders(Borders::ALL).title("Osculating elements (H from Horizons, moons about their planet)"));
 
-    f.render_widget(table, main[0]);
+    f.render_widget(table, area);
+}
 
-    // Map
-    let map = render_map_block(main[1], state);
-    f.render_widget(map, main[1]);
+/// Osculating elements of one body over time, newest first, with the drift across the span.
+fn render_history(f: &mut Frame, area: Rect, data: &DataState) {
+    let Some(h) = &data.history else {
+        let wait = Paragraph::new("Fetching elements from Horizons…")
+            .block(Block::default().borders(Borders::ALL).title("Element history"));
+        f.render_widget(wait, area);
+        return;
+    };
+
+    let j2000 = DateTime::parse_from_rfc3339("2000-01-01T12:00:00Z").unwrap().with_timezone(&Utc);
+    let rows = h.rows.iter().rev().map(|r| {
+        let el = &r.elements;
+        let date = j2000 + chrono::Duration::seconds((r.days_since_j2000() * 86_400.0) as i64);
+        Row::new(vec![
+            Cell::from(date.format("%Y-%m-%d").to_string()),
+            Cell::from(format!("{:.5}", el.a_au)),
+            Cell::from(format!("{:.5}", el.e)),
+            Cell::from(format!("{:.4}°", el.i_deg)),
+            Cell::from(format!("{:.3}°", el.raan_deg)),
+            Cell::from(format!("{:.3}°", el.arg_periapsis_deg)),
+            Cell::from(format!("{:.5}", r.perihelion_au)),
+        ])
+    });
+
+    let drift = match (h.rows.first(), h.rows.last()) {
+        (Some(a), Some(b)) => format!(
+            " — Δa {:+.2e} AU, Δe {:+.2e}, Δω {:+.3}°",
+            b.elements.a_au - a.elements.a_au,
+            b.elements.e - a.elements.e,
+            b.elements.arg_periapsis_deg - a.elements.arg_periapsis_deg,
+        ),
+        _ => String::new(),
+    };
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(10),
+            Constraint::Length(9),
+            Constraint::Length(8),
+            Constraint::Length(9),
+            Constraint::Length(9),
+            Constraint::Length(9),
+            Constraint::Min(8),
+        ],
+    )
+    .header(Row::new(vec!["TDB", "a AU", "e", "i", "Ω", "ω", "q AU"]))
+    .block(Block::default().borders(Borders::ALL).title(format!("Osculating elements: {}{} (h: back)", h.body, drift)));
+
+    f.render_widget(table, area);
+}
+
+/// Live two-body diagnostics per body, flagged yellow where they stray from the J2000
+/// reference orbit (usually a sign of a bad or mis-centered fetch).
+fn render_diagnostics(f: &mut Frame, area: Rect, data: &DataState, view: &ViewState) {
+    let days = data
+        .last_update_utc
+        .as_deref()
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| days_since_j2000(t.with_timezone(&Utc)));
+
+    let rows = data.bodies.iter().filter_map(|b| {
+        let (p, v) = b.pos_au.zip(b.vel_au_d)?;
+        if p.norm() == 0.0 {
+            return None; // the Sun
+        }
+        let d = orbit_diagnostics(p, v);
+
+        let (delta, suspect) = match reference_orbit(b.name) {
+            Some(r) => {
+                let da = (d.a_au - r.a_au) / r.a_au * 100.0;
+                let de = d.e - r.e;
+                let di = d.i_deg - r.i_deg;
+                // Where the orbit says the body should be now: propagated from the last fetched
+                // osculating elements (marked *) when there are some, else the mean orbit
+                let fresh = data.fresh_elements.get(b.name);
+                let dl = days.and_then(|t| {
+                    let predicted = match fresh {
+                        Some(el) => el.position_at(t)?,
+                        None => r.position_at(t),
+                    };
+                    let (live, _) = lon_lat_deg(p);
+                    let (predicted, _) = lon_lat_deg(predicted);
+                    Some((live - predicted + 180.0).rem_euclid(360.0) - 180.0)
+                });
+                let suspect = da.abs() > 1.0
+                    || de.abs() > 0.01
+                    || di.abs() > 0.5
+                    || dl.is_some_and(|dl| dl.abs() > 1.0);
+                let mark = if fresh.is_some() { "*" } else { "" };
+                let dl = dl.map_or("—".to_string(), |dl| format!("{:+.2}°{}", dl, mark));
+                (format!("{:+.2}% {:+.3} {:+.2}° {}", da, de, di, dl), suspect)
+            }
+            None => ("—".to_string(), false),
+        };
+        let style = if suspect { Style::default().fg(Color::Yellow) } else { Style::default() };
+
+        Some(
+            Row::new(vec![
+                Cell::from(display_name(b.name, &view.language)),
+                Cell::from(format!("{:+.4e}", d.energy)),
+                Cell::from(format!("{:.4e}", d.ang_mom)),
+                Cell::from(format!("{:.4}", d.a_au)),
+                Cell::from(format!("{:.4}", d.e)),
+                Cell::from(format!("{:.2}°", d.i_deg)),
+                Cell::from(delta),
+            ])
+            .style(style),
+        )
+    });
+
+    let table = Table::new(
+        rows,
+        [
+            Constraint::Length(8),
+            Constraint::Length(12),
+            Constraint::Length(11),
+            Constraint::Length(8),
+            Constraint::Length(7),
+            Constraint::Length(7),
+            Constraint::Min(26),
+        ],
+    )
+    .header(Row::new(vec!["Body", "ε AU²/d²", "h AU²/d", "a AU", "e", "i", "Δ vs mean orbit a/e/i/λ"]))
+    .block(Block::default().borders(Borders::ALL).title("Orbit diagnostics (d: back to vectors)"));
+
+    f.render_widget(table, area);
+}
+
+fn render_detail(f: &mut Frame, area: Rect, data: &DataState, view: &ViewState) {
+    let Some(b) = data.bodies.get(view.selected) else { return };
+    let fmt_vec = |v: Option<Vec3>| match v {
+        Some(v) => format!("{:+.6} {:+.6} {:+.6}", v.x, v.y, v.z),
+        None => "—".to_string(),
+    };
+
+    let mut lines = vec![
+        Line::from(format!("pos (AU):   {}", fmt_vec(b.pos_au))),
+        Line::from(format!("vel (AU/d): {}", fmt_vec(b.vel_au_d))),
+    ];
+
+    if let Some(src) = b.source {
+        let age_h = (data.clock() - src.epoch).num_minutes() as f64 / 60.0;
+        let stale = data.config.is_stale(b, data.clock());
+        let line = match src.source {
+            Source::Cache if stale => format!("source: {} (data {:.1} h old, stale)", src.source.label(), age_h),
+            Source::Cache => format!("source: {} (data {:.1} h old)", src.source.label(), age_h),
+            _ if stale => format!("source: {} (fetched {:.1} h ago, stale)", src.source.label(), age_h),
+            _ => format!("source: {} (fetched {})", src.source.label(), src.epoch.format("%Y-%m-%d %H:%M UTC")),
+        };
+        lines.push(Line::styled(line, Style::default().fg(Color::DarkGray)));
+    }
+    if let Some(failing) = data.retry_queue.get(b.id) {
+        let wait = (failing.next_try - Utc::now()).num_seconds().max(0);
+        let line = format!("Horizons failed {}× in a row, next try in {} s: {}", failing.failures, wait, failing.last_error);
+        lines.push(Line::styled(line, Style::default().fg(Color::Yellow)));
+    }
+
+    if let Some(p) = b.pos_au.filter(|p| p.norm() > 0.0) {
+        let (lon, lat) = lon_lat_deg(p);
+        let mut spans = vec![Span::raw(format!("helio λ {:.3}° β {:+.3}°", lon, lat))];
+        let epoch = events::epoch(data).unwrap_or_else(Utc::now);
+        if let Some(d) = observer::line_of_sight(data, view, b, epoch).filter(|_| b.id != BodyId::EARTH) {
+            let eq = ecliptic_to_equatorial(d);
+            let (ra, dec) = ra_dec_deg(if view.of_date { true_of_date(eq, days_since_j2000(epoch)) } else { eq });
+            let frame = if view.topocentric && data.config.observer.is_some() { "topo" } else { "geo" };
+            spans.push(Span::raw(format!(
+                " | {} {} RA {} Dec {} Δ {:.6} AU",
+                frame,
+                if view.of_date { "of date" } else { "J2000" },
+                format_ra(ra),
+                format_dec(dec),
+                d.norm()
+            )));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    if let Some((p, v)) = b.pos_au.zip(b.vel_au_d).filter(|(p, _)| p.norm() > 0.0) {
+        let el = state_to_elements(p, v, GM_SUN_AU3_D2);
+        lines.push(Line::from(format!(
+            "a {:.5} AU  e {:.5}  i {:.3}°  Ω {:.2}°  ω {:.2}°  ν {:.2}°",
+            el.a_au, el.e, el.i_deg, el.raan_deg, el.arg_periapsis_deg, el.true_anomaly_deg
+        )));
+
+        let earth = data.bodies.iter().find(|o| o.id == BodyId::EARTH).and_then(|o| o.pos_au.zip(o.vel_au_d));
+        let mut spans = vec![Span::raw(format!("r☉ {:.6} AU", p.norm()))];
+        if let Some((e, _)) = earth.filter(|_| b.id != BodyId::EARTH) {
+            spans.push(Span::raw(format!(" | r⊕ {:.6} AU", (p - e).norm())));
+        }
+        if el.e < 1.0 && el.a_au > 0.0 {
+            let period_days = std::f64::consts::TAU / (GM_SUN_AU3_D2 / el.a_au.powi(3)).sqrt();
+            spans.push(Span::raw(match period_days {
+                d if d < 2.0 * 365.25 => format!(" | period {:.1} d", d),
+                d => format!(" | period {:.2} yr", d / 365.25),
+            }));
+        }
+        let epoch = events::epoch(data).unwrap_or_else(Utc::now);
+        if let Some(at) = earth.and_then(|e| events::next_opposition(e, (p, v), epoch)) {
+            spans.push(Span::raw(format!(" | next opposition {}", at.format("%Y-%m-%d"))));
+        }
+        lines.push(Line::from(spans));
+    }
+
+    if let Some(o) = view.relative_to.and_then(|id| data.body(id)) {
+        let rel_pos = b.pos_au.zip(o.pos_au).map(|(p, q)| p - q);
+        let rel_vel = b.vel_au_d.zip(o.vel_au_d).map(|(v, w)| v - w);
+        lines.push(Line::from(format!("rel {} pos: {}", o.name, fmt_vec(rel_pos))));
+        lines.push(Line::from(format!("rel {} vel: {}", o.name, fmt_vec(rel_vel))));
+        if let Some((p, v)) = rel_pos.zip(rel_vel) {
+            lines.push(Line::from(format!("range {:.6} AU | rate {:+.6} AU/d", p.norm(), range_rate(p, v))));
+        }
+    }
+
+    if let Some((m, p)) = meta_by_id(b.id).zip(b.pos_au) {
+        let r = p.norm();
+        if let Some(r_hill) = hill_radius_au(m, r) {
+            lines.push(Line::from(format!(
+                "Hill sphere: {:.4} AU ({:.2} M km)",
+                r_hill,
+                r_hill * AU_KM / 1e6
+            )));
+            lines.push(Line::from(format!(
+                "Sunlight: {:.1} W/m² | T_eq: {:.0} K | solar tide: {:.2e} m/s²",
+                irradiance_w_m2(r),
+                equilibrium_temp_k(m, r),
+                solar_tidal_accel_m_s2(m, r)
+            )));
+            if let Some(v) = b.vel_au_d {
+                lines.push(speed_comparison_line(m, p, v));
+            }
+        }
+    }
+
+    // Most recent journal notes for this body
+    let notes: Vec<&JournalEntry> = data.journal.iter().rev().filter(|e| e.body == b.name).take(3).collect();
+    for e in &notes {
+        let fields: Vec<&str> =
+            [e.seeing.as_str(), e.instrument.as_str(), e.comments.as_str()].into_iter().filter(|s| !s.is_empty()).collect();
+        lines.push(Line::from(vec![
+            Span::styled(e.utc.format("%Y-%m-%d %H:%M ").to_string(), Style::default().fg(Color::DarkGray)),
+            Span::raw(fields.join(" | ")),
+        ]));
+    }
+
+    let frame = if sun_barycentric(data).is_some() { "barycentric" } else { "heliocentric" };
+    let hint = if view.relative_to.is_some() { format!("r: back to {}", frame) } else { "r: centre table and map on this body".into() };
+    lines.push(Line::from(Span::styled(format!("{}, o: log observation", hint), Style::default().fg(Color::DarkGray))));
+
+    let title = match display_name(b.name, &view.language) {
+        local if local != b.name => format!("{} ({}, {})", local, b.name, b.id),
+        _ => format!("{} ({})", b.name, b.id),
+    };
+    let detail = Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title));
+    f.render_widget(detail, area);
+}
+
+/// Current speed against circular/escape speed at the same distance. By vis-viva, moving
+/// faster than circular means r < a, i.e. the body is on the perihelion side of its orbit.
+fn speed_comparison_line(m: &BodyMeta, p: Vec3, v: Vec3) -> Line<'static> {
+    let r = p.norm();
+    let speed = au_per_day_to_km_s(v.norm());
+    let v_circ = circular_speed_km_s(r);
+    let excess = (speed / v_circ - 1.0) * 100.0;
+    let heading = if range_rate(p, v) < 0.0 { "inbound" } else { "outbound" };
+    let (side, color) = if excess >= 0.0 {
+        ("perihelion side", Color::LightGreen)
+    } else {
+        ("aphelion side", Color::LightBlue)
+    };
+
+    Line::from(vec![
+        Span::raw(format!(
+            "v {:.2} km/s | circ {:.2} | esc☉ {:.2} | surf esc {:.2} | ",
+            speed,
+            v_circ,
+            solar_escape_speed_km_s(r),
+            surface_escape_speed_km_s(m)
+        )),
+        Span::styled(format!("{:+.1}% {}, {}", excess, side, heading), Style::default().fg(color)),
+    ])
+}
+
+fn format_ra(ra_deg: f64) -> String {
+    let total_s = (ra_deg / 15.0 * 3600.0).round() as i64 % 86_400;
+    format!("{:02}h{:02}m{:02}s", total_s / 3600, total_s / 60 % 60, total_s % 60)
+}
+
+fn format_dec(dec_deg: f64) -> String {
+    let total_arcmin = (dec_deg.abs() * 60.0).round() as i64;
+    let sign = if dec_deg < 0.0 { '−' } else { '+' };
+    format!("{}{:02}°{:02}′", sign, total_arcmin / 60, total_arcmin % 60)
+}
+
+/// Rate of change of |p| given relative velocity `v` (positive = receding).
+fn range_rate(p: Vec3, v: Vec3) -> f64 {
+    let r = p.norm();
+    if r == 0.0 { return 0.0; }
+    p.dot(v) / r
 }
 
 #[derive(Clone, Copy)]
@@ -101,18 +1417,420 @@ fn put_pixel(grid: &mut [Vec<Option<Pixel>>], x: i32, y: i32, p: Pixel) {
     }
 }
 
-fn draw_ring(grid: &mut [Vec<Option<Pixel>>], cx: i32, cy: i32, r_pix: f64) {
-    if r_pix < 1.0 { return; }
-    let steps = (r_pix * 6.0).clamp(64.0, 720.0) as i32;
+/// Pixels at or above this priority are bodies, which never hide each other.
+const BODY_PRIORITY: u8 = 10;
+/// Cells a moon must be from its planet on the map to be drawn.
+const MOON_MIN_CELLS: i32 = 2;
+/// Points in the predicted path (`w`), however far ahead it reaches.
+const PATH_SAMPLES: usize = 60;
+
+/// Speed per cell of velocity-arrow length: Mercury's arrow is five cells, Jupiter's one.
+const ARROW_KM_S_PER_CELL: f64 = 10.0;
+const MAX_ARROW_CELLS: i32 = 6;
+
+/// Arrowhead and shaft glyphs for the ecliptic-plane direction of `v` and the cell step they
+/// follow (screen y grows downwards), or `None` for a body at rest such as the Sun.
+fn velocity_arrow(v: Vec3) -> Option<(char, char, i32, i32)> {
+    const ARROWS: [(char, char, i32, i32); 8] = [
+        ('→', '─', 1, 0), ('↗', '╱', 1, -1), ('↑', '│', 0, -1), ('↖', '╲', -1, -1),
+        ('←', '─', -1, 0), ('↙', '╱', -1, 1), ('↓', '│', 0, 1), ('↘', '╲', 1, 1),
+    ];
+    if v.x == 0.0 && v.y == 0.0 {
+        return None;
+    }
+    let octant = (v.y.atan2(v.x) / std::f64::consts::FRAC_PI_4).round() as i32;
+    Some(ARROWS[octant.rem_euclid(8) as usize])
+}
+
+/// An arrow from the body at (x, y) along `v`, one cell per `ARROW_KM_S_PER_CELL` of speed
+/// (at least one, so slow bodies still show their heading). The body, or another's leader,
+/// wins any cell the arrow crosses.
+fn draw_velocity_arrow(grid: &mut [Vec<Option<Pixel>>], x: i32, y: i32, v: Vec3) {
+    let Some((head, shaft, dx, dy)) = velocity_arrow(v) else { return };
+    let cells = (au_per_day_to_km_s(v.norm()) / ARROW_KM_S_PER_CELL).round().clamp(1.0, MAX_ARROW_CELLS as f64) as i32;
+    for k in 1..cells {
+        put_pixel(grid, x + dx * k, y + dy * k, Pixel { ch: shaft, color: Color::DarkGray, priority: BODY_PRIORITY - 3 });
+    }
+    put_pixel(grid, x + dx * cells, y + dy * cells, Pixel { ch: head, color: Color::Gray, priority: BODY_PRIORITY - 2 });
+}
+
+/// Puts a body glyph at (x, y), or, if another body already sits there, two cells away with a
+/// leader mark pointing back (one cell away without a leader when crowded), so that no body
+/// silently vanishes behind another, as Mercury does behind the Sun at wide zoom.
+fn place_body(grid: &mut [Vec<Option<Pixel>>], x: i32, y: i32, p: Pixel) {
+    let free = |grid: &[Vec<Option<Pixel>>], x: i32, y: i32| {
+        if x < 0 || y < 0 { return false; }
+        let cell = grid.get(y as usize).and_then(|row| row.get(x as usize));
+        cell.is_some_and(|c| c.is_none_or(|c| c.priority < BODY_PRIORITY))
+    };
+    if free(grid, x, y) {
+        put_pixel(grid, x, y, p);
+        return;
+    }
+
+    const DIRS: [(i32, i32); 8] = [(1, 0), (-1, 0), (0, -1), (0, 1), (1, -1), (-1, -1), (1, 1), (-1, 1)];
+    for (dx, dy) in DIRS {
+        let (lx, ly) = (x + dx, y + dy);
+        if free(grid, x + 2 * dx, y + 2 * dy) && free(grid, lx, ly) {
+            let leader = match (dx, dy) {
+                (_, 0) => '─',
+                (0, _) => '│',
+                _ if dx * dy < 0 => '╱',
+                _ => '╲',
+            };
+            grid[ly as usize][lx as usize] = Some(Pixel { ch: leader, color: Color::DarkGray, priority: BODY_PRIORITY - 1 });
+            grid[(y + 2 * dy) as usize][(x + 2 * dx) as usize] = Some(p);
+            return;
+        }
+    }
+    if let Some((dx, dy)) = DIRS.into_iter().find(|&(dx, dy)| free(grid, x + dx, y + dy)) {
+        grid[(y + dy) as usize][(x + dx) as usize] = Some(p);
+    }
+}
+
+/// A circle in map coordinates, sampled finely enough to leave no gaps at any zoom (the
+/// canvas `Circle` takes a fixed 360 points, which a zoomed-in orbit outruns).
+struct Ring {
+    x: f64,
+    y: f64,
+    radius: f64,
+    /// The radius in cell widths, which sets the sampling.
+    cells: f64,
+    color: Color,
+    /// Against the grid's glyphs, when drawn on it rather than the canvas.
+    priority: u8,
+}
+
+impl Shape for Ring {
+    fn draw(&self, painter: &mut Painter) {
+        // Several samples per Braille dot around the circumference
+        let steps = (self.cells * std::f64::consts::TAU * 4.0).clamp(64.0, 8000.0) as usize;
+        for i in 0..steps {
+            let t = i as f64 * std::f64::consts::TAU / steps as f64;
+            if let Some((x, y)) = painter.get_point(self.x + self.radius * t.cos(), self.y + self.radius * t.sin()) {
+                painter.paint(x, y, self.color);
+            }
+        }
+    }
+}
+
+/// A ring in whole-cell glyphs, for the map without Braille: every cell near the curve gets a
+/// glyph from a brightness ramp by how close its centre lies to the true ellipse the cell
+/// aspect makes of it, instead of only the cells a rounded sample lands on.
+fn draw_ring(grid: &mut [Vec<Option<Pixel>>], r: &Ring, projection: &MapProjection) {
+    let (rx, ry) = (r.cells, r.cells / projection.aspect);
+    if rx < 1.0 { return; }
+    let (cx, cy) = (projection.cx() as f64 + r.x * projection.scale, projection.cy() as f64 - r.y * projection.scale / projection.aspect);
+    // Distance in cells from a cell centre to the ellipse, to first order
+    let distance = |x: i32, y: i32| {
+        let (dx, dy) = (x as f64 - cx, y as f64 - cy);
+        let rho = (dx / rx).hypot(dy / ry);
+        let gradient = (dx / (rx * rx)).hypot(dy / (ry * ry)) / rho;
+        if gradient > 0.0 { (rho - 1.0) / gradient } else { rx }
+    };
+    let steps = (rx * 6.0).clamp(64.0, 8000.0) as i32;
     for i in 0..steps {
         let t = (i as f64) * std::f64::consts::TAU / (steps as f64);
-        let x = cx + (t.cos() * r_pix).round() as i32;
-        let y = cy - (t.sin() * r_pix).round() as i32;
-        put_pixel(grid, x, y, Pixel { ch: '·', color: Color::DarkGray, priority: 1 });
+        let (px, py) = ((cx + t.cos() * rx).round() as i32, (cy - t.sin() * ry).round() as i32);
+        for (x, y) in (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| (px + dx, py + dy))) {
+            if let Some(p) = ring_pixel(1.0 - distance(x, y).abs(), r.color, r.priority) {
+                put_pixel(grid, x, y, p);
+            }
+        }
+    }
+}
+
+/// Glyph for a ring cell with the given coverage (1 = centred on the curve); `None` if faint.
+fn ring_pixel(coverage: f64, color: Color, priority: u8) -> Option<Pixel> {
+    let (ch, color) = match coverage {
+        c if c >= 0.8 => ('•', color),
+        c if c >= 0.55 => ('·', color),
+        c if c >= 0.3 => ('·', Color::DarkGray),
+        _ => return None,
+    };
+    Some(Pixel { ch, color, priority })
+}
+
+fn put_text(grid: &mut [Vec<Option<Pixel>>], x: i32, y: i32, text: &str, color: Color, priority: u8) {
+    for (i, ch) in text.chars().enumerate() {
+        put_pixel(grid, x + i as i32, y, Pixel { ch, color, priority });
     }
 }
 
-fn render_map_block(area: Rect, state: &AppState) -> Paragraph<'static> {
+fn grid_lines(grid: Vec<Vec<Option<Pixel>>>) -> Vec<Line<'static>> {
+    grid.into_iter()
+        .map(|row| {
+            Line::from(
+                row.into_iter()
+                    .map(|cell| match cell {
+                        Some(p) => Span::styled(p.ch.to_string(), Style::default().fg(p.color)),
+                        None => Span::raw(" "),
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect()
+}
+
+/// Bodies on a single log-scale distance axis, Sun at the left edge. Labels alternate above
+/// and below the line so neighbours (e.g. the inner planets) don't overwrite each other.
+fn render_strip_block(area: Rect, data: &DataState, view: &ViewState) -> Paragraph<'static> {
+    let w = (area.width.saturating_sub(2) as usize).max(1);
+    let h = (area.height.saturating_sub(2) as usize).max(1);
+    let mut grid: Vec<Vec<Option<Pixel>>> = vec![vec![None; w]; h];
+    let axis_y = (h / 2) as i32;
+
+    let placed: Vec<(&BodyMeta, f64)> = data
+        .bodies
+        .iter()
+        .filter_map(|b| Some((meta_by_id(b.id)?, b.pos_au?.norm())))
+        .filter(|(m, r)| *r > 0.0 && m.parent.is_none())
+        .collect();
+    let max_r = placed.iter().map(|(_, r)| *r).fold(1.0, f64::max);
+    let (lo, hi) = (0.2f64.log10(), (max_r * 1.2).log10());
+    let left = 2.0;
+    let span = (w as f64 - left - 1.0).max(1.0);
+    let x_of = |r: f64| (left + (r.log10().clamp(lo, hi) - lo) / (hi - lo) * span).round() as i32;
+
+    put_text(&mut grid, 0, axis_y, &"─".repeat(w), Color::DarkGray, 1);
+    for tick in [1.0f64, 10.0, 100.0] {
+        if tick.log10() <= hi {
+            let x = x_of(tick);
+            put_pixel(&mut grid, x, axis_y, Pixel { ch: '┼', color: Color::DarkGray, priority: 2 });
+            put_text(&mut grid, x, h as i32 - 1, &format!("{} AU", tick), Color::DarkGray, 2);
+        }
+    }
+    if let Some(sun) = meta_by_id(BodyId::SUN) {
+        put_pixel(&mut grid, 0, axis_y, Pixel {
+            ch: icon_for(sun, view.use_unicode_icons),
+            color: body_color(data, view, sun),
+            priority: 10,
+        });
+    }
+
+    for (i, (m, r)) in placed.iter().enumerate() {
+        let x = x_of(*r);
+        let color = body_color(data, view, m);
+        put_pixel(&mut grid, x, axis_y, Pixel { ch: icon_for(m, view.use_unicode_icons), color, priority: 20 });
+        if h >= 5 {
+            let dir = if i % 2 == 0 { -1 } else { 1 };
+            put_pixel(&mut grid, x, axis_y + dir, Pixel { ch: '│', color: Color::DarkGray, priority: 3 });
+            let label = format!("{} {:.2}", m.name, r);
+            let lx = (x - label.chars().count() as i32 / 2).clamp(0, (w as i32 - label.chars().count() as i32).max(0));
+            put_text(&mut grid, lx, axis_y + 2 * dir, &label, color, 5);
+        }
+    }
+
+    Paragraph::new(grid_lines(grid)).block(Block::default().borders(Borders::ALL).title(map_title("Distance from Sun (AU, log scale)", view)))
+}
+
+/// Disc radius for the orrery as a fraction of the ring spacing: grows with the square root of
+/// the physical radius (Jupiter = 0.5), so the giants stand out without swallowing neighbours.
+fn orrery_disc_fraction(radius_km: f64) -> f64 {
+    (radius_km / 69_911.0).sqrt() * 0.5
+}
+
+/// Orbits evenly spaced in order of distance rather than to scale, each body at its true
+/// heliocentric longitude. Cells are `cell_aspect` times as tall as wide, so x is stretched to
+/// keep the rings round.
+fn render_orrery_block(area: Rect, data: &DataState, view: &ViewState) -> Paragraph<'static> {
+    let w = (area.width.saturating_sub(2) as usize).max(1);
+    let h = (area.height.saturating_sub(2) as usize).max(1);
+    let mut grid: Vec<Vec<Option<Pixel>>> = vec![vec![None; w]; h];
+    let (cx, cy) = ((w / 2) as i32, (h / 2) as i32);
+
+    let mut planets: Vec<(&BodyMeta, f64, Vec3)> = data
+        .bodies
+        .iter()
+        .filter_map(|b| {
+            let m = meta_by_id(b.id)?;
+            Some((m, m.orbit_au?, b.pos_au?))
+        })
+        .collect();
+    planets.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    let aspect = data.config.cell_aspect;
+    let outer = ((w as f64 / (2.0 * aspect)).min(h as f64 / 2.0) - 1.0).max(1.0);
+    let spacing = (outer / (planets.len() as f64 + 0.5)).max(1.0);
+    let sun_r = spacing * 0.5;
+    let plot = |grid: &mut [Vec<Option<Pixel>>], dx: f64, dy: f64, p: Pixel| {
+        put_pixel(grid, cx + (dx * aspect).round() as i32, cy - dy.round() as i32, p);
+    };
+    let disc = |grid: &mut [Vec<Option<Pixel>>], x: f64, y: f64, r: f64, p: Pixel| {
+        let n = r.ceil() as i32;
+        let m = (r * aspect).ceil() as i32;
+        for j in -n..=n {
+            for i in -m..=m {
+                let (dx, dy) = (i as f64 / aspect, j as f64);
+                if dx * dx + dy * dy <= r * r {
+                    plot(grid, x + dx, y + dy, p);
+                }
+            }
+        }
+    };
+
+    for (k, _) in planets.iter().enumerate() {
+        let r = sun_r + spacing * (k as f64 + 1.0);
+        let steps = (r * 4.0).clamp(24.0, 360.0) as i32;
+        for i in 0..steps {
+            let t = i as f64 * std::f64::consts::TAU / steps as f64;
+            plot(&mut grid, r * t.cos(), r * t.sin(), Pixel { ch: '·', color: Color::DarkGray, priority: 1 });
+        }
+    }
+
+    if let Some(sun) = meta_by_id(BodyId::SUN) {
+        disc(&mut grid, 0.0, 0.0, sun_r * 0.8, Pixel { ch: '●', color: body_color(data, view, sun), priority: 10 });
+    }
+
+    for (k, (m, _, p)) in planets.iter().enumerate() {
+        let r = sun_r + spacing * (k as f64 + 1.0);
+        let lon = p.y.atan2(p.x);
+        let (x, y) = (r * lon.cos(), r * lon.sin());
+        let color = body_color(data, view, m);
+        let size = orrery_disc_fraction(m.radius_km) * spacing;
+        if size >= 0.5 {
+            disc(&mut grid, x, y, size, Pixel { ch: '●', color, priority: 15 });
+        }
+        plot(&mut grid, x, y, Pixel { ch: icon_for(m, view.use_unicode_icons), color, priority: 20 });
+    }
+
+    Paragraph::new(grid_lines(grid)).block(Block::default().borders(Borders::ALL).title(map_title("Orrery (not to scale)", view)))
+}
+
+/// Arrow on the map border pointing along ecliptic longitude `lon_deg` from the centre,
+/// with a short label just inside it. `stack` moves the label further in, so markers in
+/// similar directions stay readable (the apex and the galactic centre differ mostly in latitude).
+fn draw_edge_marker(grid: &mut [Vec<Option<Pixel>>], lon_deg: f64, aspect: f64, label: &str, stack: i32, color: Color) {
+    let (h, w) = (grid.len() as f64, grid[0].len() as f64);
+    let (cx, cy) = ((w / 2.0).floor(), (h / 2.0).floor());
+    let (dx, dy) = (lon_deg.to_radians().cos(), -lon_deg.to_radians().sin() / aspect);
+    // Walk out until the ray leaves the panel.
+    let tx = if dx.abs() < 1e-9 { f64::INFINITY } else { ((if dx > 0.0 { w - 1.0 } else { 0.0 }) - cx) / dx };
+    let ty = if dy.abs() < 1e-9 { f64::INFINITY } else { ((if dy > 0.0 { h - 1.0 } else { 0.0 }) - cy) / dy };
+    let t = tx.min(ty);
+    let (x, y) = ((cx + dx * t).round() as i32, (cy + dy * t).round() as i32);
+
+    const ARROWS: [char; 8] = ['→', '↗', '↑', '↖', '←', '↙', '↓', '↘'];
+    let arrow = ARROWS[((lon_deg.rem_euclid(360.0) + 22.5) / 45.0) as usize % 8];
+    put_pixel(grid, x, y, Pixel { ch: arrow, color, priority: 4 });
+
+    let len = label.chars().count() as i32;
+    let lx = if dx > 0.0 { x - len - 1 } else { x + 2 };
+    let ly = if y == 0 { 1 + stack } else if y as f64 >= h - 1.0 { y - 1 - stack } else { y + stack };
+    put_text(grid, lx.clamp(0, (w as i32 - len).max(0)), ly, label, color, 4);
+}
+
+/// The orbit map's reference body, once it has a position.
+fn map_origin<'a>(data: &'a DataState, view: &ViewState) -> Option<&'a BodyState> {
+    view.relative_to.and_then(|id| data.body(id)).filter(|o| o.pos_au.is_some())
+}
+
+/// Heliocentric point at the orbit map's centre: the followed body, else the reference body,
+/// else the Sun or the barycentre, moved by any pan.
+fn map_centre(data: &DataState, view: &ViewState) -> Vec3 {
+    if let Some(camera) = view.camera {
+        return camera;
+    }
+    let sun_pos = sun_barycentric(data).map_or(Vec3::ZERO, |(p, _)| p);
+    let followed = view.follow.and_then(|id| data.body(id)).and_then(|b| b.pos_au);
+    followed.or_else(|| map_origin(data, view).and_then(|o| o.pos_au)).unwrap_or(Vec3::ZERO - sun_pos) + view.pan
+}
+
+/// Grid steps per focus orbit radius of the gravity field: about four cells apart on a
+/// 40-row map.
+const FIELD_STEPS_PER_FOCUS: f64 = 5.0;
+
+/// The part of the plane the gravity field overlay should cover for this view.
+pub fn field_window(data: &DataState, view: &ViewState) -> FieldWindow {
+    let (_, focus_au) = FOCUS_LEVELS[view.focus_index];
+    FieldWindow {
+        epoch: data.last_update_utc.clone(),
+        centre: map_centre(data, view),
+        step_au: focus_au.max(0.1) / (view.zoom * FIELD_STEPS_PER_FOCUS),
+    }
+}
+
+/// A limit radius in AU, or in thousand km while it is under a hundredth of one.
+fn format_limit(r_au: f64) -> String {
+    if r_au >= 0.01 { format!("{:.2} AU", r_au) } else { format!("{:.0}k km", r_au * AU_KM / 1e3) }
+}
+
+/// Where the orbit map put the ecliptic plane on screen, so terminal cells can be turned back
+/// into AU.
+#[derive(Debug, Clone, Copy)]
+pub struct MapProjection {
+    left: i32, // terminal column and row of the map's first cell, inside the border
+    top: i32,
+    w: usize,
+    h: usize,
+    centre: Vec3, // heliocentric point at the middle cell
+    scale: f64,   // cell widths per AU
+    aspect: f64,  // row height in cell widths
+}
+
+impl MapProjection {
+    fn cx(&self) -> i32 {
+        (self.w / 2) as i32
+    }
+
+    fn cy(&self) -> i32 {
+        (self.h / 2) as i32
+    }
+
+    /// The map cell (not terminal cell) `p` falls in; may be off the map.
+    fn cell_of(&self, p: Vec3) -> (i32, i32) {
+        (
+            self.cx() + ((p.x - self.centre.x) * self.scale).round() as i32,
+            self.cy() - ((p.y - self.centre.y) * self.scale / self.aspect).round() as i32,
+        )
+    }
+
+    /// The map cell at a terminal position, if the map covers it.
+    fn cell_at(&self, column: u16, row: u16) -> Option<(i32, i32)> {
+        let (x, y) = (column as i32 - self.left, row as i32 - self.top);
+        ((0..self.w as i32).contains(&x) && (0..self.h as i32).contains(&y)).then_some((x, y))
+    }
+
+    /// Heliocentric ecliptic point (z = 0) at the centre of a terminal cell on the map.
+    pub fn point_at(&self, column: u16, row: u16) -> Option<Vec3> {
+        let (x, y) = self.cell_at(column, row)?;
+        Some(Vec3 {
+            x: self.centre.x + (x - self.cx()) as f64 / self.scale,
+            y: self.centre.y - (y - self.cy()) as f64 * self.aspect / self.scale,
+            z: 0.0,
+        })
+    }
+
+    /// Whether `p` is drawn in the map cell at a terminal position.
+    pub fn is_under(&self, p: Vec3, column: u16, row: u16) -> bool {
+        self.cell_at(column, row) == Some(self.cell_of(p))
+    }
+
+    /// Zoom factor and pan shift (AU) that fit the box with corners `a` and `b` to the map.
+    pub fn fit(&self, a: Vec3, b: Vec3) -> (f64, Vec3) {
+        let (dx, dy) = ((a.x - b.x).abs().max(1e-9), (a.y - b.y).abs().max(1e-9));
+        let scale = (self.w as f64 / dx).min(self.h as f64 * self.aspect / dy);
+        let mid = (a + b) * 0.5;
+        (scale / self.scale, Vec3 { x: mid.x - self.centre.x, y: mid.y - self.centre.y, z: 0.0 })
+    }
+}
+
+/// Whether the left button moved far enough between down and up to draw a box rather than
+/// click.
+pub fn is_box_drag(from: (u16, u16), to: (u16, u16)) -> bool {
+    from.0.abs_diff(to.0) >= 2 || from.1.abs_diff(to.1) >= 2
+}
+
+/// A measured distance as "1.5237 AU | 227.9 M km | 12m 40s light".
+fn format_measure(d_au: f64) -> String {
+    let km = d_au * AU_KM;
+    let km = if km >= 1e6 { format!("{:.1} M km", km / 1e6) } else { format!("{:.0} km", km) };
+    format!("{:.4} AU | {} | {} light", d_au, km, format_light_time(light_time_s(d_au)))
+}
+
+/// The orbit map: rings and circles on a canvas whose bounds correct for `cell_aspect`, so
+/// orbits come out round, with bodies, labels and markers laid over it cell by cell.
+fn render_map(f: &mut Frame, area: Rect, data: &DataState, view: &ViewState) -> MapProjection {
     let w = area.width.saturating_sub(2) as usize;
     let h = area.height.saturating_sub(2) as usize;
     let w = w.max(1);
@@ -122,59 +1840,277 @@ fn render_map_block(area: Rect, state: &AppState) -> Paragraph<'static> {
     let cx = (w / 2) as i32;
     let cy = (h / 2) as i32;
 
-    // Base scale: fit selected focus orbit to the panel
-    let (_, focus_au) = FOCUS_LEVELS[state.focus_index];
-    let base_scale = (w.min(h) as f64 * 0.45) / focus_au.max(0.1);
-    let scale = base_scale * state.zoom;
+    // The low-power profile skips the overlays
+    let low_power = data.config.power == Power::Low;
+
+    // Base scale in cell widths per AU: fit selected focus orbit to the panel. A row is
+    // `aspect` cell widths tall, so it spans that much more of the plane.
+    let aspect = data.config.cell_aspect;
+    let (_, focus_au) = FOCUS_LEVELS[view.focus_index];
+    let base_scale = ((w as f64).min(h as f64 * aspect) * 0.45) / focus_au.max(0.1);
+    let scale = base_scale * view.zoom;
+
+    // Everything is drawn relative to the reference body (`r` / `C`), once it has a position,
+    // else to the Sun or the barycentre; the rings stay centred on the Sun wherever that puts it
+    let origin = map_origin(data, view);
+    let barycentric = sun_barycentric(data);
+    let sun_vel = barycentric.map_or(Vec3::ZERO, |(_, v)| v);
+    let offset = map_centre(data, view);
+    let origin_vel = origin.and_then(|o| o.vel_au_d).unwrap_or(Vec3::ZERO - sun_vel);
+    let projection = MapProjection { left: area.x as i32 + 1, top: area.y as i32 + 1, w, h, centre: offset, scale, aspect };
+    let to_screen = |p: Vec3| projection.cell_of(p);
+
+    // Rings and circles go on the canvas in Braille, in plane coordinates about the map centre
+    // (AU), else on the grid as a glyph ramp; everything else is whole-cell glyphs on the grid
+    let mut rings: Vec<Ring> = Vec::new();
+    let ring = |centre: Vec3, radius: f64, color: Color, priority: u8| {
+        Ring { x: centre.x - offset.x, y: centre.y - offset.y, radius, cells: radius * scale, color, priority }
+    };
 
-    // Orbit rings up to focus orbit (so zoom/focus actually changes what you see)
-    for m in BODIES {
+    // Planet orbit rings up to focus orbit (so zoom/focus actually changes what you see), and
+    // the dwarf planets' only while they are shown
+    let planets = [BodyClass::Terrestrial, BodyClass::GasGiant, BodyClass::IceGiant];
+    let dwarfs = bodies_of_class(BodyClass::Dwarf).filter(|m| data.config.is_enabled(m.name));
+    for m in planets.into_iter().flat_map(bodies_of_class).chain(dwarfs) {
         if let Some(r_au) = m.orbit_au {
             if r_au <= focus_au {
-                draw_ring(&mut grid, cx, cy, r_au * scale);
+                rings.push(ring(Vec3::ZERO, r_au, Color::Gray, 1));
             }
         }
     }
 
+    // Node points where each orbit crosses the ecliptic
+    for e in data.events.iter().filter(|e| !e.kind.is_search_result()) {
+        let (x, y) = to_screen(e.pos_au);
+        put_pixel(&mut grid, x, y, Pixel { ch: e.kind.symbol(), color: Color::DarkGray, priority: 3 });
+    }
+
+    for (i, marker) in data.config.sky_markers.iter().enumerate() {
+        let (dir, label) = match marker {
+            SkyMarker::Apex => (solar_apex(), "apex"),
+            SkyMarker::GalacticCenter => (galactic_center(), "gal. centre"),
+        };
+        let (lon, lat) = lon_lat_deg(dir);
+        draw_edge_marker(&mut grid, lon, aspect, &format!("{} β{:+.0}°", label, lat), i as i32, Color::Cyan);
+    }
+
+    // Direction of the net pull across the map, under everything else; the field may still be
+    // for the last window while the new one samples, which only thins or crops it
+    if let Some(field) = data.gravity_field.as_ref().filter(|_| view.gravity_field && !low_power) {
+        for &(p, a) in &field.samples {
+            let Some((head, ..)) = velocity_arrow(a) else { continue };
+            let (x, y) = to_screen(p);
+            put_pixel(&mut grid, x, y, Pixel { ch: head, color: Color::DarkGray, priority: 0 });
+        }
+    }
+
+    // The barycentre, off the Sun's limb once the zoom resolves a few solar radii
+    if let Some((sun_pos, _)) = barycentric {
+        let (x, y) = to_screen(Vec3::ZERO - sun_pos);
+        put_pixel(&mut grid, x, y, Pixel { ch: '+', color: Color::DarkGray, priority: 5 });
+    }
+
     // Sun
-    if let Some(sun) = meta_by_name("Sun") {
-        put_pixel(&mut grid, cx, cy, Pixel {
-            ch: icon_for(sun, state.use_unicode_icons),
-            color: sun.color,
+    if let Some(sun) = meta_by_id(BodyId::SUN) {
+        let (sun_x, sun_y) = to_screen(Vec3::ZERO);
+        put_pixel(&mut grid, sun_x, sun_y, Pixel {
+            ch: icon_for(sun, view.use_unicode_icons),
+            color: body_color(data, view, sun),
             priority: 10,
         });
     }
 
-    // Planets
-    for b in &state.bodies {
+    // Planets and moons
+    for b in &data.bodies {
         let Some(v) = b.pos_au else { continue };
-        let Some(m) = meta_by_name(b.name) else { continue };
+        let Some(m) = meta_by_id(b.id) else { continue };
 
         // If we're focused in (say Jupiter), still draw outer planets if they fall inside view
         // BUT their orbit rings may not be drawn. That's ok.
-        let sx = (v.x * scale).round() as i32;
-        let sy = (v.y * scale).round() as i32;
-        let x = cx + sx;
-        let y = cy - sy;
-
-        put_pixel(&mut grid, x, y, Pixel {
-            ch: icon_for(m, state.use_unicode_icons),
-            color: m.color,
+        let (x, y) = to_screen(v);
+
+        // A moon only appears once the zoom puts it a couple of cells clear of its planet
+        let parent = m.parent.and_then(|p| data.bodies.iter().find(|b| b.id == p)).and_then(|p| p.pos_au);
+        if let Some((px, py)) = parent.map(to_screen) {
+            if (x - px).abs().max((y - py).abs()) < MOON_MIN_CELLS {
+                continue;
+            }
+        }
+
+        // Where it has been, fading with age; only under the live positions it leads up to
+        if !low_power && view.goto.is_none() {
+            let faded = (b.trail.len() as f64 * data.config.trail_fade).round() as usize;
+            for (k, &(_, q)) in b.trail.iter().enumerate() {
+                let (tx, ty) = to_screen(q);
+                let crumb = if k < faded {
+                    Pixel { ch: '·', color: Color::DarkGray, priority: 1 }
+                } else {
+                    Pixel { ch: '∙', color: body_color(data, view, m), priority: 2 }
+                };
+                put_pixel(&mut grid, tx, ty, crumb);
+            }
+        }
+
+        // Hill sphere, once it is big enough to read as a circle
+        if let Some(r_hill) = hill_radius_au(m, v.norm()).filter(|_| !low_power) {
+            let r_pix = r_hill * scale;
+            if r_pix >= 2.0 {
+                rings.push(ring(v, r_hill, Color::DarkGray, 2));
+            }
+        }
+
+        // Sphere of influence and Roche limit of the selected planet, each once it clears the
+        // planet's own cell
+        if view.show_limits && !low_power && data.bodies.get(view.selected).is_some_and(|s| s.id == b.id) {
+            let limits = [
+                soi_radius_au(m, v.norm()).map(|r| (r, "SOI", Color::Cyan)),
+                roche_limit_au(m).map(|r| (r, "Roche", Color::LightRed)),
+            ];
+            for (r_au, label, color) in limits.into_iter().flatten() {
+                let r_pix = r_au * scale;
+                if r_pix >= 2.0 {
+                    rings.push(ring(v, r_au, color, 4));
+                    let text = format!("{} {}", label, format_limit(r_au));
+                    put_text(&mut grid, x - text.chars().count() as i32 / 2, y - (r_pix / aspect).round() as i32 - 1, &text, color, 4);
+                }
+            }
+        }
+
+        // Where the reference source puts the body
+        if view.show_divergence {
+            if let Some((r, _)) = reference_state(data, b) {
+                let (rx, ry) = to_screen(r);
+                put_pixel(&mut grid, rx, ry, Pixel { ch: '×', color: body_color(data, view, m), priority: BODY_PRIORITY - 1 });
+            }
+        }
+
+        // The bodies of an event jumped to, bracketed
+        if in_goto(b, view) {
+            put_pixel(&mut grid, x - 1, y, Pixel { ch: '[', color: Color::Yellow, priority: BODY_PRIORITY - 1 });
+            put_pixel(&mut grid, x + 1, y, Pixel { ch: ']', color: Color::Yellow, priority: BODY_PRIORITY - 1 });
+        }
+
+        // Direction of motion, longer the faster the body moves
+        if view.velocity_arrows && !low_power {
+            if let Some(w) = b.vel_au_d {
+                draw_velocity_arrow(&mut grid, x, y, w - origin_vel);
+            }
+        }
+
+        place_body(&mut grid, x, y, Pixel {
+            ch: icon_for(m, view.use_unicode_icons),
+            color: match pulse(b, view) {
+                Some(true) => Color::White,
+                _ if data.config.is_stale(b, data.clock()) => Color::DarkGray,
+                _ => body_color(data, view, m),
+            },
             priority: 20,
         });
     }
 
-    let mut lines: Vec<Line> = Vec::with_capacity(h);
-    for row in grid {
-        let mut spans = Vec::with_capacity(w);
-        for cell in row {
-            match cell {
-                Some(p) => spans.push(Span::styled(p.ch.to_string(), Style::default().fg(p.color))),
-                None => spans.push(Span::raw(" ")),
+    // Ecliptic coordinates under the mouse, by inverting `to_screen`, in the bottom-right corner
+    if let Some(p) = view.pointer.filter(|_| view.mouse).and_then(|(col, row)| projection.point_at(col, row)) {
+        // Enough decimals to tell neighbouring cells apart
+        let digits = (scale.log10().ceil().max(0.0) as usize).min(6);
+        let text = format!("x {:+.*} y {:+.*} AU | r☉ {:.*} AU", digits, p.x, digits, p.y, digits, p.norm());
+        put_text(&mut grid, w as i32 - text.chars().count() as i32, h as i32 - 1, &text, Color::White, 30);
+    }
+
+    // Where the selected body is heading, dotted from its next sample on
+    if view.predicted_path {
+        if let Some(m) = data.bodies.get(view.selected).and_then(|b| meta_by_id(b.id)) {
+            for q in events::predicted_path(data, view.selected, data.config.path_days, PATH_SAMPLES) {
+                let (x, y) = to_screen(q);
+                put_pixel(&mut grid, x, y, Pixel { ch: '·', color: body_color(data, view, m), priority: 3 });
             }
         }
-        lines.push(Line::from(spans));
     }
 
-    Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Orbits + positions"))
+    // Pins, under the bodies, each labelled to its right
+    for pin in &data.pins {
+        let (x, y) = to_screen(pin.pos_au);
+        let color = pin.color.as_deref().and_then(|c| c.parse().ok()).unwrap_or(Color::Magenta);
+        put_pixel(&mut grid, x, y, Pixel { ch: pin.glyph, color, priority: 12 });
+        put_text(&mut grid, x + 2, y, &pin.name, color, 2);
+    }
+
+    // The box being dragged out to zoom to
+    let dragged = view.drag_from.zip(view.pointer).filter(|&(from, to)| view.mouse && is_box_drag(from, to));
+    if let Some(((x0, y0), (x1, y1))) = dragged.and_then(|(from, to)| projection.cell_at(from.0, from.1).zip(projection.cell_at(to.0, to.1))) {
+        let edge = |ch| Pixel { ch, color: Color::Cyan, priority: 14 };
+        for x in x0.min(x1)..=x0.max(x1) {
+            put_pixel(&mut grid, x, y0, edge('─'));
+            put_pixel(&mut grid, x, y1, edge('─'));
+        }
+        for y in y0.min(y1)..=y0.max(y1) {
+            put_pixel(&mut grid, x0, y, edge('│'));
+            put_pixel(&mut grid, x1, y, edge('│'));
+        }
+    }
+
+    // The measured segment, until Esc, with its length in the bottom-left corner
+    let mut segments = Vec::new();
+    for &p in &view.measure {
+        let (x, y) = to_screen(p);
+        put_pixel(&mut grid, x, y, Pixel { ch: '+', color: Color::Yellow, priority: 15 });
+    }
+    let text = match view.measure.as_slice() {
+        [] => None,
+        [_] => Some("measure: place the second point".to_string()),
+        [a, b, ..] => {
+            segments.push(Segment::new(a.x - offset.x, a.y - offset.y, b.x - offset.x, b.y - offset.y, Color::Yellow));
+            Some(format!("measure: {}", format_measure((*b - *a).norm())))
+        }
+    };
+    if let Some(text) = text {
+        put_text(&mut grid, 0, h as i32 - 1, &text, Color::Yellow, 30);
+    }
+
+    let mut title = match origin {
+        Some(o) => format!("Orbits + positions relative to {}", display_name(o.name, &view.language)),
+        None if barycentric.is_some() => "Orbits + positions about the barycentre".to_string(),
+        None => "Orbits + positions".to_string(),
+    };
+    if let Some(b) = view.follow.and_then(|id| data.body(id)) {
+        title = format!("{}, following {}", title, display_name(b.name, &view.language));
+    }
+    let block = Block::default().borders(Borders::ALL).title(map_title(&title, view));
+    let inner = block.inner(area);
+
+    // Bounds that put the first and last dot of each row and column on the grid's cells, so
+    // rings line up with the glyphs: cell centres sit at whole cell coordinates about (cx, cy)
+    let marker = if view.braille { Marker::Braille } else { Marker::Dot };
+    let (dots_x, dots_y) = if view.braille { (2.0, 4.0) } else { (1.0, 1.0) };
+    let edges = |cells: usize, dots: f64, centre: i32| [-0.5 + 0.5 / dots - centre as f64, cells as f64 - 0.5 - 0.5 / dots - centre as f64];
+    let [left, right] = edges(w, dots_x, cx);
+    let [top, bottom] = edges(h, dots_y, cy);
+    if !view.braille {
+        for r in &rings {
+            draw_ring(&mut grid, r, &projection);
+        }
+    }
+    let canvas = Canvas::default()
+        .block(block)
+        .marker(marker)
+        .x_bounds([left / scale, right / scale])
+        .y_bounds([-bottom * aspect / scale, -top * aspect / scale])
+        .paint(|ctx| {
+            for r in rings.iter().filter(|_| view.braille) {
+                ctx.draw(r);
+            }
+            for s in &segments {
+                ctx.draw(s);
+            }
+        });
+    f.render_widget(canvas, area);
+
+    let buf = f.buffer_mut();
+    for (y, row) in grid.into_iter().enumerate().take(inner.height as usize) {
+        for (x, cell) in row.into_iter().enumerate().take(inner.width as usize) {
+            if let Some(p) = cell {
+                buf[(inner.x + x as u16, inner.y + y as u16)].set_char(p.ch).set_fg(p.color);
+            }
+        }
+    }
+    projection
 }
@@ -0,0 +1,90 @@
+//! Undo/redo of view changes (`u` / `Ctrl-r`), so an accidental zoom, focus jump or epoch
+//! change can be stepped back instead of rebuilt by hand.
+
+use chrono::{DateTime, Utc};
+use std::collections::VecDeque;
+
+use solar_rs::config::MapView;
+use solar_rs::events::GoTo;
+use solar_rs::types::{BodyId, DataState, Vec3, ViewState};
+
+/// Undo steps kept; the oldest are dropped beyond this.
+const MAX_STEPS: usize = 100;
+
+/// The parts of [`ViewState`] that undo restores, with the epoch on screen: an event jumped
+/// to and the time-travel epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ViewSnapshot {
+    zoom: f64,
+    focus_index: usize,
+    map_view: MapView,
+    relative_to: Option<BodyId>,
+    pan: Vec3,
+    follow: Option<BodyId>,
+    goto: Option<GoTo>,
+    sim_epoch: Option<DateTime<Utc>>,
+}
+
+impl ViewSnapshot {
+    pub fn of(v: &ViewState, data: &DataState) -> Self {
+        ViewSnapshot {
+            zoom: v.zoom,
+            focus_index: v.focus_index,
+            map_view: v.map_view,
+            relative_to: v.relative_to,
+            pan: v.pan,
+            follow: v.follow,
+            goto: v.goto.clone(),
+            sim_epoch: data.sim_epoch,
+        }
+    }
+
+    /// A `relative_to` or `follow` body since dropped from the list is let go by the next `ViewState::sync`.
+    /// A restored time-travel epoch is fetched on the next refresh, as when picked.
+    fn apply(self, v: &mut ViewState, data: &mut DataState) {
+        v.zoom = self.zoom;
+        v.focus_index = self.focus_index;
+        v.map_view = self.map_view;
+        v.relative_to = self.relative_to;
+        v.pan = self.pan;
+        v.follow = self.follow;
+        v.goto = self.goto;
+        data.sim_epoch = self.sim_epoch;
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct UndoHistory {
+    undo: VecDeque<ViewSnapshot>,
+    redo: Vec<ViewSnapshot>,
+}
+
+impl UndoHistory {
+    /// Records one command's effect; commands that left the view alone are not steps.
+    pub fn record(&mut self, before: ViewSnapshot, after: ViewSnapshot) {
+        if before == after {
+            return;
+        }
+        if self.undo.len() == MAX_STEPS {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(before);
+        self.redo.clear();
+    }
+
+    /// Restores the view before the last change; false if there is nothing to undo.
+    pub fn undo(&mut self, v: &mut ViewState, data: &mut DataState) -> bool {
+        let Some(previous) = self.undo.pop_back() else { return false };
+        self.redo.push(ViewSnapshot::of(v, data));
+        previous.apply(v, data);
+        true
+    }
+
+    /// Reapplies the last undone change; false if there is nothing to redo.
+    pub fn redo(&mut self, v: &mut ViewState, data: &mut DataState) -> bool {
+        let Some(next) = self.redo.pop() else { return false };
+        self.undo.push_back(ViewSnapshot::of(v, data));
+        next.apply(v, data);
+        true
+    }
+}
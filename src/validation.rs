@@ -0,0 +1,262 @@
+//! Validation of the crate's frame transforms and propagators against published ephemeris
+//! values at fixed epochs. Gated behind `cargo test --features validation`; the inputs are
+//! cached Horizons responses and published event times under `tests/fixtures/`, so no
+//! network access is needed.
+//!
+//! Documented tolerances:
+//! - Horizons fixture round-trip through the CSV parser: exact.
+//! - Sun's astrometric RA/Dec at J2000 from Earth's vector: 0.05°.
+//! - Seasons of 2000 from two-body propagation of the J2000 mean Earth–Moon orbit: 0.02°
+//!   (aberration and precession since J2000 together account for ~0.01°).
+//! - 2020 great conjunction separation from two-body propagation of J2000 mean elements:
+//!   0.5° (mean elements omit the Jupiter–Saturn great inequality).
+//! - The same conjunction from the built-in mean-element ephemeris (`src/ephemeris.rs`):
+//!   0.05° (the fitted rates absorb most of the great inequality).
+//! - Earth from the abridged VSOP87 series (`src/vsop87.rs`) against the Horizons fixture at
+//!   J2000: 1e-5 AU in position, 1e-7 AU/day in velocity.
+//! - The 2020 conjunction from VSOP87: 0.01°.
+//! - Galactic centre (l = b = 0) through the galactic frame rotation: 0.001°.
+//! - Precession J2000 → 2028 (Meeus example 21.b): 0.0005°.
+//! - Greenwich mean sidereal time (Meeus example 12.b): 0.0001°.
+//! - Nutation and mean obliquity against Meeus example 22.a: 0.05″ (IAU 2000B/2006 against
+//!   the IAU 1980 values in the book).
+//! - J2000 → true equator of 2028 (Meeus example 23.a, without aberration): 0.0005°.
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+use crate::ephemeris::state_at;
+use crate::frames::{
+    ecliptic_to_equatorial, galactic_center, lon_lat_deg, mean_obliquity_deg, nutation_deg, precess_from_j2000,
+    ra_dec_deg, true_of_date,
+};
+use crate::horizons::{extract_table_lines, parse_state_from_csv_row};
+use crate::observer::gmst_deg;
+use crate::physics::reference_orbit;
+use crate::types::{HorizonsJson, Vec3};
+use crate::vsop87;
+
+const EARTH_J2000: &str = include_str!("../tests/fixtures/horizons_earth_j2000.json");
+const PUBLISHED_EPOCHS: &str = include_str!("../tests/fixtures/published_epochs.json");
+
+#[derive(Deserialize)]
+struct PublishedEpochs {
+    seasons: Vec<Season>,
+    great_conjunction_2020: Conjunction,
+    sun_at_j2000: RaDec,
+    galactic_center: RaDec,
+    precession_meeus_21b: PrecessionCase,
+    sidereal_time_meeus_12b: SiderealCase,
+    nutation_meeus_22a: NutationCase,
+    true_of_date_meeus_23a: PrecessionCase,
+}
+
+#[derive(Deserialize)]
+struct NutationCase {
+    days_since_j2000: f64,
+    dpsi_arcsec: f64,
+    deps_arcsec: f64,
+    mean_obliquity_arcsec: f64,
+}
+
+#[derive(Deserialize)]
+struct SiderealCase {
+    utc: DateTime<Utc>,
+    gmst_deg: f64,
+}
+
+#[derive(Deserialize)]
+struct PrecessionCase {
+    ra_j2000_deg: f64,
+    dec_j2000_deg: f64,
+    days_since_j2000: f64,
+    ra_deg: f64,
+    dec_deg: f64,
+}
+
+#[derive(Deserialize)]
+struct Season {
+    event: String,
+    utc: DateTime<Utc>,
+    tt_minus_utc_s: f64,
+    earth_lon_deg: f64,
+}
+
+#[derive(Deserialize)]
+struct Conjunction {
+    utc: DateTime<Utc>,
+    tt_minus_utc_s: f64,
+    separation_arcmin: f64,
+}
+
+#[derive(Deserialize)]
+struct RaDec {
+    ra_deg: f64,
+    dec_deg: f64,
+}
+
+fn published() -> PublishedEpochs {
+    serde_json::from_str(PUBLISHED_EPOCHS).expect("published_epochs.json")
+}
+
+fn earth_j2000_state() -> (Vec3, Vec3) {
+    let parsed: HorizonsJson = serde_json::from_str(EARTH_J2000).expect("fixture JSON");
+    let lines = extract_table_lines(&parsed.result).expect("table");
+    parse_state_from_csv_row(lines[0]).expect("state row")
+}
+
+/// J2000.0 (2000-01-01 12:00 TT) as a UTC instant.
+static J2000_UTC: LazyLock<DateTime<Utc>> =
+    LazyLock::new(|| DateTime::parse_from_rfc3339("2000-01-01T11:58:55.816Z").unwrap().with_timezone(&Utc));
+
+/// TT − UTC at J2000.0: 32.184 s plus the 32 leap seconds by then.
+const J2000_TT_MINUS_UTC_S: f64 = 64.184;
+
+/// Days since J2000.0 (2000-01-01 12:00 TT) of `utc`, when TT − UTC is `tt_minus_utc_s`.
+/// UTC differences skip leap seconds, so those added since J2000 are counted on top.
+fn days_since_j2000_tt(utc: DateTime<Utc>, tt_minus_utc_s: f64) -> f64 {
+    (utc - *J2000_UTC).num_milliseconds() as f64 / 86_400_000.0 + (tt_minus_utc_s - J2000_TT_MINUS_UTC_S) / 86_400.0
+}
+
+fn propagated_reference(name: &str, dt_days: f64) -> Vec3 {
+    reference_orbit(name).expect(name).position_at(dt_days)
+}
+
+fn angle_diff_deg(a: f64, b: f64) -> f64 {
+    ((a - b + 180.0).rem_euclid(360.0) - 180.0).abs()
+}
+
+#[test]
+fn horizons_fixture_parses_to_published_state() {
+    let (p, v) = earth_j2000_state();
+    assert_eq!(p.x, -1.771_351_029_694_605e-1);
+    assert_eq!(p.y, 9.672_416_861_070_428e-1);
+    assert_eq!(p.z, -4.092_421_117_973_766e-6);
+    assert_eq!(v.x, -1.720_762_505_701_73e-2);
+    assert_eq!(v.y, -3.158_782_705_778_585e-3);
+    assert_eq!(v.z, 1.050_630_211_376_594e-7);
+}
+
+#[test]
+fn sun_radec_at_j2000() {
+    let expected = published().sun_at_j2000;
+    let (earth, _) = earth_j2000_state();
+    let (ra, dec) = ra_dec_deg(ecliptic_to_equatorial(earth * -1.0));
+
+    assert!(angle_diff_deg(ra, expected.ra_deg) < 0.05, "RA {ra}");
+    assert!((dec - expected.dec_deg).abs() < 0.05, "Dec {dec}");
+}
+
+#[test]
+fn seasons_of_2000_from_mean_earth_orbit() {
+    for s in published().seasons {
+        let dt = days_since_j2000_tt(s.utc, s.tt_minus_utc_s);
+        let (lon, lat) = lon_lat_deg(propagated_reference("Earth", dt));
+
+        assert!(angle_diff_deg(lon, s.earth_lon_deg) < 0.02, "{}: λ {lon}", s.event);
+        assert!(lat.abs() < 0.01, "{}: β {lat}", s.event);
+    }
+}
+
+#[test]
+fn great_conjunction_2020() {
+    let c = published().great_conjunction_2020;
+    let dt = days_since_j2000_tt(c.utc, c.tt_minus_utc_s);
+    let earth = propagated_reference("Earth", dt);
+    let jupiter = propagated_reference("Jupiter", dt) - earth;
+    let saturn = propagated_reference("Saturn", dt) - earth;
+
+    let separation_deg = (jupiter.dot(saturn) / (jupiter.norm() * saturn.norm())).acos().to_degrees();
+    assert!(
+        (separation_deg - c.separation_arcmin / 60.0).abs() < 0.5,
+        "separation {separation_deg}°"
+    );
+}
+
+#[test]
+fn great_conjunction_2020_from_mean_elements() {
+    let c = published().great_conjunction_2020;
+    let at = |name| state_at(name, c.utc).expect(name).0;
+    let earth = at("Earth");
+    let (jupiter, saturn) = (at("Jupiter") - earth, at("Saturn") - earth);
+
+    let separation_deg = (jupiter.dot(saturn) / (jupiter.norm() * saturn.norm())).acos().to_degrees();
+    assert!(
+        (separation_deg - c.separation_arcmin / 60.0).abs() < 0.05,
+        "separation {separation_deg}°"
+    );
+}
+
+#[test]
+fn vsop87_earth_at_j2000() {
+    let (p, v) = earth_j2000_state();
+    let (vp, vv) = vsop87::state_at("Earth", *J2000_UTC).expect("Earth");
+
+    assert!((vp - p).norm() < 1e-5, "position off by {} AU", (vp - p).norm());
+    assert!((vv - v).norm() < 1e-7, "velocity off by {} AU/day", (vv - v).norm());
+}
+
+#[test]
+fn great_conjunction_2020_from_vsop87() {
+    let c = published().great_conjunction_2020;
+    let at = |name| vsop87::state_at(name, c.utc).expect(name).0;
+    let earth = at("Earth");
+    let (jupiter, saturn) = (at("Jupiter") - earth, at("Saturn") - earth);
+
+    let separation_deg = (jupiter.dot(saturn) / (jupiter.norm() * saturn.norm())).acos().to_degrees();
+    assert!(
+        (separation_deg - c.separation_arcmin / 60.0).abs() < 0.01,
+        "separation {separation_deg}°"
+    );
+}
+
+#[test]
+fn galactic_center_icrs() {
+    let expected = published().galactic_center;
+    let (ra, dec) = ra_dec_deg(ecliptic_to_equatorial(galactic_center()));
+
+    assert!(angle_diff_deg(ra, expected.ra_deg) < 0.001, "RA {ra}");
+    assert!((dec - expected.dec_deg).abs() < 0.001, "Dec {dec}");
+}
+
+#[test]
+fn precession_to_2028() {
+    let c = published().precession_meeus_21b;
+    let (ra0, dec0) = (c.ra_j2000_deg.to_radians(), c.dec_j2000_deg.to_radians());
+    let v = Vec3 { x: dec0.cos() * ra0.cos(), y: dec0.cos() * ra0.sin(), z: dec0.sin() };
+    let (ra, dec) = ra_dec_deg(precess_from_j2000(v, c.days_since_j2000));
+
+    assert!(angle_diff_deg(ra, c.ra_deg) < 0.0005, "RA {ra}");
+    assert!((dec - c.dec_deg).abs() < 0.0005, "Dec {dec}");
+}
+
+#[test]
+fn sidereal_time_1987() {
+    let c = published().sidereal_time_meeus_12b;
+    let gmst = gmst_deg(c.utc);
+
+    assert!(angle_diff_deg(gmst, c.gmst_deg) < 0.0001, "GMST {gmst}");
+}
+
+#[test]
+fn nutation_1987() {
+    let c = published().nutation_meeus_22a;
+    let (dpsi, deps) = nutation_deg(c.days_since_j2000);
+    let eps0 = mean_obliquity_deg(c.days_since_j2000) * 3600.0;
+
+    assert!((dpsi * 3600.0 - c.dpsi_arcsec).abs() < 0.05, "Δψ {}″", dpsi * 3600.0);
+    assert!((deps * 3600.0 - c.deps_arcsec).abs() < 0.05, "Δε {}″", deps * 3600.0);
+    assert!((eps0 - c.mean_obliquity_arcsec).abs() < 0.05, "ε₀ {eps0}″");
+}
+
+#[test]
+fn true_of_date_2028() {
+    let c = published().true_of_date_meeus_23a;
+    let (ra0, dec0) = (c.ra_j2000_deg.to_radians(), c.dec_j2000_deg.to_radians());
+    let v = Vec3 { x: dec0.cos() * ra0.cos(), y: dec0.cos() * ra0.sin(), z: dec0.sin() };
+    let (ra, dec) = ra_dec_deg(true_of_date(v, c.days_since_j2000));
+
+    assert!(angle_diff_deg(ra, c.ra_deg) < 0.0005, "RA {ra}");
+    assert!((dec - c.dec_deg).abs() < 0.0005, "Dec {dec}");
+}
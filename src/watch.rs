@@ -0,0 +1,58 @@
+//! Config hot-reload: edits to the config file or the active profile apply while running.
+
+use anyhow::Result;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use solar_rs::config::{apply_config, load_config, ConfigPaths};
+use solar_rs::types::{AlertLevel, DataState};
+
+/// Watches the config file and the profiles directory, applying valid changes to the active
+/// profile live; invalid ones raise a popup and leave the running config untouched. The
+/// returned watcher must be kept alive.
+pub fn watch_config(paths: ConfigPaths, state: Arc<Mutex<DataState>>) -> Result<RecommendedWatcher> {
+    // Watch directories: editors often save by replacing the file, which drops a file watch.
+    let dir = paths.dir();
+    let profiles_dir = paths.profiles_dir();
+
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else { return };
+        if !(event.kind.is_modify() || event.kind.is_create()) {
+            return;
+        }
+
+        let profile = state.lock().unwrap().profile.clone();
+        let relevant = event.paths.iter().any(|p| {
+            let is_profile = profile
+                .as_deref()
+                .is_some_and(|name| p.ends_with(Path::new("profiles").join(format!("{}.toml", name))));
+            is_profile || p.file_name() == paths.main.file_name()
+        });
+        if !relevant {
+            return;
+        }
+
+        let result = load_config(Some(&paths), profile.as_deref());
+        let mut s = state.lock().unwrap();
+        match result {
+            Ok(config) => {
+                apply_config(&mut s, config);
+                s.status = "Config reloaded".into();
+                s.popup = None;
+            }
+            Err(e) => {
+                s.alert(AlertLevel::Error, format!("Config reload: {:#}", e));
+                s.popup = Some(format!("{:#}", e));
+            }
+        }
+    })?;
+
+    watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+    if profiles_dir.is_dir() {
+        watcher.watch(&profiles_dir, RecursiveMode::NonRecursive)?;
+    }
+    Ok(watcher)
+}